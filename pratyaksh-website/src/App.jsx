@@ -98,7 +98,7 @@ export default function App() {
       // Pass selectedPlan to PaymentPage so it knows what to charge
       case 'payment': return <PaymentPage user={user} plan={selectedPlan} onPaymentComplete={() => setPage('wait')} />;
       case 'wait': return <ApprovalWait user={user} onApproved={() => setPage('download')} />;
-      case 'download': return <DownloadScreen setPage={setPage} />; // AccessGuard inside component handles restriction
+      case 'download': return <DownloadScreen setPage={setPage} access={access} />; // AccessGuard inside component handles restriction
       
       case 'tools': return <ToolsPage setPage={setPage} />;
       
@@ -3,7 +3,17 @@ import { Download, ArrowRight, Globe, Smartphone, Lock, ShieldCheck } from 'luci
 import { THEME } from '../data/constants';
 import AccessGuard from '../components/AccessGuard';
 
-export const DownloadScreen = ({ setPage }) => (
+// The admin console stores the backend-signed license.json on the user's access record
+const downloadLicense = (license) => {
+  const url = URL.createObjectURL(new Blob([license], { type: 'application/json' }));
+  const link = document.createElement('a');
+  link.href = url;
+  link.download = 'license.json';
+  link.click();
+  URL.revokeObjectURL(url);
+};
+
+export const DownloadScreen = ({ setPage, access }) => (
   // AccessGuard listens to: artifacts/{appId}/public/data/user_access/{uid}
   // This matches the Rust Admin's write path exactly.
   <AccessGuard onDeny={() => setPage('pricing')}>
@@ -20,7 +30,7 @@ export const DownloadScreen = ({ setPage }) => (
             </div>
             <h1 className="text-6xl font-medium tracking-tighter mb-8 text-[#111111]">Download Center</h1>
             <p className="text-xl text-[#666666] leading-relaxed mb-12">
-              Get the full PratyakshAI suite. Ensure you have your license key ready for activation.<br/><br/>
+              Get the full PratyakshAI suite. Ensure you have your license file ready for activation.<br/><br/>
               Version 6.0.0 (Stable) • Updated Feb 2026
             </p>
             
@@ -43,10 +53,17 @@ export const DownloadScreen = ({ setPage }) => (
 
           <div className="bg-[#111111] p-12 flex flex-col justify-between text-white">
             <div>
-              <h4 className="text-lg font-medium mb-6">Installation Key</h4>
-              <div className="bg-[#222] p-4 rounded font-mono text-[#4FF978] text-sm break-all mb-8 border border-white/10 flex items-center gap-2">
-                <Lock size={14} /> PK-ENT-2026-X9Y2-8842-SECURE
-              </div>
+              <h4 className="text-lg font-medium mb-6">License File</h4>
+              {access?.license ? (
+                <button onClick={() => downloadLicense(access.license)} className="w-full bg-[#222] p-4 rounded font-mono text-[#4FF978] text-sm mb-8 border border-white/10 flex items-center gap-2 hover:border-[#4FF978] transition-colors">
+                  <Lock size={14} /> Download license.json <Download size={14} />
+                </button>
+              ) : (
+                <div className="bg-[#222] p-4 rounded font-mono text-gray-400 text-sm mb-8 border border-white/10 flex items-center gap-2">
+                  <Lock size={14} /> Your license is being issued
+                </div>
+              )}
+              <p className="text-gray-400 text-sm mb-8">Paste its contents into the desktop activation screen. It only opens on the device you registered.</p>
               <h4 className="text-lg font-medium mb-6">System Requirements</h4>
               <ul className="space-y-4 text-gray-400 text-sm">
                 <li className="flex justify-between border-b border-white/10 pb-2"><span>OS</span> <span>Windows 10/11</span></li>
@@ -1,4 +1,4 @@
-import React, { useState } from 'react';
+import React, { useState, useEffect } from 'react';
 import { doc, setDoc, serverTimestamp } from 'firebase/firestore';
 import { db, appId } from '../lib/firebase';
 import { 
@@ -7,21 +7,50 @@ import {
   Wallet, ArrowRightLeft, AlertTriangle, Landmark 
 } from 'lucide-react';
 
+// Backend that issues UPI payment orders (upi:// link, QR and transaction reference)
+const API_URL = import.meta.env.VITE_API_URL || "http://localhost:8080";
+
 export default function Payment({ user, plan, onPaymentComplete }) {
   const [isProcessing, setIsProcessing] = useState(false);
   const [email, setEmail] = useState("");
+  const [deviceId, setDeviceId] = useState("");
   const [error, setError] = useState("");
-  const [selectedUpiApp, setSelectedUpiApp] = useState("PhonePe");
+  const [order, setOrder] = useState(null);
+
+  // The backend hands back this user's unpaid order for the plan, so remounts keep the same reference
+  const planName = plan?.name;
+  useEffect(() => {
+    if (!user?.uid) return;
+    if (!planName) {
+      setError("No plan selected. Please choose one on the pricing page.");
+      return;
+    }
+    let cancelled = false;
+    setOrder(null);
+    fetch(`${API_URL}/api/v1/payments/orders`, {
+      method: 'POST',
+      headers: { 'Content-Type': 'application/json' },
+      body: JSON.stringify({ plan: planName, user_id: user.uid })
+    })
+      .then(async (res) => {
+        const data = await res.json();
+        if (!res.ok) throw new Error(data.detail || res.statusText);
+        if (!cancelled) setOrder(data);
+      })
+      .catch((err) => {
+        console.error("Order creation failed:", err);
+        if (!cancelled) setError("Could not create the payment order. Please reload and retry.");
+      });
+    return () => { cancelled = true; };
+  }, [user?.uid, planName]);
 
   const handleTransaction = async (e) => {
     e.preventDefault();
-    if (!user) return;
+    if (!user || !order) return;
     setIsProcessing(true);
     setError("");
 
     try {
-      const txnId = `TXN-${Math.random().toString(36).substr(2, 9).toUpperCase()}`;
-      
       // RULE 1: Strict Path Compliance
       const paymentRef = doc(db, 'artifacts', appId, 'public', 'data', 'payments', user.uid);
 
@@ -29,11 +58,11 @@ export default function Payment({ user, plan, onPaymentComplete }) {
       await setDoc(paymentRef, {
         userId: user.uid,
         userEmail: email,
-        amount: plan?.price || "₹19,999",
-        plan: plan?.name || "Enterprise Suite",
+        amount: order.amount_display,
+        plan: planName,
         status: "pending",
-        txnId: txnId,
-        device: "Web Client", // Required by Rust model
+        txnId: order.txn_ref, // Same reference the UPI app sent with the payment
+        device: deviceId.trim().toUpperCase(), // Desktop Device ID; the signed license is bound to it
         createdAt: serverTimestamp() // Audit trail (ignored by Rust struct serialization but good for DB)
       });
 
@@ -52,18 +81,6 @@ export default function Payment({ user, plan, onPaymentComplete }) {
     alert("Copied: " + text);
   };
 
-  // Logic to switch images based on selection
-  // Ensure these files exist in public/assets/
-  const getQrImage = (app) => {
-    switch (app) {
-        case "PhonePe": return "https://a-amm.vercel.app/assets/ph.png";
-        case "GPay": return "https://a-amm.vercel.app/assets/gpay.png";
-        case "BHIM": return "https://a-amm.vercel.app/assets/bhim.jpeg";
-        case "WhatsApp Pay": return "https://a-amm.vercel.app/assets/wa.png";
-        default: return "/assets/upi.png";
-    }
-  };
-
   return (
     <div className="max-w-6xl mx-auto mt-8 mb-20 animate-in fade-in slide-in-from-bottom-4 duration-700 font-sans text-[#111111]">
       
@@ -99,24 +116,17 @@ export default function Payment({ user, plan, onPaymentComplete }) {
                   <div>
                     <div className="flex items-center gap-2 mb-3">
                         <span className="text-xs font-bold text-white bg-[#111111] px-2 py-1 rounded">IN</span>
-                        <label className="text-xs text-gray-500 font-bold uppercase tracking-wider">Select App</label>
+                        <label className="text-xs text-gray-500 font-bold uppercase tracking-wider">Payment Reference</label>
                     </div>
-                    <div className="grid grid-cols-2 gap-3">
-                      {['PhonePe', 'GPay', 'BHIM', 'WhatsApp Pay'].map(app => (
-                        <button
-                          key={app}
-                          type="button"
-                          onClick={() => setSelectedUpiApp(app)}
-                          className={`px-4 py-3 rounded-lg text-xs font-bold transition-all border ${
-                            selectedUpiApp === app 
-                              ? 'bg-[#111111] text-white border-[#111111] shadow-lg' 
-                              : 'bg-white text-gray-600 border-gray-200 hover:border-gray-400 hover:text-black'
-                          }`}
-                        >
-                          {app}
+                    <div className="flex items-center gap-3 bg-gray-50 p-3 rounded-lg border border-gray-200">
+                      <code className="flex-1 text-[#111111] font-mono text-sm font-bold">{order ? order.txn_ref : "Generating..."}</code>
+                      {order && (
+                        <button onClick={() => copyToClipboard(order.txn_ref)} className="p-2 hover:bg-gray-200 rounded-md text-gray-500 hover:text-black transition-colors">
+                          <Copy size={16} />
                         </button>
-                      ))}
+                      )}
                     </div>
+                    <div className="text-[10px] text-gray-500 mt-2 ml-1">Keep this reference in the payment remark if you pay manually.</div>
                   </div>
 
                   <div>
@@ -137,17 +147,24 @@ export default function Payment({ user, plan, onPaymentComplete }) {
                {/* QR Display */}
                <div className="flex flex-col items-center justify-center">
                   <div className="w-40 h-40 bg-white p-2 rounded-xl border border-gray-200 shadow-xl relative">
-                    <img 
-                      src={getQrImage(selectedUpiApp)} 
-                      alt={`${selectedUpiApp} QR`}
-                      className="w-full h-full object-contain"
-                      onError={(e) => {e.target.src = "https://placehold.co/400x400/F3F2EC/111?text=QR+Code"}}
-                    />
+                    {order ? (
+                      <img 
+                        src={`${API_URL}${order.qr_png}`} 
+                        alt={`UPI QR for ${order.txn_ref}`}
+                        className="w-full h-full object-contain"
+                      />
+                    ) : (
+                      <div className="w-full h-full flex items-center justify-center"><Loader2 className="w-6 h-6 animate-spin text-gray-400" /></div>
+                    )}
                     <div className="absolute -bottom-3 -right-3 bg-[#111111] text-white text-[10px] font-bold px-2 py-1 rounded shadow-lg">
                       SCAN ME
                     </div>
                   </div>
-                  <span className="mt-4 text-xs text-gray-500 font-medium">Scanning via {selectedUpiApp}</span>
+                  {order ? (
+                    <a href={order.upi_uri} className="mt-4 text-xs text-[#111111] font-bold underline">Pay {order.amount_display} in a UPI app</a>
+                  ) : (
+                    <span className="mt-4 text-xs text-gray-500 font-medium">Any UPI app can scan this</span>
+                  )}
                </div>
             </div>
           </div>
@@ -247,7 +264,7 @@ export default function Payment({ user, plan, onPaymentComplete }) {
                  <div className="space-y-4 mb-8">
                     <div className="flex justify-between items-center">
                        <span className="text-gray-500 text-sm">Plan Selected</span>
-                       <span className="text-[#111111] font-bold">{plan?.name || "Enterprise Suite"}</span>
+                       <span className="text-[#111111] font-bold">{planName || <Loader2 className="w-4 h-4 animate-spin text-gray-400" />}</span>
                     </div>
                     <div className="flex justify-between items-center">
                        <span className="text-gray-500 text-sm">Billing Cycle</span>
@@ -261,7 +278,7 @@ export default function Payment({ user, plan, onPaymentComplete }) {
 
                  <div className="flex justify-between items-end mb-8 pt-6 border-t border-dashed border-gray-200">
                     <span className="text-gray-500 text-sm font-medium">Total Due</span>
-                    <span className="text-4xl font-bold text-[#111111] tracking-tighter">{plan?.price || "₹19,999"}</span>
+                    <span className="text-4xl font-bold text-[#111111] tracking-tighter">{order?.amount_display || plan?.price || <Loader2 className="w-6 h-6 animate-spin text-gray-400" />}</span>
                  </div>
 
                  {/* Confirmation Form */}
@@ -278,6 +295,19 @@ export default function Payment({ user, plan, onPaymentComplete }) {
                         />
                     </div>
 
+                    <div className="space-y-2">
+                        <label className="text-xs font-bold text-gray-500 uppercase tracking-wider">Desktop Device ID</label>
+                        <input 
+                          type="text" 
+                          required
+                          value={deviceId}
+                          onChange={(e) => setDeviceId(e.target.value)}
+                          placeholder="XXXX-XXXX-XXXX-XXXX (shown on the activation screen)"
+                          pattern="[0-9A-Fa-f]{4}(-[0-9A-Fa-f]{4}){3}"
+                          className="w-full bg-gray-50 border border-gray-200 text-black p-4 rounded-lg outline-none focus:border-black focus:ring-1 focus:ring-black transition-all text-sm font-mono placeholder:text-gray-400"
+                        />
+                    </div>
+
                     {error && (
                         <div className="p-3 bg-red-50 border border-red-200 rounded-lg text-red-600 text-xs flex items-center gap-2 font-medium">
                           <AlertTriangle size={14} /> {error}
@@ -286,7 +316,7 @@ export default function Payment({ user, plan, onPaymentComplete }) {
 
                     <button 
                         type="submit" 
-                        disabled={isProcessing}
+                        disabled={isProcessing || !order}
                         className="w-full py-4 bg-[#111111] hover:bg-black text-white font-bold rounded-xl transition-all flex items-center justify-center gap-2 disabled:opacity-50 disabled:cursor-not-allowed shadow-lg hover:shadow-xl transform hover:-translate-y-0.5"
                     >
                         {isProcessing ? (
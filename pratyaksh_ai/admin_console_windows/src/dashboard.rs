@@ -1,11 +1,14 @@
 use eframe::egui;
 use crate::models::{PaymentRequest, UserAccessRecord, DashboardStats};
+use crate::settlement::{MatchStatus, ReconciliationReport};
+use pratyaksh_core::money;
 
 #[derive(PartialEq, Clone, Copy)]
 pub enum DashboardTab {
     Pending,
     ApprovedUsers,
     FullHistory,
+    Settlement,
     Statistics,
 }
 
@@ -18,9 +21,14 @@ pub fn render_dashboard(
     stats: &DashboardStats,
     selected_id: &mut Option<String>,
     search_query: &mut String,
+    settlement_path: &mut String,
+    report: &ReconciliationReport,
+    batch_running: bool,
     on_approve: &mut dyn FnMut(&PaymentRequest),
     on_deny: &mut dyn FnMut(&PaymentRequest),
-    on_refresh: &mut dyn FnMut()
+    on_refresh: &mut dyn FnMut(),
+    on_import: &mut dyn FnMut(),
+    on_approve_verified: &mut dyn FnMut()
 ) {
     // --- TOP BAR & STATS OVERVIEW ---
     ui.vertical(|ui| {
@@ -40,7 +48,7 @@ pub fn render_dashboard(
         ui.horizontal(|ui| {
             render_stat_widget(ui, "Total Approvals", &stats.total_approved.to_string(), egui::Color32::from_rgb(79, 249, 120));
             render_stat_widget(ui, "Pending", &stats.total_pending.to_string(), egui::Color32::GOLD);
-            render_stat_widget(ui, "Revenue", &format!("{}{:.2}", stats.currency_symbol, stats.total_revenue), egui::Color32::WHITE);
+            render_stat_widget(ui, "Revenue", &money::format_inr(stats.total_revenue), egui::Color32::WHITE);
         });
     });
     
@@ -53,6 +61,7 @@ pub fn render_dashboard(
         ui.selectable_value(current_tab, DashboardTab::Pending, "📥 Pending Requests");
         ui.selectable_value(current_tab, DashboardTab::ApprovedUsers, "👥 Approved Users");
         ui.selectable_value(current_tab, DashboardTab::FullHistory, "📜 Full History");
+        ui.selectable_value(current_tab, DashboardTab::Settlement, "🧾 Settlement");
         ui.selectable_value(current_tab, DashboardTab::Statistics, "📊 Analytics");
     });
 
@@ -63,6 +72,7 @@ pub fn render_dashboard(
         DashboardTab::Pending => render_pending_tab(ui, pending_requests, selected_id, on_approve, on_deny),
         DashboardTab::ApprovedUsers => render_approved_users_tab(ui, approved_users, search_query),
         DashboardTab::FullHistory => render_history_tab(ui, full_history, search_query),
+        DashboardTab::Settlement => render_settlement_tab(ui, settlement_path, report, batch_running, on_import, on_approve_verified),
         DashboardTab::Statistics => render_statistics_tab(ui, stats),
     }
 }
@@ -184,6 +194,94 @@ fn render_history_tab(ui: &mut egui::Ui, history: &Vec<PaymentRequest>, query: &
     });
 }
 
+// --- TAB: SETTLEMENT RECONCILIATION ---
+fn render_settlement_tab(
+    ui: &mut egui::Ui,
+    path: &mut String,
+    report: &ReconciliationReport,
+    batch_running: bool,
+    on_import: &mut dyn FnMut(),
+    on_approve_verified: &mut dyn FnMut()
+) {
+    ui.horizontal(|ui| {
+        ui.label("📂 Statement (CSV / XLSX):");
+        ui.add(egui::TextEdit::singleline(path).desired_width(400.0).hint_text("C:\\Downloads\\settlement.xlsx"));
+        if ui.button("Import & Match").clicked() {
+            on_import();
+        }
+    });
+    ui.add_space(10.0);
+
+    let verified = report.count(|s| *s == MatchStatus::Verified);
+    ui.horizontal(|ui| {
+        render_stat_widget(ui, "Verified", &verified.to_string(), egui::Color32::from_rgb(79, 249, 120));
+        render_stat_widget(ui, "Amount / Plan Mismatch", &report.count(|s| matches!(s, MatchStatus::AmountMismatch(_) | MatchStatus::PlanMismatch(_))).to_string(), egui::Color32::GOLD);
+        render_stat_widget(ui, "Duplicates", &report.count(|s| matches!(s, MatchStatus::Duplicate(_) | MatchStatus::SharedReference(_))).to_string(), egui::Color32::RED);
+        render_stat_widget(ui, "No Valid Order", &report.count(|s| matches!(s, MatchStatus::UnknownOrder | MatchStatus::WrongUser(_))).to_string(), egui::Color32::RED);
+        render_stat_widget(ui, "Orphans", &report.count(|s| *s == MatchStatus::Orphan).to_string(), egui::Color32::GRAY);
+        render_stat_widget(ui, "Pending, Not Paid", &report.unpaid.len().to_string(), egui::Color32::WHITE);
+
+        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+            let label = if batch_running { "⏳ APPROVING...".to_string() } else { format!("✅ APPROVE {} VERIFIED", verified) };
+            let btn = egui::Button::new(egui::RichText::new(label).color(egui::Color32::BLACK))
+                .fill(egui::Color32::from_rgb(79, 249, 120))
+                .min_size(egui::vec2(200.0, 40.0));
+            if ui.add_enabled(verified > 0 && !batch_running, btn).clicked() {
+                on_approve_verified();
+            }
+        });
+    });
+    ui.add_space(10.0);
+
+    egui::ScrollArea::vertical().id_source("settlement_scroll").show(ui, |ui| {
+        egui::Grid::new("settlement_grid").striped(true).min_col_width(90.0).show(ui, |ui| {
+            ui.label(egui::RichText::new("Row").strong());
+            ui.label(egui::RichText::new("Date").strong());
+            ui.label(egui::RichText::new("UTR / Reference").strong());
+            ui.label(egui::RichText::new("Credited").strong());
+            ui.label(egui::RichText::new("Request").strong());
+            ui.label(egui::RichText::new("Result").strong());
+            ui.end_row();
+
+            for r in &report.rows {
+                ui.label(r.entry.row.to_string());
+                ui.label(&r.entry.date);
+                ui.monospace(if r.entry.reference.is_empty() { &r.entry.remark } else { &r.entry.reference });
+                ui.label(money::format_inr(r.entry.amount));
+                match &r.request {
+                    Some(req) => ui.label(format!("{} ({})", req.email, req.txn_id)),
+                    None => ui.label("—"),
+                };
+                let (text, color) = match &r.status {
+                    MatchStatus::Verified => ("Verified".to_string(), egui::Color32::GREEN),
+                    MatchStatus::AmountMismatch(expected) => (format!("Mismatch: order is for {}", money::format_inr(*expected)), egui::Color32::GOLD),
+                    MatchStatus::Duplicate(first) => (format!("Duplicate of row {}", first), egui::Color32::RED),
+                    MatchStatus::SharedReference(n) => (format!("Duplicate: {} requests claim this reference", n), egui::Color32::RED),
+                    MatchStatus::AlreadyProcessed(status) => (format!("Already {}", status), egui::Color32::GRAY),
+                    MatchStatus::AwaitingOrder => ("Checking order...".to_string(), egui::Color32::GRAY),
+                    MatchStatus::UnknownOrder => ("No such order on the backend".to_string(), egui::Color32::RED),
+                    MatchStatus::WrongUser(owner) => (format!("Order belongs to {}", owner), egui::Color32::RED),
+                    MatchStatus::PlanMismatch(plan) => (format!("Mismatch: order is for the {} plan", plan), egui::Color32::GOLD),
+                    MatchStatus::Orphan => ("Orphan: no request".to_string(), egui::Color32::GRAY),
+                };
+                ui.label(egui::RichText::new(text).color(color));
+                ui.end_row();
+            }
+        });
+
+        if !report.unpaid.is_empty() {
+            ui.add_space(20.0);
+            ui.label(egui::RichText::new("Pending requests not found in this statement").strong());
+            for req in &report.unpaid {
+                ui.horizontal(|ui| {
+                    ui.monospace(&req.txn_id);
+                    ui.label(format!("{} · {}", req.email, req.amount));
+                });
+            }
+        }
+    });
+}
+
 // --- TAB: STATISTICS ---
 fn render_statistics_tab(ui: &mut egui::Ui, stats: &DashboardStats) {
     ui.heading("Financial Analytics");
@@ -194,8 +292,8 @@ fn render_statistics_tab(ui: &mut egui::Ui, stats: &DashboardStats) {
         ui.vertical(|ui| {
             ui.label(egui::RichText::new("Revenue Breakdown").size(18.0).strong());
             ui.add_space(10.0);
-            ui.label(format!("Gross Revenue: {}{:.2}", stats.currency_symbol, stats.total_revenue));
-            ui.label(format!("Avg. Ticket Size: {}{:.2}", stats.currency_symbol, if stats.total_approved > 0 { stats.total_revenue / stats.total_approved as f64 } else { 0.0 }));
+            ui.label(format!("Gross Revenue: {}", money::format_inr(stats.total_revenue)));
+            ui.label(format!("Avg. Ticket Size: {}", money::format_inr(if stats.total_approved > 0 { stats.total_revenue / stats.total_approved as i64 } else { 0 })));
             
             ui.add_space(20.0);
             ui.label(egui::RichText::new("Request Volume").size(18.0).strong());
@@ -235,7 +333,7 @@ fn render_detail_view(
         ui.label("Plan Selected:"); ui.label(&req.plan); ui.end_row();
         ui.label("Amount Paid:"); ui.label(egui::RichText::new(&req.amount).color(egui::Color32::GREEN).size(18.0)); ui.end_row();
         ui.label("Transaction ID:"); ui.monospace(&req.txn_id); ui.end_row();
-        ui.label("Device:"); ui.label(&req.device); ui.end_row();
+        ui.label("Device ID:"); ui.monospace(&req.device); ui.end_row();
         ui.label("User ID:"); ui.monospace(&req.user_id); ui.end_row();
     });
 
@@ -60,8 +60,8 @@ impl FirebaseClient {
         Ok(requests)
     }
 
-    // 2. APPROVE USER (Updates status AND creates User Access record)
-    pub fn approve_request(&self, req: &PaymentRequest) -> Result<(), String> {
+    // 2. APPROVE USER (Updates status AND creates User Access record carrying the signed license)
+    pub fn approve_request(&self, req: &PaymentRequest, license: &str) -> Result<(), String> {
         // A. Update Payment Status to 'approved'
         let payment_update_url = format!("https://firestore.googleapis.com/v1/{}?updateMask.fieldPaths=status", req.doc_path);
         let body = json!({
@@ -81,7 +81,8 @@ impl FirebaseClient {
             "fields": {
                 "canDownload": { "booleanValue": true },
                 "plan": { "stringValue": req.plan },
-                "grantedAt": { "stringValue": chrono::Local::now().to_rfc3339() }
+                "grantedAt": { "stringValue": chrono::Local::now().to_rfc3339() },
+                "license": { "stringValue": license }
             }
         });
 
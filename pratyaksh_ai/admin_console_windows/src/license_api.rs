@@ -0,0 +1,69 @@
+use crate::models::{PaymentOrder, PaymentRequest};
+use reqwest::blocking::Client;
+use reqwest::StatusCode;
+use serde_json::json;
+
+// Signed desktop licenses come from the backend's admin API, never from this console:
+// the Ed25519 signing key stays on the server.
+const DEFAULT_API_URL: &str = "http://localhost:8080";
+
+pub struct LicenseClient {
+    client: Client,
+    base_url: String,
+    admin_token: Option<String>, // Same PRATYAKSH_ADMIN_TOKEN the backend was started with
+}
+
+impl LicenseClient {
+    pub fn new() -> Self {
+        Self {
+            client: Client::new(),
+            base_url: std::env::var("PRATYAKSH_API_URL").unwrap_or_else(|_| DEFAULT_API_URL.to_string()).trim_end_matches('/').to_string(),
+            admin_token: std::env::var("PRATYAKSH_ADMIN_TOKEN").ok().filter(|t| !t.is_empty()),
+        }
+    }
+
+    // Issues a license bound to the device id the user entered with the payment; returns the license.json text.
+    // The plan is the one the backend order was paid for: the request's plan is only what the website wrote.
+    // Safe to repeat: for a payment already licensed to this user and device the backend answers 200 with
+    // the same license, so an approval whose Firestore write failed can simply be retried.
+    pub fn issue(&self, req: &PaymentRequest) -> Result<String, String> {
+        let token = self.admin_token.as_deref().ok_or("PRATYAKSH_ADMIN_TOKEN not set, cannot issue the license")?;
+        let order = self.order(req.txn_id.trim())?.ok_or_else(|| format!("No backend order {}, cannot issue the license", req.txn_id))?;
+        let body = json!({
+            "user_id": req.user_id,
+            "email": req.email,
+            "plan": order.plan,
+            "device_id": req.device,
+            "txn_id": req.txn_id.trim(),
+        });
+        let url = format!("{}/api/v1/admin/licenses", self.base_url);
+        let resp = self.client.post(&url).bearer_auth(token).json(&body).send().map_err(|e| e.to_string())?;
+
+        let status = resp.status();
+        let json: serde_json::Value = resp.json().map_err(|e| e.to_string())?;
+        if !status.is_success() {
+            // Problem details: list the rejected fields when there are any
+            let reasons: Vec<String> = json.get("invalid_params").and_then(|p| p.as_array()).into_iter().flatten()
+                .map(|p| format!("{} {}", p["name"].as_str().unwrap_or(""), p["reason"].as_str().unwrap_or("")))
+                .collect();
+            let detail = if reasons.is_empty() { json["detail"].as_str().unwrap_or("").to_string() } else { reasons.join("; ") };
+            return Err(format!("License API Error: {} {}", status, detail));
+        }
+        json.get("file").map(|f| f.to_string()).ok_or_else(|| "License API returned no license file".to_string())
+    }
+
+    // Backend order behind a transaction reference; Ok(None) when the backend never issued it
+    pub fn order(&self, txn_ref: &str) -> Result<Option<PaymentOrder>, String> {
+        // Issued references are alphanumeric; anything else cannot name an order and must not reach the path
+        if txn_ref.is_empty() || !txn_ref.chars().all(|c| c.is_ascii_alphanumeric()) {
+            return Ok(None);
+        }
+        let url = format!("{}/api/v1/payments/orders/{}", self.base_url, txn_ref);
+        let resp = self.client.get(&url).send().map_err(|e| e.to_string())?;
+        match resp.status() {
+            StatusCode::NOT_FOUND => Ok(None),
+            status if status.is_success() => resp.json().map(Some).map_err(|e| e.to_string()),
+            status => Err(format!("Order API Error: {} for {}", status, txn_ref)),
+        }
+    }
+}
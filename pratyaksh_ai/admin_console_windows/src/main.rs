@@ -2,30 +2,46 @@
 
 mod dashboard;
 mod firebase_api;
+mod license_api;
 mod models;
+mod settlement;
 
 use eframe::egui;
 use firebase_api::FirebaseClient;
-use models::{PaymentRequest, UserAccessRecord, DashboardStats};
+use license_api::LicenseClient;
+use models::{PaymentOrder, PaymentRequest, UserAccessRecord, DashboardStats};
 use dashboard::DashboardTab;
+use pratyaksh_core::money;
+use settlement::SettlementEntry;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
 use std::thread;
 use std::time::Duration;
 
 struct AdminApp {
     client: Arc<FirebaseClient>,
+    licenses: Arc<LicenseClient>,
     
     // --- Data Stores ---
     pending_requests: Arc<Mutex<Vec<PaymentRequest>>>,
     approved_users: Arc<Mutex<Vec<UserAccessRecord>>>,
     full_history: Arc<Mutex<Vec<PaymentRequest>>>,
     stats: Arc<Mutex<DashboardStats>>,
+    orders: Arc<Mutex<HashMap<String, Option<PaymentOrder>>>>, // Backend orders by txn ref; None: not issued
+    approving: Arc<Mutex<HashSet<String>>>, // Txn refs approved in this session, kept out of the next batch
+    batch_running: Arc<AtomicBool>,
+    notice: Arc<Mutex<Option<String>>>, // Status line from background work; triggers a refresh
     
     // --- UI State ---
     current_tab: DashboardTab,
     selected_id: Option<String>,
     search_query: String,
     status: String,
+    settlement_path: String,
+    settlement: Vec<SettlementEntry>, // Last imported statement; matched against fresh data every frame
+    order_lookups: HashSet<String>,   // Txn refs whose backend order has been requested
 }
 
 impl AdminApp {
@@ -38,15 +54,23 @@ impl AdminApp {
 
         let app = Self {
             client: Arc::new(FirebaseClient::new()),
+            licenses: Arc::new(LicenseClient::new()),
             pending_requests: Arc::new(Mutex::new(Vec::new())),
             approved_users: Arc::new(Mutex::new(Vec::new())),
             full_history: Arc::new(Mutex::new(Vec::new())),
             stats: Arc::new(Mutex::new(DashboardStats::default())),
+            orders: Arc::new(Mutex::new(HashMap::new())),
+            approving: Arc::new(Mutex::new(HashSet::new())),
+            batch_running: Arc::new(AtomicBool::new(false)),
+            notice: Arc::new(Mutex::new(None)),
             
             current_tab: DashboardTab::Pending,
             selected_id: None,
             search_query: String::new(),
             status: "Ready".to_string(),
+            settlement_path: String::new(),
+            settlement: Vec::new(),
+            order_lookups: HashSet::new(),
         };
         
         // Initial Fetch
@@ -81,10 +105,10 @@ impl AdminApp {
                 let total_denied = all_data.iter().filter(|r| r.status == "denied").count();
                 let total_pending = all_data.iter().filter(|r| r.status == "pending").count();
                 
-                // Simple revenue calc (assuming "₹19,999" format)
-                let revenue: f64 = all_data.iter()
+                // Revenue in paise; amounts arrive as display strings like "₹19,999"
+                let revenue: i64 = all_data.iter()
                     .filter(|r| r.status == "approved")
-                    .map(|r| r.amount.replace("₹", "").replace(",", "").parse::<f64>().unwrap_or(0.0))
+                    .filter_map(|r| money::parse_inr(&r.amount))
                     .sum();
 
                 let mut s_lock = stats_store.lock().unwrap();
@@ -93,7 +117,6 @@ impl AdminApp {
                     total_approved,
                     total_denied,
                     total_revenue: revenue,
-                    currency_symbol: "₹".to_string(),
                 };
             }
 
@@ -106,7 +129,10 @@ impl AdminApp {
     }
 
     fn approve(&mut self, req: &PaymentRequest) {
-        if let Err(e) = self.client.approve_request(req) {
+        // Sign first: a payment is only marked approved once its license exists. If the Firestore write
+        // fails, approving again fetches the same license instead of issuing a second one
+        let result = self.licenses.issue(req).and_then(|license| self.client.approve_request(req, &license));
+        if let Err(e) = result {
             self.status = format!("Error: {}", e);
         } else {
             self.status = format!("Approved {}", req.email);
@@ -115,6 +141,78 @@ impl AdminApp {
         }
     }
 
+    fn import_settlement(&mut self) {
+        match settlement::load(Path::new(self.settlement_path.trim())) {
+            Ok(entries) => {
+                self.status = format!("Imported {} credits from {}", entries.len(), self.settlement_path.trim());
+                self.settlement = entries;
+                // A fresh import retries lookups that failed last time
+                self.order_lookups.clear();
+                self.orders.lock().unwrap().clear();
+            }
+            Err(e) => self.status = format!("Error: {}", e),
+        }
+    }
+
+    // Fetches the backend order of every reference the statement pays that has not been looked up yet
+    fn fetch_orders(&mut self, history: &[PaymentRequest]) {
+        let missing: Vec<String> = settlement::referenced(&self.settlement, history).into_iter()
+            .filter(|txn| self.order_lookups.insert(txn.clone()))
+            .collect();
+        if missing.is_empty() {
+            return;
+        }
+        let licenses = self.licenses.clone();
+        let orders = self.orders.clone();
+        let notice = self.notice.clone();
+
+        thread::spawn(move || {
+            let mut failures = Vec::new();
+            for txn in missing {
+                match licenses.order(&txn) {
+                    Ok(order) => { orders.lock().unwrap().insert(txn, order); }
+                    Err(e) => failures.push(e),
+                }
+            }
+            if !failures.is_empty() {
+                *notice.lock().unwrap() = Some(format!("Order lookup failed, re-import to retry: {}", failures.join("; ")));
+            }
+        });
+    }
+
+    // Same path as a manual approval, one license per verified payment. Runs off the UI thread; the
+    // references are marked approved up front so a second click before the refresh cannot re-approve them.
+    fn approve_verified(&mut self, verified: Vec<PaymentRequest>) {
+        if verified.is_empty() || self.batch_running.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        self.approving.lock().unwrap().extend(verified.iter().map(|r| r.txn_id.clone()));
+        self.status = format!("Approving {} verified payments...", verified.len());
+
+        let client = self.client.clone();
+        let licenses = self.licenses.clone();
+        let approving = self.approving.clone();
+        let running = self.batch_running.clone();
+        let notice = self.notice.clone();
+
+        thread::spawn(move || {
+            let mut failures = Vec::new();
+            for req in &verified {
+                if let Err(e) = licenses.issue(req).and_then(|license| client.approve_request(req, &license)) {
+                    // Unmark so the payment can be retried
+                    approving.lock().unwrap().remove(&req.txn_id);
+                    failures.push(format!("{}: {}", req.email, e));
+                }
+            }
+            *notice.lock().unwrap() = Some(if failures.is_empty() {
+                format!("Approved {} verified payments", verified.len())
+            } else {
+                format!("Approved {} of {}. Errors: {}", verified.len() - failures.len(), verified.len(), failures.join("; "))
+            });
+            running.store(false, Ordering::SeqCst);
+        });
+    }
+
     fn deny(&mut self, req: &PaymentRequest) {
         if let Err(e) = self.client.deny_request(req) {
             self.status = format!("Error: {}", e);
@@ -133,10 +231,23 @@ impl eframe::App for AdminApp {
         let approved = self.approved_users.lock().unwrap().clone();
         let history = self.full_history.lock().unwrap().clone();
         let stats = self.stats.lock().unwrap().clone();
+        if let Some(notice) = self.notice.lock().unwrap().take() {
+            self.status = notice;
+            self.refresh_data();
+        }
+        self.fetch_orders(&history);
+        let report = {
+            let orders = self.orders.lock().unwrap();
+            let approving = self.approving.lock().unwrap();
+            settlement::reconcile(&self.settlement, &history, &orders, &approving)
+        };
+        let batch_running = self.batch_running.load(Ordering::SeqCst);
 
         let mut approve_req: Option<PaymentRequest> = None;
         let mut deny_req: Option<PaymentRequest> = None;
         let mut do_refresh = false;
+        let mut do_import = false;
+        let mut do_approve_verified = false;
 
         egui::CentralPanel::default().show(ctx, |ui| {
             dashboard::render_dashboard(
@@ -148,9 +259,14 @@ impl eframe::App for AdminApp {
                 &stats,
                 &mut self.selected_id,
                 &mut self.search_query,
+                &mut self.settlement_path,
+                &report,
+                batch_running,
                 &mut |r| approve_req = Some(r.clone()),
                 &mut |r| deny_req = Some(r.clone()),
-                &mut || do_refresh = true
+                &mut || do_refresh = true,
+                &mut || do_import = true,
+                &mut || do_approve_verified = true
             );
             
             ui.with_layout(egui::Layout::bottom_up(egui::Align::Min), |ui| {
@@ -163,6 +279,8 @@ impl eframe::App for AdminApp {
         if let Some(r) = approve_req { self.approve(&r); }
         if let Some(r) = deny_req { self.deny(&r); }
         if do_refresh { self.refresh_data(); }
+        if do_import { self.import_settlement(); }
+        if do_approve_verified { self.approve_verified(report.verified()); }
         
         // Auto-refresh every 5 seconds
         ctx.request_repaint_after(Duration::from_secs(5));
@@ -24,6 +24,14 @@ pub struct PaymentRequest {
     pub doc_path: String, 
 }
 
+// Backend UPI order behind a transaction reference; only the fields settlement checks
+#[derive(Debug, Deserialize, Clone)]
+pub struct PaymentOrder {
+    pub user_id: String,
+    pub plan: String, // Plan id the order was priced for, e.g. "scale"
+    pub amount: i64,  // Paise
+}
+
 #[allow(dead_code)]
 #[derive(Debug, Serialize, Deserialize)]
 pub struct FirestoreDocument {
@@ -89,8 +97,7 @@ pub struct DashboardStats {
     pub total_pending: usize,
     pub total_approved: usize,
     pub total_denied: usize,
-    pub total_revenue: f64, // Calculated from parsed amount strings
-    pub currency_symbol: String,
+    pub total_revenue: i64, // Paise, from the parsed amount strings
 }
 
 #[allow(dead_code)]
@@ -0,0 +1,352 @@
+use crate::models::{PaymentOrder, PaymentRequest};
+use calamine::{open_workbook_auto, Reader};
+use pratyaksh_core::money;
+use pratyaksh_core::upi::find_plan;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+// Settlement reconciliation: bank / PhonePe statements (CSV or XLSX) matched against payment requests.
+// UPI carries the order reference (PRK...) into the statement remark, so a hit in any text column counts.
+// The request only says what the website wrote; a payment verifies against the backend order the
+// reference was issued for, which fixes the paying user, the plan and the amount.
+
+// Header keywords, lowercase; the first column whose header contains one wins
+const REFERENCE_HEADERS: &[&str] = &["utr", "rrn", "transaction id", "txn id", "reference", "ref no", "ref. no"];
+const AMOUNT_HEADERS: &[&str] = &["credit", "deposit", "amount"];
+const REMARK_HEADERS: &[&str] = &["remark", "narration", "description", "particulars", "note", "message"];
+const DATE_HEADERS: &[&str] = &["date", "time"];
+const MIN_REFERENCE_LEN: usize = 8; // Shorter txn ids would match inside unrelated UTRs
+
+#[derive(Debug, Clone)]
+pub struct SettlementEntry {
+    pub row: usize, // 1-based, as the spreadsheet shows it
+    pub reference: String,
+    pub amount: i64, // Paise
+    pub remark: String,
+    pub date: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum MatchStatus {
+    Verified,                 // Pending request, backend order of the same user and plan, amount agrees
+    AmountMismatch(i64),      // Credit differs from the order amount (paise)
+    Duplicate(usize),         // Same request already matched at this statement row
+    SharedReference(usize),   // This many requests claim the same reference; none can be trusted
+    AlreadyProcessed(String), // Request was approved or denied before the import, or in this session
+    AwaitingOrder,            // Backend order not fetched yet
+    UnknownOrder,             // The backend never issued this reference
+    WrongUser(String),        // The order was created for this other user
+    PlanMismatch(String),     // The order paid for this other plan than the request names
+    Orphan,                   // No request carries this reference
+}
+
+#[derive(Debug, Clone)]
+pub struct Reconciliation {
+    pub entry: SettlementEntry,
+    pub request: Option<PaymentRequest>,
+    pub status: MatchStatus,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ReconciliationReport {
+    pub rows: Vec<Reconciliation>,
+    pub unpaid: Vec<PaymentRequest>, // Pending requests the statement never mentions
+}
+
+impl ReconciliationReport {
+    pub fn verified(&self) -> Vec<PaymentRequest> {
+        self.rows.iter().filter(|r| r.status == MatchStatus::Verified).filter_map(|r| r.request.clone()).collect()
+    }
+
+    pub fn count(&self, pred: impl Fn(&MatchStatus) -> bool) -> usize {
+        self.rows.iter().filter(|r| pred(&r.status)).count()
+    }
+}
+
+// Uppercase alphanumerics only: "UPI/CR/4123/prk-d28d" and "PRKD28D" compare as substrings
+fn normalize(raw: &str) -> String {
+    raw.chars().filter(|c| c.is_ascii_alphanumeric()).map(|c| c.to_ascii_uppercase()).collect()
+}
+
+fn find_column(headers: &[String], keywords: &[&str]) -> Option<usize> {
+    keywords.iter().find_map(|k| headers.iter().position(|h| h.contains(k)))
+}
+
+fn read_rows(path: &Path) -> Result<Vec<Vec<String>>, String> {
+    let ext = path.extension().and_then(|e| e.to_str()).unwrap_or("").to_ascii_lowercase();
+    match ext.as_str() {
+        "csv" => {
+            let mut reader = csv::ReaderBuilder::new().has_headers(false).flexible(true).from_path(path).map_err(|e| e.to_string())?;
+            reader.records()
+                .map(|r| r.map(|rec| rec.iter().map(|c| c.trim().to_string()).collect()).map_err(|e| e.to_string()))
+                .collect()
+        }
+        "xlsx" | "xls" | "xlsm" | "ods" => {
+            let mut workbook = open_workbook_auto(path).map_err(|e| e.to_string())?;
+            let range = workbook.worksheet_range_at(0).ok_or("Workbook has no sheets")?.map_err(|e| e.to_string())?;
+            Ok(range.rows().map(|row| row.iter().map(|c| c.to_string().trim().to_string()).collect()).collect())
+        }
+        _ => Err(format!("Unsupported statement format '{}', expected CSV or XLSX", ext)),
+    }
+}
+
+// Statements often open with account details; the header is the first row naming an amount column
+pub fn load(path: &Path) -> Result<Vec<SettlementEntry>, String> {
+    let rows = read_rows(path)?;
+    let (header_idx, headers) = rows.iter().enumerate()
+        .map(|(i, row)| (i, row.iter().map(|h| h.to_lowercase()).collect::<Vec<String>>()))
+        .find(|(_, headers)| find_column(headers, AMOUNT_HEADERS).is_some() && find_column(headers, &[REFERENCE_HEADERS, REMARK_HEADERS].concat()).is_some())
+        .ok_or("No header row with an amount and a reference or remark column")?;
+
+    let amount_col = find_column(&headers, AMOUNT_HEADERS).unwrap_or_default();
+    let reference_col = find_column(&headers, REFERENCE_HEADERS);
+    let remark_col = find_column(&headers, REMARK_HEADERS);
+    let date_col = find_column(&headers, DATE_HEADERS);
+    let cell = |row: &Vec<String>, col: Option<usize>| col.and_then(|c| row.get(c)).cloned().unwrap_or_default();
+
+    // Debit rows and footers have no parseable credit amount; skip them
+    Ok(rows.iter().enumerate().skip(header_idx + 1)
+        .filter_map(|(i, row)| {
+            let amount = money::parse_inr(row.get(amount_col)?)?;
+            (amount > 0).then(|| SettlementEntry {
+                row: i + 1,
+                reference: cell(row, reference_col),
+                amount,
+                remark: cell(row, remark_col),
+                date: cell(row, date_col),
+            })
+        })
+        .collect())
+}
+
+fn find_request<'a>(entry: &SettlementEntry, requests: &'a [PaymentRequest]) -> Option<&'a PaymentRequest> {
+    let haystack = normalize(&format!("{} {}", entry.reference, entry.remark));
+    requests.iter().find(|r| {
+        let txn = normalize(&r.txn_id);
+        txn.len() >= MIN_REFERENCE_LEN && haystack.contains(&txn)
+    })
+}
+
+// Transaction references the statement pays, i.e. the backend orders reconcile needs
+pub fn referenced(entries: &[SettlementEntry], requests: &[PaymentRequest]) -> HashSet<String> {
+    entries.iter().filter_map(|e| find_request(e, requests)).map(|r| r.txn_id.trim().to_string()).collect()
+}
+
+// `orders` maps a reference to its backend order (None: the backend has no such order); a missing
+// key means the lookup has not finished. `approving` holds references approved since the last refresh.
+pub fn reconcile(
+    entries: &[SettlementEntry],
+    requests: &[PaymentRequest],
+    orders: &HashMap<String, Option<PaymentOrder>>,
+    approving: &HashSet<String>,
+) -> ReconciliationReport {
+    let mut claims: HashMap<String, usize> = HashMap::new(); // Normalized txn id -> requests carrying it
+    for req in requests {
+        *claims.entry(normalize(&req.txn_id)).or_default() += 1;
+    }
+
+    let mut matched_rows: Vec<(String, usize)> = Vec::new(); // Normalized txn id -> first statement row
+    let mut rows = Vec::new();
+    for entry in entries {
+        let request = find_request(entry, requests);
+        let status = match request {
+            None => MatchStatus::Orphan,
+            Some(req) => {
+                let txn = normalize(&req.txn_id);
+                match matched_rows.iter().find(|(t, _)| *t == txn) {
+                    Some((_, first)) => MatchStatus::Duplicate(*first),
+                    None => {
+                        matched_rows.push((txn.clone(), entry.row));
+                        let order = orders.get(req.txn_id.trim());
+                        match_request(req, entry, claims[&txn], order, approving)
+                    }
+                }
+            }
+        };
+        rows.push(Reconciliation { entry: entry.clone(), request: request.cloned(), status });
+    }
+
+    let seen: HashSet<&String> = matched_rows.iter().map(|(txn, _)| txn).collect();
+    let unpaid = requests.iter().filter(|r| r.status == "pending" && !seen.contains(&normalize(&r.txn_id))).cloned().collect();
+    ReconciliationReport { rows, unpaid }
+}
+
+fn match_request(req: &PaymentRequest, entry: &SettlementEntry, claims: usize, order: Option<&Option<PaymentOrder>>, approving: &HashSet<String>) -> MatchStatus {
+    if claims > 1 {
+        return MatchStatus::SharedReference(claims);
+    }
+    if approving.contains(&req.txn_id) {
+        return MatchStatus::AlreadyProcessed("approved".to_string());
+    }
+    if req.status != "pending" {
+        return MatchStatus::AlreadyProcessed(req.status.clone());
+    }
+    match order {
+        None => MatchStatus::AwaitingOrder,
+        Some(None) => MatchStatus::UnknownOrder,
+        Some(Some(o)) if o.user_id != req.user_id => MatchStatus::WrongUser(o.user_id.clone()),
+        Some(Some(o)) if find_plan(&req.plan).map(|p| p.id) != Some(o.plan.as_str()) => MatchStatus::PlanMismatch(o.plan.clone()),
+        Some(Some(o)) if o.amount != entry.amount => MatchStatus::AmountMismatch(o.amount),
+        Some(Some(_)) => MatchStatus::Verified,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(user: &str, txn: &str, status: &str) -> PaymentRequest {
+        PaymentRequest {
+            user_id: user.to_string(),
+            email: format!("{}@example.in", user),
+            amount: "₹2,399".to_string(),
+            plan: "Scale".to_string(),
+            status: status.to_string(),
+            txn_id: txn.to_string(),
+            device: "PRK-DEV-1".to_string(),
+            doc_path: String::new(),
+        }
+    }
+
+    fn credit(row: usize, remark: &str, amount: i64) -> SettlementEntry {
+        SettlementEntry { row, reference: format!("41230{}", row), amount, remark: remark.to_string(), date: "01/04/2025".to_string() }
+    }
+
+    fn order(user: &str, amount: i64) -> Option<PaymentOrder> {
+        Some(PaymentOrder { user_id: user.to_string(), plan: "scale".to_string(), amount })
+    }
+
+    fn statuses(report: &ReconciliationReport) -> Vec<MatchStatus> {
+        report.rows.iter().map(|r| r.status.clone()).collect()
+    }
+
+    // PhonePe writes the UPI remark as "UPI/CR/<rrn>/<note>"; the reference matches case-insensitively
+    #[test]
+    fn verifies_against_the_backend_order() {
+        let requests = [request("u1", "PRKD28DAE202E62", "pending")];
+        let entries = [credit(2, "UPI/CR/412300/Scale prkd28dae202e62", 239_900)];
+        let orders = HashMap::from([("PRKD28DAE202E62".to_string(), order("u1", 239_900))]);
+        let report = reconcile(&entries, &requests, &orders, &HashSet::new());
+        assert_eq!(statuses(&report), [MatchStatus::Verified]);
+        assert_eq!(report.verified().len(), 1);
+        assert!(report.unpaid.is_empty());
+    }
+
+    #[test]
+    fn order_decides_user_and_amount() {
+        let requests = [request("u1", "PRK000000000001", "pending"), request("u2", "PRK000000000002", "pending"), request("u3", "PRK000000000003", "pending")];
+        let entries = [credit(2, "PRK000000000001", 239_900), credit(3, "PRK000000000002", 119_900), credit(4, "PRK000000000003", 239_900)];
+        let orders = HashMap::from([
+            ("PRK000000000001".to_string(), order("someone-else", 239_900)),
+            ("PRK000000000002".to_string(), order("u2", 239_900)),
+            ("PRK000000000003".to_string(), None),
+        ]);
+        let report = reconcile(&entries, &requests, &orders, &HashSet::new());
+        assert_eq!(statuses(&report), [
+            MatchStatus::WrongUser("someone-else".to_string()),
+            MatchStatus::AmountMismatch(239_900),
+            MatchStatus::UnknownOrder,
+        ]);
+        assert!(report.verified().is_empty());
+    }
+
+    // The signed license carries the plan, so a request for more than was paid must not verify
+    #[test]
+    fn order_decides_the_plan() {
+        let mut upgraded = request("u1", "PRK000000000001", "pending");
+        upgraded.plan = "Enterprise".to_string();
+        let mut renamed = request("u2", "PRK000000000002", "pending");
+        renamed.plan = "Enterprise Suite".to_string();
+        let same = request("u3", "PRK000000000003", "pending");
+        let entries = [credit(2, "PRK000000000001", 239_900), credit(3, "PRK000000000002", 239_900), credit(4, "PRK000000000003", 239_900)];
+        let orders = HashMap::from([
+            ("PRK000000000001".to_string(), order("u1", 239_900)),
+            ("PRK000000000002".to_string(), Some(PaymentOrder { user_id: "u2".to_string(), plan: "enterprise".to_string(), amount: 239_900 })),
+            ("PRK000000000003".to_string(), order("u3", 239_900)),
+        ]);
+        let report = reconcile(&entries, &[upgraded, renamed, same], &orders, &HashSet::new());
+        assert_eq!(statuses(&report), [
+            MatchStatus::PlanMismatch("scale".to_string()),
+            MatchStatus::PlanMismatch("enterprise".to_string()),
+            MatchStatus::Verified,
+        ]);
+    }
+
+    #[test]
+    fn waits_for_the_order_lookup() {
+        let requests = [request("u1", "PRK000000000001", "pending")];
+        let entries = [credit(2, "PRK000000000001", 239_900)];
+        let report = reconcile(&entries, &requests, &HashMap::new(), &HashSet::new());
+        assert_eq!(statuses(&report), [MatchStatus::AwaitingOrder]);
+        assert_eq!(referenced(&entries, &requests), HashSet::from(["PRK000000000001".to_string()]));
+    }
+
+    // Two website requests quoting one reference: one payment cannot license both
+    #[test]
+    fn requests_sharing_a_reference_are_duplicates() {
+        let requests = [request("u1", "PRK000000000001", "pending"), request("u2", "prk-000000000001", "pending")];
+        let entries = [credit(2, "PRK000000000001", 239_900)];
+        let orders = HashMap::from([("PRK000000000001".to_string(), order("u1", 239_900))]);
+        let report = reconcile(&entries, &requests, &orders, &HashSet::new());
+        assert_eq!(statuses(&report), [MatchStatus::SharedReference(2)]);
+        assert!(report.verified().is_empty());
+        assert!(report.unpaid.is_empty());
+    }
+
+    #[test]
+    fn second_credit_for_a_request_is_a_duplicate() {
+        let requests = [request("u1", "PRK000000000001", "pending")];
+        let entries = [credit(2, "PRK000000000001", 239_900), credit(5, "PRK000000000001", 239_900)];
+        let orders = HashMap::from([("PRK000000000001".to_string(), order("u1", 239_900))]);
+        let report = reconcile(&entries, &requests, &orders, &HashSet::new());
+        assert_eq!(statuses(&report), [MatchStatus::Verified, MatchStatus::Duplicate(2)]);
+    }
+
+    // A batch in flight must not offer the same payments again before the refresh lands
+    #[test]
+    fn approved_this_session_is_not_verified_again() {
+        let requests = [request("u1", "PRK000000000001", "pending"), request("u2", "PRK000000000002", "denied")];
+        let entries = [credit(2, "PRK000000000001", 239_900), credit(3, "PRK000000000002", 239_900)];
+        let orders = HashMap::from([
+            ("PRK000000000001".to_string(), order("u1", 239_900)),
+            ("PRK000000000002".to_string(), order("u2", 239_900)),
+        ]);
+        let approving = HashSet::from(["PRK000000000001".to_string()]);
+        let report = reconcile(&entries, &requests, &orders, &approving);
+        assert_eq!(statuses(&report), [
+            MatchStatus::AlreadyProcessed("approved".to_string()),
+            MatchStatus::AlreadyProcessed("denied".to_string()),
+        ]);
+        assert!(report.verified().is_empty());
+    }
+
+    #[test]
+    fn orphans_and_unpaid() {
+        let requests = [request("u1", "PRK000000000001", "pending"), request("u2", "SHORT1", "pending")];
+        // "SHORT1" is under MIN_REFERENCE_LEN, so it never matches inside an unrelated remark
+        let entries = [credit(2, "NEFT SALARY SHORT1", 5_000_000)];
+        let report = reconcile(&entries, &requests, &HashMap::new(), &HashSet::new());
+        assert_eq!(statuses(&report), [MatchStatus::Orphan]);
+        assert_eq!(report.unpaid.len(), 2);
+    }
+
+    // Bank exports open with account details before the header row; debit rows carry no credit
+    #[test]
+    fn loads_csv_after_the_preamble() {
+        let path = std::env::temp_dir().join(format!("pratyaksh_settlement_{}.csv", std::process::id()));
+        std::fs::write(&path, "Account,XXXX1234\n\
+            Date,Narration,UTR No,Debit,Credit\n\
+            01/04/2025,UPI/CR/412300/PRKD28DAE202E62,412300111,,\"2,399.00\"\n\
+            02/04/2025,ATM WDL,,500.00,\n\
+            Closing balance,,,,\n").unwrap();
+        let entries = load(&path).unwrap();
+        std::fs::remove_file(&path).ok();
+
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].row, 3);
+        assert_eq!(entries[0].amount, 239_900);
+        assert_eq!(entries[0].reference, "412300111");
+        assert_eq!(entries[0].remark, "UPI/CR/412300/PRKD28DAE202E62");
+        assert_eq!(entries[0].date, "01/04/2025");
+    }
+}
@@ -0,0 +1,342 @@
+use axum::{
+    async_trait,
+    extract::{rejection::{JsonRejection, PathRejection}, FromRequestParts, Path, Request, State},
+    http::{header, request::Parts, HeaderMap, StatusCode},
+    middleware::Next,
+    response::Response,
+    Json,
+};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use utoipa::ToSchema;
+
+use crate::errors::{ApiError, FieldError};
+use crate::store::{ApiKey, Caller, Firm, Scope, Store};
+use crate::AppState;
+
+// API-key authentication. Each key belongs to one CA firm and carries a read or write scope;
+// only its SHA-256 is stored. Firms and keys are managed with the operator's admin token.
+
+pub const ADMIN_TOKEN_ENV: &str = "PRATYAKSH_ADMIN_TOKEN";
+const KEY_PREFIX: &str = "pk_";
+
+#[derive(Deserialize, ToSchema)]
+pub struct NewFirm {
+    pub id: String, // Short slug, e.g. "sharma-associates"
+    pub name: String,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct NewKey {
+    pub name: String, // What the key is for, e.g. "desktop - audit team"
+    pub scope: Scope,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct IssuedKey {
+    #[serde(flatten)]
+    pub key: ApiKey,
+    pub secret: String, // Shown once; only the hash is kept
+}
+
+pub fn hash_key(secret: &str) -> String {
+    hex::encode(Sha256::digest(secret.as_bytes()))
+}
+
+fn generate_key() -> String {
+    let mut bytes = [0u8; 32];
+    getrandom::getrandom(&mut bytes).expect("OS random source unavailable");
+    format!("{}{}", KEY_PREFIX, hex::encode(bytes))
+}
+
+// Accepts "Authorization: Bearer <key>" or "X-API-Key: <key>"
+fn presented_key(headers: &HeaderMap) -> Option<&str> {
+    let bearer = headers.get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "));
+    bearer.or_else(|| headers.get("x-api-key").and_then(|v| v.to_str().ok())).map(str::trim)
+}
+
+impl Caller {
+    pub fn require(&self, scope: Scope) -> Result<(), ApiError> {
+        if self.scope < scope {
+            return Err(ApiError::Forbidden("This API key is read-only".to_string()));
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for Caller {
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        parts.extensions.get::<Caller>().cloned().ok_or_else(|| ApiError::Unauthorized("API key required".to_string()))
+    }
+}
+
+// Resolves the key to its firm and scope for every /api/v1 route except the admin ones
+pub async fn require_key(State(state): State<AppState>, mut req: Request, next: Next) -> Result<Response, ApiError> {
+    let secret = presented_key(req.headers()).ok_or_else(|| ApiError::Unauthorized("API key required".to_string()))?;
+    let key_hash = hash_key(secret);
+    let caller = state.store.run(move |s| s.grant(&key_hash)).await?.ok_or_else(|| ApiError::Unauthorized("Unknown or revoked API key".to_string()))?;
+    tracing::debug!("{} {} by key {} of firm {}", req.method(), req.uri().path(), caller.key_id, caller.firm_id);
+    req.extensions_mut().insert(caller);
+    Ok(next.run(req).await)
+}
+
+pub async fn require_admin(State(state): State<AppState>, req: Request, next: Next) -> Result<Response, ApiError> {
+    let Some(expected) = &state.admin_token_hash else {
+        return Err(ApiError::Forbidden(format!("Admin API disabled; set {}", ADMIN_TOKEN_ENV)));
+    };
+    // Comparing hashes keeps the comparison time independent of the token
+    match presented_key(req.headers()) {
+        Some(token) if hash_key(token) == **expected => Ok(next.run(req).await),
+        _ => Err(ApiError::Unauthorized("Admin token required".to_string())),
+    }
+}
+
+fn check_firm_id(id: &str, errors: &mut Vec<FieldError>) {
+    if id.is_empty() || !id.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-') {
+        errors.push(FieldError::OutOfRange { field: "id".to_string(), reason: "must be lowercase letters, digits and hyphens".to_string() });
+    }
+}
+
+fn find_firm(store: &Store, firm: &str) -> Result<(), ApiError> {
+    if !store.firm_exists(firm)? {
+        return Err(ApiError::NotFound(format!("No firm {}", firm)));
+    }
+    Ok(())
+}
+
+// ADMIN
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/firms",
+    tag = "admin",
+    responses(
+        (status = 200, body = Vec<Firm>),
+        (status = 401, description = "Missing, unknown or revoked API key", body = ProblemDetails, content_type = "application/problem+json"),
+    ),
+    security(("admin_token" = []))
+)]
+pub async fn list_firms(State(state): State<AppState>) -> Result<Json<Vec<Firm>>, ApiError> {
+    Ok(Json(state.store.run(|s| s.firms()).await?))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/firms",
+    tag = "admin",
+    request_body = NewFirm,
+    responses(
+        (status = 201, body = Firm),
+        (status = 400, description = "Malformed request", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 401, description = "Missing, unknown or revoked API key", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 409, description = "Conflict", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 422, description = "Invalid parameters", body = ProblemDetails, content_type = "application/problem+json"),
+    ),
+    security(("admin_token" = []))
+)]
+pub async fn create_firm(State(state): State<AppState>, body: Result<Json<NewFirm>, JsonRejection>) -> Result<(StatusCode, Json<Firm>), ApiError> {
+    let Json(firm) = body?;
+
+    let mut errors = Vec::new();
+    check_firm_id(&firm.id, &mut errors);
+    if firm.name.trim().is_empty() {
+        errors.push(FieldError::OutOfRange { field: "name".to_string(), reason: "must not be empty".to_string() });
+    }
+    if !errors.is_empty() {
+        return Err(ApiError::Validation(errors));
+    }
+    let firm = state.store.run(move |s| -> Result<_, ApiError> {
+        if s.firm_exists(&firm.id)? {
+            return Err(ApiError::Conflict(format!("Firm {} already exists", firm.id)));
+        }
+        Ok(s.insert_firm(&firm.id, &firm.name)?)
+    }).await?;
+    Ok((StatusCode::CREATED, Json(firm)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/firms/{firm}/keys",
+    tag = "admin",
+    params(("firm" = String, Path, description = "Firm id")),
+    responses(
+        (status = 200, body = Vec<ApiKey>),
+        (status = 401, description = "Missing, unknown or revoked API key", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 404, description = "Not found", body = ProblemDetails, content_type = "application/problem+json"),
+    ),
+    security(("admin_token" = []))
+)]
+pub async fn list_keys(State(state): State<AppState>, path: Result<Path<String>, PathRejection>) -> Result<Json<Vec<ApiKey>>, ApiError> {
+    let Path(firm) = path?;
+    let keys = state.store.run(move |s| -> Result<_, ApiError> {
+        find_firm(s, &firm)?;
+        Ok(s.keys(&firm)?)
+    }).await?;
+    Ok(Json(keys))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/firms/{firm}/keys",
+    tag = "admin",
+    params(("firm" = String, Path, description = "Firm id")),
+    request_body = NewKey,
+    responses(
+        (status = 201, body = IssuedKey),
+        (status = 400, description = "Malformed request", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 401, description = "Missing, unknown or revoked API key", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 404, description = "Not found", body = ProblemDetails, content_type = "application/problem+json"),
+    ),
+    security(("admin_token" = []))
+)]
+pub async fn issue_key(State(state): State<AppState>, path: Result<Path<String>, PathRejection>, body: Result<Json<NewKey>, JsonRejection>) -> Result<(StatusCode, Json<IssuedKey>), ApiError> {
+    let Path(firm) = path?;
+    let Json(req) = body?;
+    let secret = generate_key();
+    let key_hash = hash_key(&secret);
+    let key = state.store.run(move |s| -> Result<_, ApiError> {
+        find_firm(s, &firm)?;
+        Ok(s.insert_key(&firm, &req.name, req.scope, &key_hash)?)
+    }).await?;
+    Ok((StatusCode::CREATED, Json(IssuedKey { key, secret })))
+}
+
+// Revoked keys stay listed with their revocation time
+#[utoipa::path(
+    delete,
+    path = "/api/v1/admin/firms/{firm}/keys/{id}",
+    tag = "admin",
+    params(("firm" = String, Path, description = "Firm id"), ("id" = i64, Path, description = "Key id")),
+    responses(
+        (status = 200, body = ApiKey),
+        (status = 401, description = "Missing, unknown or revoked API key", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 404, description = "Not found", body = ProblemDetails, content_type = "application/problem+json"),
+    ),
+    security(("admin_token" = []))
+)]
+pub async fn revoke_key(State(state): State<AppState>, path: Result<Path<(String, i64)>, PathRejection>) -> Result<Json<ApiKey>, ApiError> {
+    let Path((firm, id)) = path?;
+    state.store.run(move |s| s.revoke_key(&firm, id)?.ok_or_else(|| ApiError::NotFound(format!("No key {} for firm {}", id, firm))))
+        .await
+        .map(Json)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{key_for, request, send, state, ADMIN_TOKEN};
+    use serde_json::json;
+
+    fn company(cin: &str) -> serde_json::Value {
+        json!({ "cin": cin, "name": "Acme Private Limited", "fy_end_date": "2024-03-31", "incorporation_date": "2018-04-01", "company_type": "private" })
+    }
+
+    #[test]
+    fn presented_key_accepts_bearer_and_header() {
+        let mut headers = HeaderMap::new();
+        headers.insert("x-api-key", " pk_abc ".parse().unwrap());
+        assert_eq!(presented_key(&headers), Some("pk_abc"));
+        headers.insert(header::AUTHORIZATION, "Bearer pk_def".parse().unwrap());
+        assert_eq!(presented_key(&headers), Some("pk_def"));
+        headers.insert(header::AUTHORIZATION, "Basic cGs6".parse().unwrap());
+        assert_eq!(presented_key(&headers), Some("pk_abc"));
+    }
+
+    #[tokio::test]
+    async fn firms_only_see_their_own_companies() {
+        let state = state();
+        let sharma = key_for(&state, "sharma", Scope::Write);
+        let mehta = key_for(&state, "mehta", Scope::Write);
+
+        let (status, _) = send(&state, request("POST", "/api/v1/companies", Some(&sharma), Some(company("U1")))).await;
+        assert_eq!(status, StatusCode::CREATED);
+
+        let (status, _) = send(&state, request("GET", "/api/v1/companies/U1", Some(&mehta), None)).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        let (_, listed) = send(&state, request("GET", "/api/v1/companies", Some(&mehta), None)).await;
+        assert_eq!(listed, json!([]));
+        let (status, _) = send(&state, request("DELETE", "/api/v1/companies/U1", Some(&mehta), None)).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+
+        // The same CIN can be registered by another firm without touching the first firm's row
+        let (status, _) = send(&state, request("POST", "/api/v1/companies", Some(&mehta), Some(company("U1")))).await;
+        assert_eq!(status, StatusCode::CREATED);
+        let (status, _) = send(&state, request("GET", "/api/v1/companies/U1", Some(&sharma), None)).await;
+        assert_eq!(status, StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn read_keys_cannot_write() {
+        let state = state();
+        let read = key_for(&state, "sharma", Scope::Read);
+
+        let (status, body) = send(&state, request("POST", "/api/v1/companies", Some(&read), Some(company("U1")))).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+        assert_eq!(body["detail"], "This API key is read-only");
+        let (status, _) = send(&state, request("GET", "/api/v1/companies", Some(&read), None)).await;
+        assert_eq!(status, StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn missing_unknown_and_revoked_keys_are_rejected() {
+        let state = state();
+        let (status, _) = send(&state, request("GET", "/api/v1/companies", None, None)).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        let (status, _) = send(&state, request("GET", "/api/v1/companies", Some("pk_unknown"), None)).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+        let (status, issued) = send(&state, request("POST", "/api/v1/admin/firms", Some(ADMIN_TOKEN), Some(json!({ "id": "sharma", "name": "Sharma & Associates" })))).await;
+        assert_eq!(status, StatusCode::CREATED, "{}", issued);
+        let (status, issued) = send(&state, request("POST", "/api/v1/admin/firms/sharma/keys", Some(ADMIN_TOKEN), Some(json!({ "name": "desktop", "scope": "read" })))).await;
+        assert_eq!(status, StatusCode::CREATED);
+        let secret = issued["secret"].as_str().unwrap().to_string();
+        assert!(secret.starts_with(KEY_PREFIX));
+
+        let (status, _) = send(&state, request("GET", "/api/v1/companies", Some(&secret), None)).await;
+        assert_eq!(status, StatusCode::OK);
+
+        let uri = format!("/api/v1/admin/firms/sharma/keys/{}", issued["id"]);
+        let (status, revoked) = send(&state, request("DELETE", &uri, Some(ADMIN_TOKEN), None)).await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(revoked["revoked_at"].is_string());
+
+        let (status, body) = send(&state, request("GET", "/api/v1/companies", Some(&secret), None)).await;
+        assert_eq!((status, body["detail"].as_str()), (StatusCode::UNAUTHORIZED, Some("Unknown or revoked API key")));
+    }
+
+    #[tokio::test]
+    async fn admin_routes_need_the_admin_token() {
+        let mut state = state();
+        let key = key_for(&state, "sharma", Scope::Write);
+
+        let (status, _) = send(&state, request("GET", "/api/v1/admin/firms", None, None)).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        // A firm's API key is not an admin token, whatever its scope
+        let (status, _) = send(&state, request("GET", "/api/v1/admin/firms", Some(&key), None)).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        let (status, firms) = send(&state, request("GET", "/api/v1/admin/firms", Some(ADMIN_TOKEN), None)).await;
+        assert_eq!((status, firms[0]["id"].as_str()), (StatusCode::OK, Some("sharma")));
+
+        state.admin_token_hash = None;
+        let (status, _) = send(&state, request("GET", "/api/v1/admin/firms", Some(ADMIN_TOKEN), None)).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+    }
+
+    #[tokio::test]
+    async fn firm_ids_are_validated_and_unique() {
+        let state = state();
+        let (status, _) = send(&state, request("POST", "/api/v1/admin/firms", Some(ADMIN_TOKEN), Some(json!({ "id": "Sharma Associates", "name": "Sharma" })))).await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        let firm = json!({ "id": "sharma", "name": "Sharma & Associates" });
+        let (status, _) = send(&state, request("POST", "/api/v1/admin/firms", Some(ADMIN_TOKEN), Some(firm.clone()))).await;
+        assert_eq!(status, StatusCode::CREATED);
+        let (status, _) = send(&state, request("POST", "/api/v1/admin/firms", Some(ADMIN_TOKEN), Some(firm))).await;
+        assert_eq!(status, StatusCode::CONFLICT);
+        let (status, _) = send(&state, request("POST", "/api/v1/admin/firms/mehta/keys", Some(ADMIN_TOKEN), Some(json!({ "name": "desktop", "scope": "write" })))).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+}
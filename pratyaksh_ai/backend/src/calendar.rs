@@ -0,0 +1,108 @@
+use axum::{
+    extract::{rejection::QueryRejection, Query, State},
+    Json,
+};
+use serde::Deserialize;
+use utoipa::{IntoParams, ToSchema};
+
+use crate::errors::{parse_date, ApiError, FieldError};
+use crate::AppState;
+
+pub use pratyaksh_core::calendar::*;
+
+// CalendarQuery as sent by clients: dates stay strings so a bad one is reported by field name
+#[derive(Deserialize, ToSchema, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct CalendarParams {
+    pub cin: Option<String>,        // Labels exported events
+    pub fy_end_date: String,        // YYYY-MM-DD
+    pub incorporation_date: String, // YYYY-MM-DD
+    pub company_type: CompanyType,
+    #[serde(default)]
+    pub has_subsidiaries: bool,
+    pub board_meeting_date: Option<String>, // YYYY-MM-DD, board meeting approving the accounts
+    #[serde(default)]
+    pub first_fy: bool,
+    #[serde(default)]
+    pub agm_extension_months: u32,
+}
+
+impl CalendarParams {
+    // Records every problem under `prefix`; None when a date did not parse
+    pub fn validate(&self, prefix: &str, errors: &mut Vec<FieldError>) -> Option<CalendarQuery> {
+        let date = |name: &str, raw: &str, errors: &mut Vec<FieldError>| parse_date(&format!("{}{}", prefix, name), raw, errors);
+        let fy_end_date = date("fy_end_date", &self.fy_end_date, errors);
+        let incorporation_date = date("incorporation_date", &self.incorporation_date, errors);
+        let board_meeting_date = match &self.board_meeting_date {
+            Some(raw) => Some(date("board_meeting_date", raw, errors)?),
+            None => None,
+        };
+        let query = CalendarQuery {
+            cin: self.cin.clone(),
+            fy_end_date: fy_end_date?,
+            incorporation_date: incorporation_date?,
+            company_type: self.company_type,
+            has_subsidiaries: self.has_subsidiaries,
+            board_meeting_date,
+            first_fy: self.first_fy,
+            agm_extension_months: self.agm_extension_months,
+        };
+        query.check(prefix, errors);
+        Some(query)
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/compliance/calendar",
+    tag = "compliance",
+    params(CalendarParams),
+    responses(
+        (status = 200, body = ComplianceCalendar),
+        (status = 400, description = "Malformed request", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 401, description = "Missing, unknown or revoked API key", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 422, description = "Invalid parameters", body = ProblemDetails, content_type = "application/problem+json"),
+    ),
+    security(("api_key" = []))
+)]
+pub async fn compliance_calendar(State(state): State<AppState>, query: Result<Query<CalendarParams>, QueryRejection>) -> Result<Json<ComplianceCalendar>, ApiError> {
+    let Query(params) = query?;
+
+    let mut errors = Vec::new();
+    let query = match params.validate("", &mut errors) {
+        Some(query) if errors.is_empty() => query,
+        _ => return Err(ApiError::Validation(errors)),
+    };
+
+    Ok(Json(build_calendar(&query, &state.circulars)))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::store::Scope;
+    use crate::testing::{key_for, request, send, state};
+    use axum::http::StatusCode;
+    use serde_json::json;
+
+    // A bad date is an invalid parameter named after its field, not a 400 with serde's message
+    #[tokio::test]
+    async fn bad_dates_are_invalid_params() {
+        let state = state();
+        let key = key_for(&state, "sharma", Scope::Read);
+        let (status, body) = send(&state, request("GET", "/api/v1/compliance/calendar?fy_end_date=2024-02-30&incorporation_date=2020-06-01&company_type=private&board_meeting_date=soon", Some(&key), None)).await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        let names: Vec<&str> = body["invalid_params"].as_array().unwrap().iter().map(|p| p["name"].as_str().unwrap()).collect();
+        assert_eq!(names, ["fy_end_date", "board_meeting_date"]);
+
+        let (status, _) = send(&state, request("GET", "/api/v1/compliance/calendar.ics?fy_end_date=31-03-2024&incorporation_date=2020-06-01&company_type=private", Some(&key), None)).await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+
+        let company = json!({ "fy_end_date": "2024-03-31", "incorporation_date": "2020-06-01", "company_type": "private" });
+        let bad = json!({ "fy_end_date": "2024-03-31", "incorporation_date": "June 2020", "company_type": "private" });
+        let (status, body) = send(&state, request("POST", "/api/v1/compliance/calendar.ics", Some(&key), Some(json!({ "companies": [company, bad] })))).await;
+        assert_eq!((status, &body["invalid_params"][0]["name"]), (StatusCode::UNPROCESSABLE_ENTITY, &json!("companies[1].incorporation_date")));
+
+        let (status, _) = send(&state, request("GET", "/api/v1/compliance/calendar?fy_end_date=2024-03-31&incorporation_date=2020-06-01&company_type=private", Some(&key), None)).await;
+        assert_eq!(status, StatusCode::OK);
+    }
+}
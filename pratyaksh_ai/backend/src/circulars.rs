@@ -0,0 +1,26 @@
+use std::path::{Path, PathBuf};
+
+pub use pratyaksh_core::circulars::*;
+
+// The registry file can be replaced in the data dir without a rebuild; the core crate carries a bundled copy.
+
+pub const REGISTRY_PATH_ENV: &str = "PRATYAKSH_CIRCULARS";
+const REGISTRY_FILE: &str = "circulars.json";
+
+// Reads the registry from $PRATYAKSH_CIRCULARS or <data_dir>/circulars.json, falling back to the bundled copy
+pub fn load(data_dir: &Path) -> Result<CircularRegistry, String> {
+    let path = std::env::var(REGISTRY_PATH_ENV).map(PathBuf::from).unwrap_or_else(|_| data_dir.join(REGISTRY_FILE));
+    load_from(&path)
+}
+
+// A missing file falls back to the bundled copy; an unreadable or invalid one is a configuration error
+pub fn load_from(path: &Path) -> Result<CircularRegistry, String> {
+    match std::fs::read_to_string(path) {
+        Ok(raw) => CircularRegistry::from_json(&raw).map_err(|e| format!("circulars: {} is not a valid registry: {}", path.display(), e)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            tracing::warn!("Circular registry {} not found, using bundled copy", path.display());
+            Ok(CircularRegistry::bundled())
+        }
+        Err(e) => Err(format!("circulars: cannot read {}: {}", path.display(), e)),
+    }
+}
@@ -0,0 +1,392 @@
+use axum::{
+    extract::{rejection::{JsonRejection, PathRejection}, Path, State},
+    http::StatusCode,
+    Json,
+};
+use chrono::Utc;
+use serde::Deserialize;
+use utoipa::ToSchema;
+
+use crate::calendar::CompanyType;
+use crate::errors::{parse_date, ApiError, FieldError};
+use crate::fees::FEE_FORMS;
+use crate::portfolio::company_risk;
+use crate::store::{Caller, Company, Filing, FirmStore, NewFiling, RiskSnapshot, Scope};
+use crate::AppState;
+use pratyaksh_core::llp;
+use pratyaksh_core::risk::{check_form, check_share_capital, RiskInput};
+
+// CRUD over the calling firm's companies, and risk assessed from the filings actually recorded
+
+#[derive(Deserialize, ToSchema)]
+pub struct RiskRequest {
+    pub as_of: Option<String>,       // YYYY-MM-DD, defaults to today
+    pub fy_end_date: Option<String>, // YYYY-MM-DD, defaults to the company's stored FY end
+    pub forms: Option<Vec<String>>,  // Defaults to the annual filings for the company type
+    pub nominal_share_capital: Option<i64>, // Sets the normal fee slab; omit for companies without share capital
+}
+
+fn check_company(company: &Company, errors: &mut Vec<FieldError>) {
+    if company.cin.trim().is_empty() {
+        errors.push(FieldError::OutOfRange { field: "cin".to_string(), reason: "must not be empty".to_string() });
+    }
+    if company.incorporation_date > company.fy_end_date {
+        errors.push(FieldError::OutOfRange { field: "incorporation_date".to_string(), reason: "must not be after fy_end_date".to_string() });
+    }
+    company.profile().check("", errors);
+}
+
+fn check_filing(company: &Company, filing: &NewFiling, errors: &mut Vec<FieldError>) {
+    if !FEE_FORMS.contains(&filing.form.as_str()) {
+        errors.push(FieldError::UnsupportedForm { field: "form".to_string(), value: filing.form.clone(), accepted: FEE_FORMS });
+    } else {
+        company.profile().check_form("form", &filing.form, errors);
+    }
+    if filing.srn.trim().is_empty() {
+        errors.push(FieldError::OutOfRange { field: "srn".to_string(), reason: "must not be empty".to_string() });
+    }
+    if filing.filing_date <= filing.fy_end_date {
+        errors.push(FieldError::OutOfRange { field: "filing_date".to_string(), reason: "must be after fy_end_date".to_string() });
+    }
+}
+
+fn find_company(store: &FirmStore, cin: &str) -> Result<Company, ApiError> {
+    store.company(cin)?.ok_or_else(|| ApiError::NotFound(format!("No company with CIN {}", cin)))
+}
+
+// COMPANIES
+#[utoipa::path(
+    get,
+    path = "/api/v1/companies",
+    tag = "companies",
+    responses(
+        (status = 200, body = Vec<Company>),
+        (status = 401, description = "Missing, unknown or revoked API key", body = ProblemDetails, content_type = "application/problem+json"),
+    ),
+    security(("api_key" = []))
+)]
+pub async fn list_companies(State(state): State<AppState>, caller: Caller) -> Result<Json<Vec<Company>>, ApiError> {
+    let store = state.store.for_firm(&caller.firm_id);
+    Ok(Json(store.run(|s| s.companies()).await?))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/companies",
+    tag = "companies",
+    request_body = Company,
+    responses(
+        (status = 201, body = Company),
+        (status = 400, description = "Malformed request", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 401, description = "Missing, unknown or revoked API key", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "API key is read-only", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 409, description = "Conflict", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 422, description = "Invalid parameters", body = ProblemDetails, content_type = "application/problem+json"),
+    ),
+    security(("api_key" = []))
+)]
+pub async fn create_company(State(state): State<AppState>, caller: Caller, body: Result<Json<Company>, JsonRejection>) -> Result<(StatusCode, Json<Company>), ApiError> {
+    caller.require(Scope::Write)?;
+    let store = state.store.for_firm(&caller.firm_id);
+    let Json(company) = body?;
+
+    let mut errors = Vec::new();
+    check_company(&company, &mut errors);
+    if !errors.is_empty() {
+        return Err(ApiError::Validation(errors));
+    }
+    let company = store.run(move |s| -> Result<_, ApiError> {
+        if s.company(&company.cin)?.is_some() {
+            return Err(ApiError::Conflict(format!("Company {} already exists", company.cin)));
+        }
+        s.insert_company(&company)?;
+        Ok(company)
+    }).await?;
+    Ok((StatusCode::CREATED, Json(company)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/companies/{cin}",
+    tag = "companies",
+    params(("cin" = String, Path, description = "Corporate Identification Number")),
+    responses(
+        (status = 200, body = Company),
+        (status = 401, description = "Missing, unknown or revoked API key", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 404, description = "Not found", body = ProblemDetails, content_type = "application/problem+json"),
+    ),
+    security(("api_key" = []))
+)]
+pub async fn get_company(State(state): State<AppState>, caller: Caller, path: Result<Path<String>, PathRejection>) -> Result<Json<Company>, ApiError> {
+    let store = state.store.for_firm(&caller.firm_id);
+    let Path(cin) = path?;
+    Ok(Json(store.run(move |s| find_company(s, &cin)).await?))
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/v1/companies/{cin}",
+    tag = "companies",
+    params(("cin" = String, Path, description = "Corporate Identification Number")),
+    request_body = Company,
+    responses(
+        (status = 200, body = Company),
+        (status = 400, description = "Malformed request", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 401, description = "Missing, unknown or revoked API key", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "API key is read-only", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 404, description = "Not found", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 422, description = "Invalid parameters", body = ProblemDetails, content_type = "application/problem+json"),
+    ),
+    security(("api_key" = []))
+)]
+pub async fn update_company(State(state): State<AppState>, caller: Caller, path: Result<Path<String>, PathRejection>, body: Result<Json<Company>, JsonRejection>) -> Result<Json<Company>, ApiError> {
+    caller.require(Scope::Write)?;
+    let store = state.store.for_firm(&caller.firm_id);
+    let Path(cin) = path?;
+    let Json(company) = body?;
+
+    let mut errors = Vec::new();
+    if company.cin != cin {
+        errors.push(FieldError::OutOfRange { field: "cin".to_string(), reason: "must match the CIN in the path".to_string() });
+    }
+    check_company(&company, &mut errors);
+    if !errors.is_empty() {
+        return Err(ApiError::Validation(errors));
+    }
+
+    let company = store.run(move |s| -> Result<_, ApiError> {
+        if !s.update_company(&company)? {
+            return Err(ApiError::NotFound(format!("No company with CIN {}", cin)));
+        }
+        Ok(company)
+    }).await?;
+    Ok(Json(company))
+}
+
+// Removes the company together with its filings and snapshots
+#[utoipa::path(
+    delete,
+    path = "/api/v1/companies/{cin}",
+    tag = "companies",
+    params(("cin" = String, Path, description = "Corporate Identification Number")),
+    responses(
+        (status = 204, description = "Company, filings and snapshots deleted"),
+        (status = 401, description = "Missing, unknown or revoked API key", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "API key is read-only", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 404, description = "Not found", body = ProblemDetails, content_type = "application/problem+json"),
+    ),
+    security(("api_key" = []))
+)]
+pub async fn delete_company(State(state): State<AppState>, caller: Caller, path: Result<Path<String>, PathRejection>) -> Result<StatusCode, ApiError> {
+    caller.require(Scope::Write)?;
+    let store = state.store.for_firm(&caller.firm_id);
+    let Path(cin) = path?;
+    store.run(move |s| -> Result<_, ApiError> {
+        if !s.delete_company(&cin)? {
+            return Err(ApiError::NotFound(format!("No company with CIN {}", cin)));
+        }
+        Ok(())
+    }).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+// FILINGS
+#[utoipa::path(
+    get,
+    path = "/api/v1/companies/{cin}/filings",
+    tag = "companies",
+    params(("cin" = String, Path, description = "Corporate Identification Number")),
+    responses(
+        (status = 200, body = Vec<Filing>),
+        (status = 401, description = "Missing, unknown or revoked API key", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 404, description = "Not found", body = ProblemDetails, content_type = "application/problem+json"),
+    ),
+    security(("api_key" = []))
+)]
+pub async fn list_filings(State(state): State<AppState>, caller: Caller, path: Result<Path<String>, PathRejection>) -> Result<Json<Vec<Filing>>, ApiError> {
+    let store = state.store.for_firm(&caller.firm_id);
+    let Path(cin) = path?;
+    let filings = store.run(move |s| -> Result<_, ApiError> {
+        find_company(s, &cin)?;
+        Ok(s.filings(&cin)?)
+    }).await?;
+    Ok(Json(filings))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/companies/{cin}/filings",
+    tag = "companies",
+    params(("cin" = String, Path, description = "Corporate Identification Number")),
+    request_body = NewFiling,
+    responses(
+        (status = 201, body = Filing),
+        (status = 400, description = "Malformed request", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 401, description = "Missing, unknown or revoked API key", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "API key is read-only", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 404, description = "Not found", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 409, description = "Conflict", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 422, description = "Invalid parameters", body = ProblemDetails, content_type = "application/problem+json"),
+    ),
+    security(("api_key" = []))
+)]
+pub async fn create_filing(State(state): State<AppState>, caller: Caller, path: Result<Path<String>, PathRejection>, body: Result<Json<NewFiling>, JsonRejection>) -> Result<(StatusCode, Json<Filing>), ApiError> {
+    caller.require(Scope::Write)?;
+    let store = state.store.for_firm(&caller.firm_id);
+    let Path(cin) = path?;
+    let Json(filing) = body?;
+    let filing = store.run(move |s| -> Result<_, ApiError> {
+        let company = find_company(s, &cin)?;
+        let mut errors = Vec::new();
+        check_filing(&company, &filing, &mut errors);
+        if !errors.is_empty() {
+            return Err(ApiError::Validation(errors));
+        }
+        // A duplicate SRN surfaces as a 409 from the UNIQUE constraint
+        Ok(s.insert_filing(&cin, &filing)?)
+    }).await?;
+    Ok((StatusCode::CREATED, Json(filing)))
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/v1/companies/{cin}/filings/{id}",
+    tag = "companies",
+    params(("cin" = String, Path, description = "Corporate Identification Number"), ("id" = i64, Path, description = "Filing id")),
+    request_body = NewFiling,
+    responses(
+        (status = 200, body = Filing),
+        (status = 400, description = "Malformed request", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 401, description = "Missing, unknown or revoked API key", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "API key is read-only", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 404, description = "Not found", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 409, description = "Conflict", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 422, description = "Invalid parameters", body = ProblemDetails, content_type = "application/problem+json"),
+    ),
+    security(("api_key" = []))
+)]
+pub async fn update_filing(State(state): State<AppState>, caller: Caller, path: Result<Path<(String, i64)>, PathRejection>, body: Result<Json<NewFiling>, JsonRejection>) -> Result<Json<Filing>, ApiError> {
+    caller.require(Scope::Write)?;
+    let store = state.store.for_firm(&caller.firm_id);
+    let Path((cin, id)) = path?;
+    let Json(filing) = body?;
+    let filing = store.run(move |s| -> Result<_, ApiError> {
+        let company = find_company(s, &cin)?;
+        let mut errors = Vec::new();
+        check_filing(&company, &filing, &mut errors);
+        if !errors.is_empty() {
+            return Err(ApiError::Validation(errors));
+        }
+        s.update_filing(&cin, id, &filing)?.ok_or_else(|| ApiError::NotFound(format!("No filing {} for {}", id, cin)))
+    }).await?;
+    Ok(Json(filing))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/v1/companies/{cin}/filings/{id}",
+    tag = "companies",
+    params(("cin" = String, Path, description = "Corporate Identification Number"), ("id" = i64, Path, description = "Filing id")),
+    responses(
+        (status = 204, description = "Filing deleted"),
+        (status = 401, description = "Missing, unknown or revoked API key", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "API key is read-only", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 404, description = "Not found", body = ProblemDetails, content_type = "application/problem+json"),
+    ),
+    security(("api_key" = []))
+)]
+pub async fn delete_filing(State(state): State<AppState>, caller: Caller, path: Result<Path<(String, i64)>, PathRejection>) -> Result<StatusCode, ApiError> {
+    caller.require(Scope::Write)?;
+    let store = state.store.for_firm(&caller.firm_id);
+    let Path((cin, id)) = path?;
+    store.run(move |s| -> Result<_, ApiError> {
+        if !s.delete_filing(&cin, id)? {
+            return Err(ApiError::NotFound(format!("No filing {} for {}", id, cin)));
+        }
+        Ok(())
+    }).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+// RISK
+// Assesses the company against its recorded filings and keeps the result as a snapshot
+#[utoipa::path(
+    post,
+    path = "/api/v1/companies/{cin}/risk",
+    tag = "companies",
+    params(("cin" = String, Path, description = "Corporate Identification Number")),
+    request_body = RiskRequest,
+    responses(
+        (status = 201, body = RiskSnapshot),
+        (status = 400, description = "Malformed request", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 401, description = "Missing, unknown or revoked API key", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "API key is read-only", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 404, description = "Not found", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 422, description = "Invalid parameters", body = ProblemDetails, content_type = "application/problem+json"),
+    ),
+    security(("api_key" = []))
+)]
+pub async fn assess_risk(State(state): State<AppState>, caller: Caller, path: Result<Path<String>, PathRejection>, body: Result<Json<RiskRequest>, JsonRejection>) -> Result<(StatusCode, Json<RiskSnapshot>), ApiError> {
+    caller.require(Scope::Write)?;
+    let store = state.store.for_firm(&caller.firm_id);
+    let Path(cin) = path?;
+    let Json(req) = body?;
+    let company = store.run({
+        let cin = cin.clone();
+        move |s| find_company(s, &cin)
+    }).await?;
+    let profile = company.profile();
+
+    let mut errors = Vec::new();
+    let as_of = match &req.as_of {
+        Some(raw) => parse_date("as_of", raw, &mut errors),
+        None => Some(Utc::now().date_naive()),
+    };
+    let fy_end = match &req.fy_end_date {
+        Some(raw) => parse_date("fy_end_date", raw, &mut errors),
+        None => Some(company.fy_end_date),
+    };
+    let forms = req.forms.unwrap_or_else(|| match company.company_type {
+        CompanyType::Llp => llp::LLP_FORMS.iter().map(|f| f.to_string()).collect(),
+        _ => vec!["AOC-4".to_string(), "MGT-7".to_string()],
+    });
+    for (i, form) in forms.iter().enumerate() {
+        check_form(&format!("forms[{}]", i), form, &profile, &mut errors);
+    }
+    check_share_capital("nominal_share_capital", req.nominal_share_capital, &mut errors);
+    let (as_of, fy_end) = match (as_of, fy_end) {
+        (Some(as_of), Some(fy_end)) if errors.is_empty() => (as_of, fy_end),
+        _ => return Err(ApiError::Validation(errors)),
+    };
+
+    let circulars = state.circulars.clone();
+    let snapshot = store.run(move |s| -> Result<_, ApiError> {
+        let input = RiskInput { fy_end, form_type: String::new(), as_of, profile, share_capital: req.nominal_share_capital, filing: None };
+        let risk = company_risk(&cin, &forms, &input, &circulars, s)?;
+        let report = serde_json::to_value(&risk).expect("risk report serializes");
+        Ok(s.insert_snapshot(&cin, as_of, &risk.overall_risk, risk.penalty_exposure, &report)?)
+    }).await?;
+    Ok((StatusCode::CREATED, Json(snapshot)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/companies/{cin}/risk-snapshots",
+    tag = "companies",
+    params(("cin" = String, Path, description = "Corporate Identification Number")),
+    responses(
+        (status = 200, body = Vec<RiskSnapshot>),
+        (status = 401, description = "Missing, unknown or revoked API key", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 404, description = "Not found", body = ProblemDetails, content_type = "application/problem+json"),
+    ),
+    security(("api_key" = []))
+)]
+pub async fn list_snapshots(State(state): State<AppState>, caller: Caller, path: Result<Path<String>, PathRejection>) -> Result<Json<Vec<RiskSnapshot>>, ApiError> {
+    let store = state.store.for_firm(&caller.firm_id);
+    let Path(cin) = path?;
+    let snapshots = store.run(move |s| -> Result<_, ApiError> {
+        find_company(s, &cin)?;
+        Ok(s.snapshots(&cin)?)
+    }).await?;
+    Ok(Json(snapshots))
+}
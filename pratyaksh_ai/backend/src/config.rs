@@ -0,0 +1,352 @@
+use axum::http::{header, HeaderName, HeaderValue, Method};
+use clap::Parser;
+use rustls::pki_types::{pem::PemObject, CertificateDer, PrivateKeyDer};
+use rustls::ServerConfig;
+use serde::Deserialize;
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use tower_http::cors::{AllowOrigin, CorsLayer};
+use tracing_subscriber::EnvFilter;
+
+use crate::circulars::{self, CircularRegistry};
+
+// Server configuration, layered: built-in defaults, then the TOML file, then PRATYAKSH_* environment
+// variables, then command-line flags. The merged result is validated, and the files it names are loaded,
+// before anything binds.
+
+const DEFAULT_CONFIG_PATH: &str = "pratyaksh.toml";
+const DEFAULT_LISTEN: &str = "0.0.0.0:8080";
+const DEFAULT_LOG_LEVEL: &str = "info";
+const DEFAULT_DATA_DIR: &str = "data";
+const DEFAULT_UPI_PAYEE_NAME: &str = "Pratyaksh AI";
+const DEFAULT_DRAIN_DELAY_SECS: u64 = 10;
+// drain_delay plus ops::DRAIN_TIMEOUT must finish inside the unit's TimeoutStopSec=50 with margin to spare,
+// or systemd kills the process mid-drain; see pratyaksh.example.service
+const MAX_DRAIN_DELAY_SECS: u64 = 15;
+
+#[derive(Parser)]
+#[command(name = "pratyaksh_backend", about = "Pratyaksh compliance API server")]
+struct Cli {
+    #[arg(long, env = "PRATYAKSH_CONFIG", help = "TOML config file [default: pratyaksh.toml, if present]")]
+    config: Option<PathBuf>,
+    #[arg(long, env = "PRATYAKSH_LISTEN", help = "Listen address, e.g. 127.0.0.1:8080")]
+    listen: Option<String>,
+    #[arg(long = "cors-origin", env = "PRATYAKSH_CORS_ORIGINS", value_delimiter = ',', help = "Allowed browser origin; repeat or comma-separate, \"*\" for any")]
+    cors_origins: Option<Vec<String>>,
+    #[arg(long, env = "PRATYAKSH_TLS_CERT", help = "PEM certificate chain; enables HTTPS together with --tls-key")]
+    tls_cert: Option<PathBuf>,
+    #[arg(long, env = "PRATYAKSH_TLS_KEY", help = "PEM private key")]
+    tls_key: Option<PathBuf>,
+    #[arg(long, env = "PRATYAKSH_LOG", help = "Log filter, e.g. info or pratyaksh_backend=debug")]
+    log_level: Option<String>,
+    #[arg(long, env = "PRATYAKSH_DATA_DIR", help = "Directory for the database, circulars and reminders")]
+    data_dir: Option<PathBuf>,
+    #[arg(long, env = "PRATYAKSH_UPI_VPA", help = "UPI address plan payments are collected on, e.g. pratyaksh@okaxis; enables payment orders")]
+    upi_vpa: Option<String>,
+    #[arg(long, env = "PRATYAKSH_UPI_PAYEE_NAME", help = "Payee name shown in the UPI app [default: Pratyaksh AI]")]
+    upi_payee_name: Option<String>,
+    #[arg(long, env = "PRATYAKSH_DRAIN_DELAY", help = "Seconds /readyz reports draining after SIGTERM before connections are refused, at most 15 [default: 10]")]
+    drain_delay: Option<u64>,
+    #[arg(long, env = "PRATYAKSH_TRUSTED_PROXY_HOPS", help = "Reverse proxies in front of the server that append to X-Forwarded-For; 0 when clients connect directly [default: 0]")]
+    trusted_proxy_hops: Option<usize>,
+    #[arg(long, help = "Print the OpenAPI document to stdout and exit")]
+    export_openapi: bool,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+struct FileTls {
+    cert: Option<PathBuf>,
+    key: Option<PathBuf>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+struct FileUpi {
+    vpa: Option<String>,
+    payee_name: Option<String>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+struct FileConfig {
+    listen: Option<String>,
+    cors_origins: Option<Vec<String>>,
+    log_level: Option<String>,
+    data_dir: Option<PathBuf>,
+    drain_delay: Option<u64>,
+    trusted_proxy_hops: Option<usize>,
+    #[serde(default)]
+    tls: FileTls,
+    #[serde(default)]
+    upi: FileUpi,
+}
+
+#[derive(Clone)]
+pub struct UpiConfig {
+    pub vpa: String,
+    pub payee_name: String,
+}
+
+pub struct Config {
+    pub listen: SocketAddr,
+    pub cors_origins: Vec<String>, // Empty: no cross-origin access
+    pub tls: Option<Arc<ServerConfig>>, // Certificate chain and key, already parsed; None serves plain HTTP
+    pub log_level: String,
+    pub data_dir: PathBuf,
+    pub circulars: Arc<CircularRegistry>,
+    pub upi: Option<UpiConfig>, // None: payment orders are disabled
+    pub drain_delay: Duration,  // Between SIGTERM and closing the listener, so load balancers see /readyz fail first
+    pub trusted_proxy_hops: usize, // X-Forwarded-For entries appended by our own proxies; 0 trusts only the peer address
+    pub source: Option<PathBuf>, // TOML file that was read, if any
+    pub export_openapi: bool,
+}
+
+// "*" or a bare scheme://host[:port] origin, as browsers send it
+fn check_origin(origin: &str, errors: &mut Vec<String>) {
+    if origin == "*" {
+        return;
+    }
+    let host = origin.strip_prefix("https://").or_else(|| origin.strip_prefix("http://"));
+    let valid = matches!(host, Some(h) if !h.is_empty() && !h.contains('/')) && HeaderValue::from_str(origin).is_ok();
+    if !valid {
+        errors.push(format!("cors_origins: '{}' is not an origin like https://app.example.com", origin));
+    }
+}
+
+fn load_tls(cert: &Path, key: &Path) -> Result<Arc<ServerConfig>, String> {
+    let certs = CertificateDer::pem_file_iter(cert)
+        .and_then(|certs| certs.collect::<Result<Vec<_>, _>>())
+        .map_err(|e| format!("tls.cert: cannot read {}: {}", cert.display(), e))?;
+    if certs.is_empty() {
+        return Err(format!("tls.cert: {} holds no PEM certificate", cert.display()));
+    }
+    let private_key = PrivateKeyDer::from_pem_file(key).map_err(|e| format!("tls.key: cannot read a private key from {}: {}", key.display(), e))?;
+    let mut config = ServerConfig::builder_with_provider(Arc::new(rustls::crypto::ring::default_provider()))
+        .with_safe_default_protocol_versions()
+        .map_err(|e| format!("tls: {}", e))?
+        .with_no_client_auth()
+        .with_single_cert(certs, private_key)
+        .map_err(|e| format!("tls: {} and {} do not form a usable certificate and key: {}", cert.display(), key.display(), e))?;
+    config.alpn_protocols = vec![b"h2".to_vec(), b"http/1.1".to_vec()];
+    Ok(Arc::new(config))
+}
+
+impl Config {
+    // Collects every problem rather than stopping at the first, so the config can be fixed in one pass
+    pub fn load() -> Result<Self, Vec<String>> {
+        let cli = Cli::parse();
+        let path = cli.config.clone().unwrap_or_else(|| PathBuf::from(DEFAULT_CONFIG_PATH));
+        let (file, source) = match std::fs::read_to_string(&path) {
+            Ok(raw) => {
+                let file: FileConfig = toml::from_str(&raw).map_err(|e| vec![format!("{}: {}", path.display(), e)])?;
+                (file, Some(path))
+            }
+            // Only an explicitly named file has to exist
+            Err(e) if cli.config.is_none() && e.kind() == std::io::ErrorKind::NotFound => (FileConfig::default(), None),
+            Err(e) => return Err(vec![format!("{}: {}", path.display(), e)]),
+        };
+        Self::merge(cli, file, source)
+    }
+
+    fn merge(cli: Cli, file: FileConfig, source: Option<PathBuf>) -> Result<Self, Vec<String>> {
+        let mut errors = Vec::new();
+        let export_openapi = cli.export_openapi;
+
+        let listen_raw = cli.listen.or(file.listen).unwrap_or_else(|| DEFAULT_LISTEN.to_string());
+        let listen = listen_raw.parse::<SocketAddr>()
+            .map_err(|_| errors.push(format!("listen: '{}' is not a socket address like 0.0.0.0:8080", listen_raw)))
+            .ok();
+
+        let cors_origins: Vec<String> = cli.cors_origins.or(file.cors_origins).unwrap_or_default()
+            .into_iter()
+            .map(|o| o.trim().to_string())
+            .filter(|o| !o.is_empty())
+            .collect();
+        for origin in &cors_origins {
+            check_origin(origin, &mut errors);
+        }
+        if cors_origins.len() > 1 && cors_origins.iter().any(|o| o == "*") {
+            errors.push("cors_origins: \"*\" cannot be combined with specific origins".to_string());
+        }
+
+        let tls = match (cli.tls_cert.or(file.tls.cert), cli.tls_key.or(file.tls.key)) {
+            (Some(cert), Some(key)) => {
+                let missing: Vec<String> = [("tls.cert", &cert), ("tls.key", &key)].into_iter()
+                    .filter(|(_, path)| !path.is_file())
+                    .map(|(name, path)| format!("{}: {} does not exist", name, path.display()))
+                    .collect();
+                if missing.is_empty() {
+                    load_tls(&cert, &key).map_err(|e| errors.push(e)).ok()
+                } else {
+                    errors.extend(missing);
+                    None
+                }
+            }
+            (None, None) => None,
+            _ => {
+                errors.push("tls: cert and key must be set together".to_string());
+                None
+            }
+        };
+
+        let log_level = cli.log_level.or(file.log_level).unwrap_or_else(|| DEFAULT_LOG_LEVEL.to_string());
+        if let Err(e) = EnvFilter::try_new(&log_level) {
+            errors.push(format!("log_level: '{}' is not a valid filter: {}", log_level, e));
+        }
+
+        let data_dir = cli.data_dir.or(file.data_dir).unwrap_or_else(|| PathBuf::from(DEFAULT_DATA_DIR));
+        if let Err(e) = std::fs::create_dir_all(&data_dir) {
+            errors.push(format!("data_dir: cannot create {}: {}", data_dir.display(), e));
+        }
+        let circulars = circulars::load(&data_dir).map(Arc::new).map_err(|e| errors.push(e)).ok();
+
+        let upi = cli.upi_vpa.or(file.upi.vpa).map(|vpa| vpa.trim().to_string()).map(|vpa| {
+            let valid = matches!(vpa.split_once('@'), Some((handle, bank)) if !handle.is_empty() && !bank.is_empty() && !bank.contains('@'))
+                && !vpa.contains(char::is_whitespace);
+            if !valid {
+                errors.push(format!("upi.vpa: '{}' is not a UPI address like name@bank", vpa));
+            }
+            let payee_name = cli.upi_payee_name.or(file.upi.payee_name).unwrap_or_else(|| DEFAULT_UPI_PAYEE_NAME.to_string());
+            UpiConfig { vpa, payee_name }
+        });
+
+        let drain_delay = cli.drain_delay.or(file.drain_delay).unwrap_or(DEFAULT_DRAIN_DELAY_SECS);
+        if drain_delay > MAX_DRAIN_DELAY_SECS {
+            errors.push(format!("drain_delay: {}s is longer than the {}s maximum", drain_delay, MAX_DRAIN_DELAY_SECS));
+        }
+        let drain_delay = Duration::from_secs(drain_delay);
+
+        let trusted_proxy_hops = cli.trusted_proxy_hops.or(file.trusted_proxy_hops).unwrap_or(0);
+
+        match (listen, circulars) {
+            (Some(listen), Some(circulars)) if errors.is_empty() => Ok(Config { listen, cors_origins, tls, log_level, data_dir, circulars, upi, drain_delay, trusted_proxy_hops, source, export_openapi }),
+            _ => Err(errors),
+        }
+    }
+
+    pub fn cors_layer(&self) -> CorsLayer {
+        if self.cors_origins.iter().any(|o| o == "*") {
+            return CorsLayer::permissive();
+        }
+        let origins = self.cors_origins.iter().map(|o| HeaderValue::from_str(o).expect("validated at startup"));
+        CorsLayer::new()
+            .allow_origin(AllowOrigin::list(origins))
+            .allow_methods([Method::GET, Method::POST, Method::PUT, Method::DELETE])
+            .allow_headers([header::AUTHORIZATION, header::CONTENT_TYPE, HeaderName::from_static("x-api-key")])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scratch_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("pratyaksh-config-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn cli(data_dir: &Path, args: &[&str]) -> Cli {
+        let data_dir = data_dir.to_str().unwrap();
+        Cli::try_parse_from(["pratyaksh_backend", "--data-dir", data_dir].iter().chain(args)).unwrap()
+    }
+
+    fn file(raw: &str) -> FileConfig {
+        toml::from_str(raw).unwrap()
+    }
+
+    #[test]
+    fn defaults_apply_without_file_or_flags() {
+        let dir = scratch_dir("defaults");
+        let config = Config::merge(cli(&dir, &[]), FileConfig::default(), None).unwrap();
+        assert_eq!(config.listen, DEFAULT_LISTEN.parse().unwrap());
+        assert_eq!((config.log_level.as_str(), config.drain_delay), (DEFAULT_LOG_LEVEL, Duration::from_secs(DEFAULT_DRAIN_DELAY_SECS)));
+        assert!(config.cors_origins.is_empty() && config.tls.is_none() && config.upi.is_none());
+        assert_eq!(config.trusted_proxy_hops, 0);
+        assert_eq!(config.circulars.version, CircularRegistry::bundled().version);
+    }
+
+    #[test]
+    fn flags_override_the_file() {
+        let dir = scratch_dir("flags");
+        let file = file("listen = \"127.0.0.1:9000\"\nlog_level = \"debug\"\ncors_origins = [\"https://app.example.com\"]");
+        let config = Config::merge(cli(&dir, &["--listen", "127.0.0.1:9100"]), file, None).unwrap();
+        assert_eq!(config.listen, "127.0.0.1:9100".parse().unwrap());
+        // Values without a flag still come from the file
+        assert_eq!(config.log_level, "debug");
+        assert_eq!(config.cors_origins, ["https://app.example.com"]);
+    }
+
+    // Mutates the environment under the shared lock; no other test reads PRATYAKSH_UPI_PAYEE_NAME
+    #[test]
+    fn environment_overrides_the_file_and_flags_override_the_environment() {
+        let _env = crate::testing::env_lock();
+        let dir = scratch_dir("env");
+        let upi = "[upi]\nvpa = \"pratyaksh@okaxis\"\npayee_name = \"File Payee\"";
+        std::env::set_var("PRATYAKSH_UPI_PAYEE_NAME", "Env Payee");
+        let from_env = Config::merge(cli(&dir, &[]), file(upi), None);
+        let from_flag = Config::merge(cli(&dir, &["--upi-payee-name", "Flag Payee"]), file(upi), None);
+        std::env::remove_var("PRATYAKSH_UPI_PAYEE_NAME");
+
+        assert_eq!(from_env.unwrap().upi.unwrap().payee_name, "Env Payee");
+        assert_eq!(from_flag.unwrap().upi.unwrap().payee_name, "Flag Payee");
+        assert_eq!(Config::merge(cli(&dir, &[]), file(upi), None).unwrap().upi.unwrap().payee_name, "File Payee");
+    }
+
+    #[test]
+    fn every_problem_is_reported_at_once() {
+        let dir = scratch_dir("errors");
+        let file = file("listen = \"nowhere\"\ncors_origins = [\"ftp://files.example.com\", \"*\"]\nlog_level = \"info,[\"\ndrain_delay = 1000\n[tls]\ncert = \"cert.pem\"\n[upi]\nvpa = \"pratyaksh\"");
+        let errors = Config::merge(cli(&dir, &[]), file, None).err().unwrap();
+        let fields: Vec<&str> = errors.iter().map(|e| e.split(':').next().unwrap()).collect();
+        assert_eq!(fields, ["listen", "cors_origins", "cors_origins", "tls", "log_level", "upi.vpa", "drain_delay"]);
+    }
+
+    // The longest drain must end before systemd gives up and sends SIGKILL
+    #[test]
+    fn drain_fits_inside_the_unit_stop_timeout() {
+        let unit = include_str!("../pratyaksh.example.service");
+        let stop_timeout: u64 = unit.lines().find_map(|l| l.strip_prefix("TimeoutStopSec=")).unwrap().parse().unwrap();
+        assert!(MAX_DRAIN_DELAY_SECS + crate::ops::DRAIN_TIMEOUT.as_secs() < stop_timeout);
+
+        let dir = scratch_dir("drain");
+        let at_max = Config::merge(cli(&dir, &["--drain-delay", "15"]), FileConfig::default(), None).unwrap();
+        assert_eq!(at_max.drain_delay, Duration::from_secs(MAX_DRAIN_DELAY_SECS));
+        let errors = Config::merge(cli(&dir, &["--drain-delay", "16"]), FileConfig::default(), None).err().unwrap();
+        assert_eq!(errors, ["drain_delay: 16s is longer than the 15s maximum"]);
+    }
+
+    #[test]
+    fn unknown_keys_in_the_file_are_rejected() {
+        assert!(toml::from_str::<FileConfig>("listn = \"0.0.0.0:8080\"").is_err());
+        assert!(toml::from_str::<FileConfig>("[tls]\ncertificate = \"cert.pem\"").is_err());
+    }
+
+    #[test]
+    fn invalid_circulars_file_is_a_config_error() {
+        let dir = scratch_dir("circulars");
+        std::fs::write(dir.join("circulars.json"), "{ not json").unwrap();
+        let errors = Config::merge(cli(&dir, &[]), FileConfig::default(), None).err().unwrap();
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].starts_with("circulars: "), "{}", errors[0]);
+    }
+
+    #[test]
+    fn unusable_tls_files_are_config_errors() {
+        let dir = scratch_dir("tls");
+        let (cert, key) = (dir.join("cert.pem"), dir.join("key.pem"));
+        std::fs::write(&cert, "not a certificate").unwrap();
+        std::fs::write(&key, "not a key").unwrap();
+        let args = ["--tls-cert", cert.to_str().unwrap(), "--tls-key", key.to_str().unwrap()];
+        let errors = Config::merge(cli(&dir, &args), FileConfig::default(), None).err().unwrap();
+        assert_eq!(errors, [format!("tls.cert: {} holds no PEM certificate", cert.display())]);
+
+        let missing = ["--tls-cert", "missing-cert.pem", "--tls-key", "missing-key.pem"];
+        let errors = Config::merge(cli(&dir, &missing), FileConfig::default(), None).err().unwrap();
+        assert_eq!(errors, ["tls.cert: missing-cert.pem does not exist", "tls.key: missing-key.pem does not exist"]);
+    }
+}
@@ -0,0 +1,133 @@
+use axum::{
+    extract::rejection::{JsonRejection, PathRejection, QueryRejection},
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::Serialize;
+use utoipa::ToSchema;
+
+pub use pratyaksh_core::errors::{check_amount, parse_date, FieldError};
+
+#[derive(Debug)]
+pub enum ApiError {
+    MalformedRequest(String),       // 400: request could not be decoded at all
+    Validation(Vec<FieldError>),    // 422: decoded, but values are invalid
+    Unauthorized(String),           // 401: missing, unknown or revoked API key
+    Forbidden(String),              // 403: key lacks the scope for this call
+    NotFound(String),               // 404: no stored record with that key
+    Conflict(String),               // 409: would break a uniqueness constraint
+    TooManyRequests(String),        // 429: the client hit a rate limit
+    Internal(String),               // 500: storage failure
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct InvalidParam {
+    name: String,
+    reason: String,
+}
+
+// RFC 7807 problem details
+#[derive(Serialize, ToSchema)]
+pub struct ProblemDetails {
+    #[serde(rename = "type")]
+    kind: &'static str,
+    title: &'static str,
+    status: u16,
+    detail: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    invalid_params: Vec<InvalidParam>,
+}
+
+fn problem(status: StatusCode, title: &'static str, detail: String) -> ProblemDetails {
+    ProblemDetails { kind: "about:blank", title, status: status.as_u16(), detail, invalid_params: Vec::new() }
+}
+
+impl From<QueryRejection> for ApiError {
+    fn from(rejection: QueryRejection) -> Self {
+        ApiError::MalformedRequest(rejection.body_text())
+    }
+}
+
+impl From<JsonRejection> for ApiError {
+    fn from(rejection: JsonRejection) -> Self {
+        ApiError::MalformedRequest(rejection.body_text())
+    }
+}
+
+impl From<PathRejection> for ApiError {
+    fn from(rejection: PathRejection) -> Self {
+        ApiError::MalformedRequest(rejection.body_text())
+    }
+}
+
+// Client-facing text for each constraint SQLite can report; the raw message names tables and columns
+const CONSTRAINT_MESSAGES: &[(&str, &str)] = &[
+    ("filings.srn", "A filing with this SRN already exists"),
+    ("companies.cin", "A company with this CIN already exists"),
+    ("firms.id", "A firm with this id already exists"),
+    ("payment_orders.txn_ref", "A payment order with this reference already exists"),
+    ("licenses.txn_id", "A license was already issued for this payment"),
+    ("FOREIGN KEY", "The record refers to a company or firm that does not exist"),
+];
+
+fn conflict_message(detail: &str) -> &'static str {
+    CONSTRAINT_MESSAGES.iter()
+        .find(|(constraint, _)| detail.contains(constraint))
+        .map_or("The request conflicts with an existing record", |(_, message)| message)
+}
+
+impl From<rusqlite::Error> for ApiError {
+    fn from(error: rusqlite::Error) -> Self {
+        match error {
+            rusqlite::Error::SqliteFailure(e, detail) if e.code == rusqlite::ErrorCode::ConstraintViolation => {
+                let detail = detail.unwrap_or_else(|| e.to_string());
+                tracing::warn!("Constraint violation: {}", detail);
+                ApiError::Conflict(conflict_message(&detail).to_string())
+            }
+            e => {
+                tracing::error!("Store error: {}", e);
+                ApiError::Internal("the request could not be completed".to_string())
+            }
+        }
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let (status, problem) = match self {
+            ApiError::MalformedRequest(detail) => (
+                StatusCode::BAD_REQUEST,
+                ProblemDetails {
+                    kind: "about:blank",
+                    title: "Malformed request",
+                    status: StatusCode::BAD_REQUEST.as_u16(),
+                    detail,
+                    invalid_params: Vec::new(),
+                },
+            ),
+            ApiError::Validation(errors) => (
+                StatusCode::UNPROCESSABLE_ENTITY,
+                ProblemDetails {
+                    kind: "about:blank",
+                    title: "Invalid request parameters",
+                    status: StatusCode::UNPROCESSABLE_ENTITY.as_u16(),
+                    detail: format!("{} field(s) failed validation", errors.len()),
+                    invalid_params: errors.iter().map(|e| InvalidParam { name: e.field().to_string(), reason: e.reason() }).collect(),
+                },
+            ),
+            ApiError::Unauthorized(detail) => {
+                let problem = problem(StatusCode::UNAUTHORIZED, "Unauthorized", detail);
+                let headers = [(header::CONTENT_TYPE, "application/problem+json"), (header::WWW_AUTHENTICATE, "Bearer")];
+                return (StatusCode::UNAUTHORIZED, headers, Json(problem)).into_response();
+            }
+            ApiError::Forbidden(detail) => (StatusCode::FORBIDDEN, problem(StatusCode::FORBIDDEN, "Forbidden", detail)),
+            ApiError::NotFound(detail) => (StatusCode::NOT_FOUND, problem(StatusCode::NOT_FOUND, "Not found", detail)),
+            ApiError::Conflict(detail) => (StatusCode::CONFLICT, problem(StatusCode::CONFLICT, "Conflict", detail)),
+            ApiError::TooManyRequests(detail) => (StatusCode::TOO_MANY_REQUESTS, problem(StatusCode::TOO_MANY_REQUESTS, "Too many requests", detail)),
+            ApiError::Internal(detail) => (StatusCode::INTERNAL_SERVER_ERROR, problem(StatusCode::INTERNAL_SERVER_ERROR, "Internal error", detail)),
+        };
+
+        (status, [(header::CONTENT_TYPE, "application/problem+json")], Json(problem)).into_response()
+    }
+}
@@ -0,0 +1,94 @@
+use axum::{
+    extract::{rejection::QueryRejection, Query, State},
+    Json,
+};
+
+use serde::Deserialize;
+use utoipa::IntoParams;
+
+use crate::errors::{parse_date, ApiError, FieldError};
+use crate::AppState;
+
+pub use pratyaksh_core::fees::*;
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct FeeParams {
+    pub form: String,
+    pub nominal_share_capital: Option<i64>, // Omit for companies without share capital
+    pub contribution: Option<i64>,          // LLP forms only: total partners' contribution
+    pub due_date: String,                   // YYYY-MM-DD, statutory due date
+    pub fy_end_date: Option<String>,        // YYYY-MM-DD, FY the filing is for; MCA extensions for that FY move the due date
+    pub filing_date: String,                // YYYY-MM-DD
+}
+
+impl FeeParams {
+    fn validate(self) -> Result<FeeQuery, ApiError> {
+        let mut errors = Vec::new();
+        if !FEE_FORMS.contains(&self.form.as_str()) {
+            errors.push(FieldError::UnsupportedForm { field: "form".to_string(), value: self.form.clone(), accepted: FEE_FORMS });
+        }
+        if self.nominal_share_capital.is_some_and(|c| c < 0) {
+            errors.push(FieldError::OutOfRange { field: "nominal_share_capital".to_string(), reason: "must not be negative".to_string() });
+        }
+        if self.contribution.is_some_and(|c| c < 0) {
+            errors.push(FieldError::OutOfRange { field: "contribution".to_string(), reason: "must not be negative".to_string() });
+        }
+        let due_date = parse_date("due_date", &self.due_date, &mut errors);
+        let fy_end_date = self.fy_end_date.as_deref().map(|raw| parse_date("fy_end_date", raw, &mut errors));
+        let filing_date = parse_date("filing_date", &self.filing_date, &mut errors);
+        if let (Some(Some(fy_end)), Some(due)) = (fy_end_date, due_date) {
+            if fy_end >= due {
+                errors.push(FieldError::OutOfRange { field: "fy_end_date".to_string(), reason: "must be before due_date".to_string() });
+            }
+        }
+
+        match (due_date, filing_date) {
+            (Some(due_date), Some(filing_date)) if errors.is_empty() => Ok(FeeQuery {
+                form: self.form,
+                nominal_share_capital: self.nominal_share_capital,
+                contribution: self.contribution,
+                due_date,
+                fy_end_date: fy_end_date.flatten(),
+                filing_date,
+            }),
+            _ => Err(ApiError::Validation(errors)),
+        }
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/compliance/fees",
+    tag = "compliance",
+    params(FeeParams),
+    responses(
+        (status = 200, body = FeeBreakdown),
+        (status = 400, description = "Malformed request", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 401, description = "Missing, unknown or revoked API key", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 422, description = "Invalid parameters", body = ProblemDetails, content_type = "application/problem+json"),
+    ),
+    security(("api_key" = []))
+)]
+pub async fn fee_schedule(State(state): State<AppState>, query: Result<Query<FeeParams>, QueryRejection>) -> Result<Json<FeeBreakdown>, ApiError> {
+    let Query(params) = query?;
+    let query = params.validate()?;
+    Ok(Json(compute_fees(&query, &state.circulars)))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::store::Scope;
+    use crate::testing::{key_for, request, send, state};
+    use axum::http::StatusCode;
+
+    #[tokio::test]
+    async fn bad_dates_are_invalid_params() {
+        let state = state();
+        let key = key_for(&state, "sharma", Scope::Read);
+        let (status, body) = send(&state, request("GET", "/api/v1/compliance/fees?form=AOC-4&due_date=2024-10-30&fy_end_date=2024-13-31&filing_date=tomorrow", Some(&key), None)).await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        let names: Vec<&str> = body["invalid_params"].as_array().unwrap().iter().map(|p| p["name"].as_str().unwrap()).collect();
+        assert_eq!(names, ["fy_end_date", "filing_date"]);
+    }
+}
@@ -0,0 +1,176 @@
+use axum::{
+    extract::{rejection::QueryRejection, Query},
+    Json,
+};
+use chrono::{Datelike, NaiveDate};
+use serde::Deserialize;
+use utoipa::IntoParams;
+
+use crate::errors::{parse_date, ApiError, FieldError};
+use pratyaksh_core::tools::Calculator;
+
+pub use pratyaksh_core::gst::*;
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct GstLateFeeParams {
+    pub return_type: String,
+    pub due_date: String,    // YYYY-MM-DD
+    pub filing_date: String, // YYYY-MM-DD
+    #[serde(default)]
+    pub nil_return: bool,
+    pub aggregate_turnover: Option<i64>, // Required for GSTR-9
+}
+
+impl GstLateFeeParams {
+    fn validate(self) -> Result<GstLateFeeQuery, ApiError> {
+        let mut errors = Vec::new();
+        let due_date = parse_date("due_date", &self.due_date, &mut errors);
+        let filing_date = parse_date("filing_date", &self.filing_date, &mut errors);
+        let (Some(due_date), Some(filing_date)) = (due_date, filing_date) else {
+            return Err(ApiError::Validation(errors));
+        };
+
+        let query = GstLateFeeQuery {
+            return_type: self.return_type,
+            due_date,
+            filing_date,
+            nil_return: self.nil_return,
+            aggregate_turnover: self.aggregate_turnover,
+        };
+        let errors = query.check();
+        if !errors.is_empty() {
+            return Err(ApiError::Validation(errors));
+        }
+        Ok(query)
+    }
+}
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct GstInterestParams {
+    pub tax_amount: i64,
+    pub due_date: String,     // YYYY-MM-DD
+    pub payment_date: String, // YYYY-MM-DD
+    #[serde(default)]
+    pub ground: InterestGround,
+}
+
+impl GstInterestParams {
+    fn validate(&self) -> Result<GstInterestQuery, ApiError> {
+        let mut errors = Vec::new();
+        if self.tax_amount < 0 {
+            errors.push(FieldError::OutOfRange { field: "tax_amount".to_string(), reason: "must not be negative".to_string() });
+        }
+        let due_date = parse_date("due_date", &self.due_date, &mut errors);
+        let payment_date = parse_date("payment_date", &self.payment_date, &mut errors);
+        match (due_date, payment_date) {
+            (Some(due_date), Some(payment_date)) if errors.is_empty() => Ok(GstInterestQuery { tax_amount: self.tax_amount, due_date, payment_date, ground: self.ground }),
+            _ => Err(ApiError::Validation(errors)),
+        }
+    }
+}
+
+fn parse_period(raw: &str, errors: &mut Vec<FieldError>) -> Option<NaiveDate> {
+    let parsed = NaiveDate::parse_from_str(&format!("{}-01", raw), "%Y-%m-%d").ok();
+    if parsed.is_none() {
+        errors.push(FieldError::OutOfRange { field: "period".to_string(), reason: format!("'{}' is not a valid YYYY-MM period", raw) });
+    }
+    parsed
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/gst/due-dates",
+    tag = "gst",
+    params(GstDueDateQuery),
+    responses(
+        (status = 200, body = GstDueDates),
+        (status = 400, description = "Malformed request", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 401, description = "Missing, unknown or revoked API key", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 422, description = "Invalid parameters", body = ProblemDetails, content_type = "application/problem+json"),
+    ),
+    security(("api_key" = []))
+)]
+pub async fn gst_due_dates(query: Result<Query<GstDueDateQuery>, QueryRejection>) -> Result<Json<GstDueDates>, ApiError> {
+    let Query(params) = query?;
+
+    let mut errors = Vec::new();
+    let period = parse_period(&params.period, &mut errors);
+    if params.scheme == FilingScheme::Qrmp {
+        if period.is_some_and(|p| p.month() % 3 != 0) {
+            errors.push(FieldError::OutOfRange { field: "period".to_string(), reason: "QRMP returns are filed for the quarter-end month".to_string() });
+        }
+        if params.state_group.is_none() {
+            errors.push(FieldError::OutOfRange { field: "state_group".to_string(), reason: "required under QRMP".to_string() });
+        }
+    }
+    if params.aggregate_turnover.is_some_and(|t| t < 0) {
+        errors.push(FieldError::OutOfRange { field: "aggregate_turnover".to_string(), reason: "must not be negative".to_string() });
+    }
+    let period = match period {
+        Some(p) if errors.is_empty() => p,
+        _ => return Err(ApiError::Validation(errors)),
+    };
+
+    let (returns, explanation) = due_dates(period, params.scheme, params.state_group, params.aggregate_turnover);
+    Ok(Json(GstDueDates { period: params.period, scheme: params.scheme, returns, explanation }))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/gst/late-fee",
+    tag = "gst",
+    params(GstLateFeeParams),
+    responses(
+        (status = 200, body = GstLateFee),
+        (status = 400, description = "Malformed request", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 401, description = "Missing, unknown or revoked API key", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 422, description = "Invalid parameters", body = ProblemDetails, content_type = "application/problem+json"),
+    ),
+    security(("api_key" = []))
+)]
+pub async fn gst_late_fee(query: Result<Query<GstLateFeeParams>, QueryRejection>) -> Result<Json<GstLateFee>, ApiError> {
+    let Query(params) = query?;
+    let query = params.validate()?;
+    Ok(Json(query.evaluate()))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/gst/interest",
+    tag = "gst",
+    params(GstInterestParams),
+    responses(
+        (status = 200, body = GstInterest),
+        (status = 400, description = "Malformed request", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 401, description = "Missing, unknown or revoked API key", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 422, description = "Invalid parameters", body = ProblemDetails, content_type = "application/problem+json"),
+    ),
+    security(("api_key" = []))
+)]
+pub async fn gst_interest(query: Result<Query<GstInterestParams>, QueryRejection>) -> Result<Json<GstInterest>, ApiError> {
+    let Query(params) = query?;
+    let q = params.validate()?;
+    Ok(Json(interest(q.tax_amount, q.ground, q.due_date, q.payment_date)))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::store::Scope;
+    use crate::testing::{key_for, request, send, state};
+    use axum::http::StatusCode;
+
+    #[tokio::test]
+    async fn bad_dates_are_invalid_params() {
+        let state = state();
+        let key = key_for(&state, "sharma", Scope::Read);
+        let (status, body) = send(&state, request("GET", "/api/v1/gst/late-fee?return_type=GSTR-3B&due_date=2024-06-20&filing_date=2024-06-31", Some(&key), None)).await;
+        assert_eq!((status, &body["invalid_params"][0]["name"]), (StatusCode::UNPROCESSABLE_ENTITY, &serde_json::json!("filing_date")));
+
+        let (status, body) = send(&state, request("GET", "/api/v1/gst/interest?tax_amount=-1&due_date=20-06-2024&payment_date=2024-07-20", Some(&key), None)).await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        let names: Vec<&str> = body["invalid_params"].as_array().unwrap().iter().map(|p| p["name"].as_str().unwrap()).collect();
+        assert_eq!(names, ["tax_amount", "due_date"]);
+    }
+}
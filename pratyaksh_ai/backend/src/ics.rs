@@ -0,0 +1,90 @@
+use axum::{
+    extract::{rejection::{JsonRejection, QueryRejection}, Query, State},
+    http::header,
+    response::{IntoResponse, Response},
+    Json,
+};
+use chrono::Utc;
+use serde::Deserialize;
+use utoipa::ToSchema;
+
+use crate::calendar::{build_calendar, CalendarParams};
+use crate::errors::ApiError;
+use crate::AppState;
+
+pub use pratyaksh_core::ics::*;
+
+#[derive(Deserialize, ToSchema)]
+pub struct BookCalendarRequest {
+    pub companies: Vec<CalendarParams>,
+}
+
+fn ics_response(filename: &str, body: String) -> Response {
+    (
+        [
+            (header::CONTENT_TYPE, "text/calendar; charset=utf-8".to_string()),
+            (header::CONTENT_DISPOSITION, format!("attachment; filename=\"{}\"", filename)),
+        ],
+        body,
+    ).into_response()
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/compliance/calendar.ics",
+    tag = "compliance",
+    params(CalendarParams),
+    responses(
+        (status = 200, description = "RFC 5545 calendar", body = String, content_type = "text/calendar"),
+        (status = 400, description = "Malformed request", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 401, description = "Missing, unknown or revoked API key", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 422, description = "Invalid parameters", body = ProblemDetails, content_type = "application/problem+json"),
+    ),
+    security(("api_key" = []))
+)]
+pub async fn company_calendar_ics(State(state): State<AppState>, query: Result<Query<CalendarParams>, QueryRejection>) -> Result<Response, ApiError> {
+    let Query(params) = query?;
+
+    let mut errors = Vec::new();
+    let query = match params.validate("", &mut errors) {
+        Some(query) if errors.is_empty() => query,
+        _ => return Err(ApiError::Validation(errors)),
+    };
+
+    let company = query.cin.clone().unwrap_or_else(|| "Company".to_string());
+    let events = calendar_events(&company, &build_calendar(&query, &state.circulars));
+    Ok(ics_response("compliance.ics", render(&format!("{} compliance", company), &events, Utc::now())))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/compliance/calendar.ics",
+    tag = "compliance",
+    request_body = BookCalendarRequest,
+    responses(
+        (status = 200, description = "RFC 5545 calendar", body = String, content_type = "text/calendar"),
+        (status = 400, description = "Malformed request", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 401, description = "Missing, unknown or revoked API key", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 422, description = "Invalid parameters", body = ProblemDetails, content_type = "application/problem+json"),
+    ),
+    security(("api_key" = []))
+)]
+pub async fn book_calendar_ics(State(state): State<AppState>, body: Result<Json<BookCalendarRequest>, JsonRejection>) -> Result<Response, ApiError> {
+    let Json(req) = body?;
+
+    let mut errors = Vec::new();
+    let companies: Vec<_> = req.companies.iter().enumerate()
+        .filter_map(|(i, company)| company.validate(&format!("companies[{}].", i), &mut errors))
+        .collect();
+    if !errors.is_empty() {
+        return Err(ApiError::Validation(errors));
+    }
+
+    let events: Vec<IcsEvent> = companies.iter().enumerate()
+        .flat_map(|(i, company)| {
+            let name = company.cin.clone().unwrap_or_else(|| format!("Company {}", i + 1));
+            calendar_events(&name, &build_calendar(company, &state.circulars))
+        })
+        .collect();
+    Ok(ics_response("client-book.ics", render("Client book compliance", &events, Utc::now())))
+}
@@ -0,0 +1,113 @@
+use axum::{extract::rejection::JsonRejection, Json};
+use chrono::{Datelike, NaiveDate};
+use serde::Deserialize;
+use utoipa::ToSchema;
+
+use crate::errors::{check_amount, parse_date, ApiError, FieldError};
+
+pub use pratyaksh_core::income_tax::*;
+
+#[derive(Deserialize, ToSchema)]
+pub struct TaxPaymentRequest {
+    pub date: String, // YYYY-MM-DD
+    pub amount: i64,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct InterestRequest {
+    pub fy_end_date: String,  // YYYY-MM-DD, e.g. 2024-03-31 for FY 2023-24
+    pub assessed_tax: i64,    // Tax on total income less TDS/TCS and reliefs
+    #[serde(default)]
+    pub advance_tax: Vec<TaxPaymentRequest>,
+    #[serde(default)]
+    pub self_assessment_tax: Vec<TaxPaymentRequest>, // Sec 140A, paid after the FY
+    pub return_due_date: Option<String>, // Defaults to 31 July of the AY
+    pub return_filing_date: String,
+    #[serde(default)]
+    pub presumptive: bool, // Sec 44AD / 44ADA: single instalment by 15 March
+}
+
+fn parse_payments(field: &str, raw: &[TaxPaymentRequest], errors: &mut Vec<FieldError>) -> Vec<TaxPayment> {
+    raw.iter().enumerate()
+        .filter_map(|(i, p)| {
+            check_amount(&format!("{}[{}].amount", field, i), p.amount, errors);
+            parse_date(&format!("{}[{}].date", field, i), &p.date, errors).map(|date| TaxPayment { date, amount: p.amount })
+        })
+        .collect()
+}
+
+impl InterestRequest {
+    fn validate(&self) -> Result<InterestInput, ApiError> {
+        let mut errors = Vec::new();
+
+        let fy_end = parse_date("fy_end_date", &self.fy_end_date, &mut errors);
+        if fy_end.is_some_and(|d| (d.month(), d.day()) != (3, 31)) {
+            errors.push(FieldError::OutOfRange { field: "fy_end_date".to_string(), reason: "the financial year ends on 31 March".to_string() });
+        }
+        check_amount("assessed_tax", self.assessed_tax, &mut errors);
+        let advance_tax = parse_payments("advance_tax", &self.advance_tax, &mut errors);
+        let self_assessment_tax = parse_payments("self_assessment_tax", &self.self_assessment_tax, &mut errors);
+        let due = match &self.return_due_date {
+            Some(raw) => parse_date("return_due_date", raw, &mut errors),
+            None => fy_end.map(|d| NaiveDate::from_ymd_opt(d.year(), 7, 31).unwrap()),
+        };
+        let filing_date = parse_date("return_filing_date", &self.return_filing_date, &mut errors);
+
+        match (fy_end, due, filing_date) {
+            (Some(fy_end), Some(return_due_date), Some(filing_date)) if errors.is_empty() => Ok(InterestInput {
+                fy_end,
+                assessed_tax: self.assessed_tax,
+                advance_tax,
+                self_assessment_tax,
+                return_due_date,
+                filing_date,
+                presumptive: self.presumptive,
+            }),
+            _ => Err(ApiError::Validation(errors)),
+        }
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/income-tax/interest",
+    tag = "income-tax",
+    request_body = InterestRequest,
+    responses(
+        (status = 200, body = IncomeTaxInterest),
+        (status = 400, description = "Malformed request", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 401, description = "Missing, unknown or revoked API key", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 422, description = "Invalid parameters", body = ProblemDetails, content_type = "application/problem+json"),
+    ),
+    security(("api_key" = []))
+)]
+pub async fn interest_234(body: Result<Json<InterestRequest>, JsonRejection>) -> Result<Json<IncomeTaxInterest>, ApiError> {
+    let Json(req) = body?;
+    let input = req.validate()?;
+    Ok(Json(compute_interest(&input)))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::store::Scope;
+    use crate::testing::{key_for, request, send, state};
+    use axum::http::StatusCode;
+    use serde_json::json;
+
+    // An assessed tax near 1e17 used to overflow the 234B/234C percentages and panic the handler
+    #[tokio::test]
+    async fn oversized_amounts_are_invalid_params() {
+        let state = state();
+        let key = key_for(&state, "sharma", Scope::Read);
+        let body = json!({
+            "fy_end_date": "2024-03-31",
+            "assessed_tax": 100_000_000_000_000_000i64,
+            "advance_tax": [{ "date": "2023-06-15", "amount": 5_000_000_000_000_000_000i64 }],
+            "return_filing_date": "2024-11-15",
+        });
+        let (status, body) = send(&state, request("POST", "/api/v1/income-tax/interest", Some(&key), Some(body))).await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        let names: Vec<&str> = body["invalid_params"].as_array().unwrap().iter().map(|p| p["name"].as_str().unwrap()).collect();
+        assert_eq!(names, ["assessed_tax", "advance_tax[0].amount"]);
+    }
+}
@@ -0,0 +1,355 @@
+use axum::{
+    extract::{rejection::JsonRejection, State},
+    http::StatusCode,
+    Json,
+};
+use chrono::{Months, Utc};
+use ed25519_dalek::SigningKey;
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use utoipa::ToSchema;
+
+use crate::errors::{ApiError, FieldError};
+use crate::payments::find_plan;
+use crate::store::{License, PaymentOrder};
+use crate::AppState;
+
+pub use pratyaksh_core::license::*;
+
+// Signed offline licenses for the desktop client, issued by the operator when a payment is approved.
+// Desktop builds embed the public half of the Ed25519 key. Production keys are provisioned out of band
+// and named by $PRATYAKSH_LICENSE_KEY; only development setups get one generated in the data dir.
+// A payment licenses once: the license is tied to the backend order behind txn_id, which fixes the plan.
+// Repeating the same approval returns that license again, so a caller that failed after issuing can retry.
+
+pub const SIGNING_KEY_PATH_ENV: &str = "PRATYAKSH_LICENSE_KEY";
+const SIGNING_KEY_FILE: &str = "license_signing.key";
+const DEFAULT_MONTHS: u32 = 1; // Plans are billed monthly
+const MAX_MONTHS: u32 = 36;
+
+#[derive(Deserialize, ToSchema)]
+pub struct NewLicense {
+    pub user_id: String,
+    pub email: String,
+    pub plan: Option<String>,   // Plan id or name; required without txn_id, otherwise must match the order if given
+    pub device_id: String,      // As shown on the desktop activation screen
+    pub months: Option<u32>,    // Validity from today, 1 to 36; defaults to 1. With txn_id, the paid plan's period
+    pub txn_id: Option<String>, // Payment order being approved; at most one license per order
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct IssuedLicense {
+    #[serde(flatten)]
+    pub license: License,
+    pub file: LicenseFile, // Save as license.json next to the desktop app
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct LicensePublicKey {
+    pub algorithm: &'static str,
+    pub public_key: String, // Hex; build the desktop with PRATYAKSH_LICENSE_PUBLIC_KEY set to this
+}
+
+fn read_signing_key(path: &Path) -> std::io::Result<SigningKey> {
+    let raw = std::fs::read_to_string(path)?;
+    let seed: [u8; 32] = hex::decode(raw.trim()).ok()
+        .and_then(|b| b.try_into().ok())
+        .ok_or_else(|| std::io::Error::new(std::io::ErrorKind::InvalidData, format!("{} is not a 32-byte hex seed", path.display())))?;
+    Ok(SigningKey::from_bytes(&seed))
+}
+
+// Reads the hex seed at $PRATYAKSH_LICENSE_KEY, which must exist. Without it, falls back to
+// <data_dir>/license_signing.key and generates that on first use, for development only.
+pub fn load_signing_key(data_dir: &Path) -> std::io::Result<SigningKey> {
+    if let Ok(path) = std::env::var(SIGNING_KEY_PATH_ENV) {
+        let path = PathBuf::from(path);
+        return read_signing_key(&path).map_err(|e| std::io::Error::new(e.kind(), format!("{} ({}): {}", path.display(), SIGNING_KEY_PATH_ENV, e)));
+    }
+    let path = data_dir.join(SIGNING_KEY_FILE);
+    tracing::warn!("{} not set, using the development license key {}; production keys must come from {}", SIGNING_KEY_PATH_ENV, path.display(), SIGNING_KEY_PATH_ENV);
+    match read_signing_key(&path) {
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            let mut seed = [0u8; 32];
+            getrandom::getrandom(&mut seed).expect("OS random source unavailable");
+            let mut options = std::fs::OpenOptions::new();
+            options.write(true).create_new(true);
+            #[cfg(unix)]
+            std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+            options.open(&path)?.write_all(hex::encode(seed).as_bytes())?;
+            tracing::warn!("Created development license key {}; licenses it signs only verify on builds embedding its public key", path.display());
+            Ok(SigningKey::from_bytes(&seed))
+        }
+        loaded => loaded,
+    }
+}
+
+fn generate_license_id() -> String {
+    let mut bytes = [0u8; 8];
+    getrandom::getrandom(&mut bytes).expect("OS random source unavailable");
+    format!("lic_{}", hex::encode(bytes))
+}
+
+// The order behind a payment decides the plan and the period it buys; the request may only repeat them
+fn order_plan(txn_id: &str, order: Option<PaymentOrder>, user_id: &str, requested: Option<&str>, months: Option<u32>) -> Result<(String, u32), ApiError> {
+    let invalid = |field: &str, reason: String| ApiError::Validation(vec![FieldError::OutOfRange { field: field.to_string(), reason }]);
+    let order = order.ok_or_else(|| invalid("txn_id", format!("no payment order {}", txn_id)))?;
+    if order.user_id != user_id {
+        return Err(invalid("user_id", format!("payment order {} belongs to another user", txn_id)));
+    }
+    let paid = find_plan(&order.plan);
+    let paid_name = paid.map(|p| p.name).unwrap_or(&order.plan);
+    if let Some(requested) = requested {
+        if find_plan(requested).map(|p| p.id) != Some(order.plan.as_str()) {
+            return Err(invalid("plan", format!("payment order {} paid for {}, not {}", txn_id, paid_name, requested)));
+        }
+    }
+    let paid_months = paid.map_or(DEFAULT_MONTHS, |p| p.months);
+    if months.is_some_and(|m| m != paid_months) {
+        return Err(invalid("months", format!("payment order {} paid for {} month(s)", txn_id, paid_months)));
+    }
+    Ok((paid_name.to_string(), paid_months))
+}
+
+fn required(field: &str, value: &str, errors: &mut Vec<FieldError>) {
+    if value.trim().is_empty() {
+        errors.push(FieldError::OutOfRange { field: field.to_string(), reason: "must not be empty".to_string() });
+    }
+}
+
+// LICENSES
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/licenses",
+    tag = "licenses",
+    request_body = NewLicense,
+    responses(
+        (status = 200, description = "The license already issued for this payment, user and device, returned again", body = IssuedLicense),
+        (status = 201, body = IssuedLicense),
+        (status = 400, description = "Malformed request", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 401, description = "Missing, unknown or revoked API key", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 409, description = "The payment already licensed another user or device", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 422, description = "Invalid parameters, or the payment order does not match", body = ProblemDetails, content_type = "application/problem+json"),
+    ),
+    security(("admin_token" = []))
+)]
+pub async fn issue_license(State(state): State<AppState>, body: Result<Json<NewLicense>, JsonRejection>) -> Result<(StatusCode, Json<IssuedLicense>), ApiError> {
+    let Json(req) = body?;
+
+    let mut errors = Vec::new();
+    required("user_id", &req.user_id, &mut errors);
+    required("device_id", &req.device_id, &mut errors);
+    let txn_id = req.txn_id.as_deref().map(str::trim).filter(|t| !t.is_empty()).map(str::to_string);
+    let plan = req.plan.as_deref().map(str::trim).filter(|p| !p.is_empty()).map(str::to_string);
+    if txn_id.is_none() && plan.is_none() {
+        errors.push(FieldError::OutOfRange { field: "plan".to_string(), reason: "must not be empty without txn_id".to_string() });
+    }
+    if !req.email.contains('@') {
+        errors.push(FieldError::OutOfRange { field: "email".to_string(), reason: "must be an email address".to_string() });
+    }
+    if !(1..=MAX_MONTHS).contains(&req.months.unwrap_or(DEFAULT_MONTHS)) {
+        errors.push(FieldError::OutOfRange { field: "months".to_string(), reason: format!("must be between 1 and {}", MAX_MONTHS) });
+    }
+    if !errors.is_empty() {
+        return Err(ApiError::Validation(errors));
+    }
+
+    let user_id = req.user_id.trim().to_string();
+    let (plan, months) = match &txn_id {
+        Some(txn_id) => {
+            let order = state.store.run({
+                let txn_id = txn_id.clone();
+                move |s| s.order(&txn_id)
+            }).await?;
+            order_plan(txn_id, order, &user_id, plan.as_deref(), req.months)?
+        }
+        None => (plan.unwrap_or_default(), req.months.unwrap_or(DEFAULT_MONTHS)),
+    };
+
+    let issued_at = Utc::now();
+    let claims = LicenseClaims {
+        license_id: generate_license_id(),
+        user_id,
+        email: req.email.trim().to_string(),
+        plan,
+        device_id: normalize_device_id(&req.device_id),
+        issued_at,
+        expires_on: issued_at.date_naive() + Months::new(months),
+    };
+    let license = License { claims, txn_id };
+    let (license, status) = state.store.run(move |s| -> Result<_, ApiError> {
+        if let Some(txn_id) = &license.txn_id {
+            if let Some(existing) = s.license_for_txn(txn_id)? {
+                let (old, new) = (&existing.claims, &license.claims);
+                if old.user_id != new.user_id || old.device_id != new.device_id {
+                    return Err(ApiError::Conflict(format!("License {} was already issued for payment {} to another user or device", old.license_id, txn_id)));
+                }
+                return Ok((existing, StatusCode::OK));
+            }
+        }
+        s.insert_license(&license)?;
+        Ok((license, StatusCode::CREATED))
+    }).await?;
+    // Ed25519 signatures are deterministic, so a repeated approval gets the same file back
+    let file = sign(&license.claims, &state.license_key);
+    if status == StatusCode::CREATED {
+        tracing::info!("Issued license {} ({}) to {} until {}", license.claims.license_id, license.claims.plan, license.claims.email, license.claims.expires_on);
+    } else {
+        tracing::info!("Returned license {} again for payment {}", license.claims.license_id, license.txn_id.as_deref().unwrap_or_default());
+    }
+
+    Ok((status, Json(IssuedLicense { license, file })))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/licenses",
+    tag = "licenses",
+    responses(
+        (status = 200, body = Vec<License>),
+        (status = 401, description = "Missing, unknown or revoked API key", body = ProblemDetails, content_type = "application/problem+json"),
+    ),
+    security(("admin_token" = []))
+)]
+pub async fn list_licenses(State(state): State<AppState>) -> Result<Json<Vec<License>>, ApiError> {
+    Ok(Json(state.store.run(|s| s.licenses()).await?))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/license/public-key",
+    tag = "licenses",
+    responses((status = 200, body = LicensePublicKey))
+)]
+pub async fn public_key(State(state): State<AppState>) -> Json<LicensePublicKey> {
+    Json(LicensePublicKey { algorithm: "Ed25519", public_key: public_key_hex(&state.license_key.verifying_key()) })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{request, send, state, ADMIN_TOKEN};
+    use serde_json::{json, Value};
+
+    const TXN: &str = "PRK0000000000A1";
+
+    fn paid_state() -> AppState {
+        let state = state();
+        state.store.insert_order(TXN, "scale", 239_900, "u1").unwrap();
+        state
+    }
+
+    fn license(plan: Option<&str>, txn_id: Option<&str>) -> Value {
+        json!({ "user_id": "u1", "email": "ca@example.in", "plan": plan, "device_id": "prk-dev-1", "txn_id": txn_id })
+    }
+
+    async fn issue(state: &AppState, body: Value) -> (StatusCode, Value) {
+        send(state, request("POST", "/api/v1/admin/licenses", Some(ADMIN_TOKEN), Some(body))).await
+    }
+
+    fn invalid_fields(body: &Value) -> Vec<&str> {
+        body["invalid_params"].as_array().unwrap().iter().map(|p| p["name"].as_str().unwrap()).collect()
+    }
+
+    #[tokio::test]
+    async fn plan_comes_from_the_payment_order() {
+        let state = paid_state();
+        let (status, body) = issue(&state, license(None, Some(TXN))).await;
+        assert_eq!(status, StatusCode::CREATED, "{}", body);
+        assert_eq!((body["plan"].as_str(), body["txn_id"].as_str()), (Some("Scale"), Some(TXN)));
+        let expected = Utc::now().date_naive() + Months::new(find_plan("scale").unwrap().months);
+        assert_eq!(body["expires_on"], expected.to_string());
+
+        let file = body["file"].to_string();
+        let claims = verify(&file, &state.license_key.verifying_key(), "PRK-DEV-1").unwrap();
+        assert_eq!((claims.plan.as_str(), claims.device_id.as_str()), ("Scale", "PRK-DEV-1"));
+    }
+
+    // The console signs before it marks the request approved; if that write fails, the retry
+    // must get the same license back rather than a conflict
+    #[tokio::test]
+    async fn retried_approval_returns_the_same_license() {
+        let state = paid_state();
+        let (status, first) = issue(&state, license(Some("scale"), Some(TXN))).await;
+        assert_eq!(status, StatusCode::CREATED);
+        let (status, again) = issue(&state, license(None, Some(TXN))).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!((&again["license_id"], &again["expires_on"]), (&first["license_id"], &first["expires_on"]));
+        assert_eq!(again["file"], first["file"]);
+        assert!(verify(&again["file"].to_string(), &state.license_key.verifying_key(), "PRK-DEV-1").is_ok());
+        assert_eq!(state.store.licenses().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn one_payment_licenses_one_device() {
+        let state = paid_state();
+        let (status, _) = issue(&state, license(Some("scale"), Some(TXN))).await;
+        assert_eq!(status, StatusCode::CREATED);
+        let mut other_device = license(Some("scale"), Some(TXN));
+        other_device["device_id"] = json!("prk-dev-2");
+        let (status, body) = issue(&state, other_device).await;
+        assert_eq!(status, StatusCode::CONFLICT);
+        assert!(body["detail"].as_str().unwrap().contains(&format!("already issued for payment {}", TXN)));
+        assert_eq!(state.store.licenses().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn order_must_match_the_request() {
+        let state = paid_state();
+        // The website request says Enterprise, the order paid for Scale
+        let (status, body) = issue(&state, license(Some("Enterprise"), Some(TXN))).await;
+        assert_eq!((status, invalid_fields(&body)), (StatusCode::UNPROCESSABLE_ENTITY, vec!["plan"]));
+
+        let mut other_user = license(None, Some(TXN));
+        other_user["user_id"] = json!("u2");
+        let (status, body) = issue(&state, other_user).await;
+        assert_eq!((status, invalid_fields(&body)), (StatusCode::UNPROCESSABLE_ENTITY, vec!["user_id"]));
+
+        // Scale buys one month; the approval cannot stretch it
+        let mut longer = license(None, Some(TXN));
+        longer["months"] = json!(36);
+        let (status, body) = issue(&state, longer).await;
+        assert_eq!((status, invalid_fields(&body)), (StatusCode::UNPROCESSABLE_ENTITY, vec!["months"]));
+
+        let (status, body) = issue(&state, license(Some("scale"), Some("PRK00000000FFFF"))).await;
+        assert_eq!((status, invalid_fields(&body)), (StatusCode::UNPROCESSABLE_ENTITY, vec!["txn_id"]));
+        assert!(state.store.licenses().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn requests_are_validated() {
+        let state = state();
+        let (status, body) = issue(&state, json!({ "user_id": " ", "email": "nobody", "device_id": "", "months": 37 })).await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(invalid_fields(&body), ["user_id", "device_id", "plan", "email", "months"]);
+
+        // A complimentary license without a payment names its plan
+        let (status, body) = issue(&state, license(Some("Launch"), None)).await;
+        assert_eq!((status, body["plan"].as_str()), (StatusCode::CREATED, Some("Launch")));
+        let (status, _) = send(&state, request("POST", "/api/v1/admin/licenses", Some("pk_not_admin"), Some(license(Some("Launch"), None)))).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+    }
+
+    // Sequential in one test: both cases depend on whether $PRATYAKSH_LICENSE_KEY is set
+    #[test]
+    fn signing_key_is_generated_only_for_development() {
+        let _env = crate::testing::env_lock();
+        let dir = std::env::temp_dir().join(format!("pratyaksh-licenses-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let generated = load_signing_key(&dir).unwrap();
+        assert_eq!(load_signing_key(&dir).unwrap().to_bytes(), generated.to_bytes());
+
+        let production = dir.join("production.key");
+        std::env::set_var(SIGNING_KEY_PATH_ENV, &production);
+        let missing = load_signing_key(&dir);
+        std::fs::write(&production, hex::encode([9u8; 32])).unwrap();
+        let provisioned = load_signing_key(&dir);
+        std::env::remove_var(SIGNING_KEY_PATH_ENV);
+
+        assert_eq!(missing.unwrap_err().kind(), std::io::ErrorKind::NotFound);
+        assert_eq!(provisioned.unwrap().to_bytes(), [9u8; 32]);
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
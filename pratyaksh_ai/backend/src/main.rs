@@ -1,84 +1,342 @@
+mod auth;
+mod calendar;
+mod circulars;
+mod companies;
+mod config;
+mod errors;
+mod fees;
+mod gst;
+mod ics;
+mod income_tax;
+mod licenses;
+mod openapi;
+mod ops;
+mod payments;
+mod portfolio;
+mod reminders;
+mod store;
+mod tds;
+mod tools;
+
 use axum::{
-    routing::{get, post},
+    middleware,
+    routing::{delete, get, post, put},
     Router,
     Json,
-    extract::Query,
+    extract::{rejection::QueryRejection, Query, State},
 };
-use serde::{Deserialize, Serialize};
-use chrono::{NaiveDate, Duration, Utc, Datelike};
+use serde::Deserialize;
+use utoipa::IntoParams;
+use chrono::Utc;
 use std::net::SocketAddr;
-use tower_http::cors::CorsLayer;
-
-#[derive(Serialize)]
-struct ComplianceRisk {
-    agm_due_date: NaiveDate,
-    filing_due_date: NaiveDate,
-    penalty_estimate: i32,
-    risk_level: String,
-    act_section: String,
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+use calendar::{CompanyProfile, CompanyType};
+use errors::{parse_date, ApiError};
+use pratyaksh_core::risk::{calculate_risk, check_form, check_share_capital, ComplianceRisk, RiskInput};
+use store::{Caller, Store};
+use axum_server::{tls_rustls::RustlsConfig, Handle};
+use tracing_subscriber::EnvFilter;
+
+#[derive(Clone)]
+struct AppState {
+    circulars: Arc<circulars::CircularRegistry>,
+    store: Store,
+    admin_token_hash: Option<Arc<String>>, // SHA-256 of $PRATYAKSH_ADMIN_TOKEN; None disables the admin API
+    license_key: Arc<ed25519_dalek::SigningKey>,
+    upi: Option<Arc<config::UpiConfig>>, // None disables payment orders
+    order_limiter: Arc<payments::OrderLimiter>,
+    metrics: Arc<ops::Metrics>,
+    draining: Arc<AtomicBool>, // Set on SIGTERM so /readyz takes the instance out of rotation
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 struct RiskQuery {
     fy_end_date: String, // YYYY-MM-DD
-    form_type: String,   // "AOC-4" or "MGT-7"
+    form_type: String,   // One of ACCEPTED_FORMS
+    as_of: Option<String>, // YYYY-MM-DD, defaults to today
+    company_type: Option<CompanyType>,
+    first_fy: Option<bool>,
+    agm_extension_months: Option<u32>,
+    nominal_share_capital: Option<i64>, // Sets the normal fee slab; omit for companies without share capital
+    cin: Option<String>, // Looks up the stored filing status of a registered company
 }
 
-// REAL LOGIC ENGINE
-fn calculate_risk(fy_end: NaiveDate, form_type: &str) -> ComplianceRisk {
-    // Sec 96: AGM within 6 months
-    let agm_deadline = fy_end + Duration::days(180);
+impl RiskQuery {
+    fn validate(&self) -> Result<RiskInput, ApiError> {
+        let mut errors = Vec::new();
 
-    // Sec 137 vs Sec 92
-    let (filing_deadline, section) = match form_type {
-        "MGT-7" => (agm_deadline + Duration::days(60), "Sec 92"),
-        _ => (agm_deadline + Duration::days(30), "Sec 137"),
-    };
-
-    let today = Utc::now().date_naive();
-    let mut penalty = 0;
-    let mut risk = "SAFE".to_string();
-
-    if today > filing_deadline {
-        let days_late = (today - filing_deadline).num_days();
-        
-        // Companies (Registration Offices and Fees) Rules, 2014
-        let factor = match days_late {
-            0..=30 => 2,
-            31..=60 => 4,
-            61..=90 => 6,
-            91..=180 => 10,
-            _ => 12,
+        let fy_end = parse_date("fy_end_date", &self.fy_end_date, &mut errors);
+        let as_of = match &self.as_of {
+            Some(raw) => parse_date("as_of", raw, &mut errors),
+            None => Some(Utc::now().date_naive()),
+        };
+        let profile = CompanyProfile {
+            company_type: self.company_type.unwrap_or_default(),
+            first_fy: self.first_fy.unwrap_or(false),
+            agm_extension_months: self.agm_extension_months.unwrap_or(0),
         };
-        penalty = 300 * factor;
-        risk = if days_late > 60 { "CRITICAL" } else { "MODERATE" }.to_string();
+        profile.check("", &mut errors);
+        check_form("form_type", &self.form_type, &profile, &mut errors);
+        check_share_capital("nominal_share_capital", self.nominal_share_capital, &mut errors);
+
+        match (fy_end, as_of) {
+            (Some(fy_end), Some(as_of)) if errors.is_empty() => Ok(RiskInput { fy_end, form_type: self.form_type.clone(), as_of, profile, share_capital: self.nominal_share_capital, filing: None }),
+            _ => Err(ApiError::Validation(errors)),
+        }
     }
+}
 
-    ComplianceRisk {
-        agm_due_date: agm_deadline,
-        filing_due_date: filing_deadline,
-        penalty_estimate: penalty,
-        risk_level: risk,
-        act_section: section.to_string(),
+#[utoipa::path(
+    get,
+    path = "/api/v1/compliance/analyze",
+    tag = "compliance",
+    params(RiskQuery),
+    responses(
+        (status = 200, body = ComplianceRisk),
+        (status = 400, description = "Malformed request", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 401, description = "Missing, unknown or revoked API key", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 422, description = "Invalid parameters", body = ProblemDetails, content_type = "application/problem+json"),
+    ),
+    security(("api_key" = []))
+)]
+async fn analyze_risk(State(state): State<AppState>, caller: Caller, query: Result<Query<RiskQuery>, QueryRejection>) -> Result<Json<ComplianceRisk>, ApiError> {
+    let Query(params) = query?;
+    let mut input = params.validate()?;
+    if let Some(cin) = params.cin.clone() {
+        let (form, fy_end) = (input.form_type.clone(), input.fy_end);
+        input.filing = state.store.for_firm(&caller.firm_id).run(move |s| s.filing_for(&cin, &form, fy_end)).await?;
     }
+    Ok(Json(calculate_risk(&input, &state.circulars)))
 }
 
-async fn analyze_risk(Query(params): Query<RiskQuery>) -> Json<ComplianceRisk> {
-    let fy_date = NaiveDate::parse_from_str(&params.fy_end_date, "%Y-%m-%d")
-        .unwrap_or_else(|_| Utc::now().date_naive());
-    Json(calculate_risk(fy_date, &params.form_type))
+fn router(state: AppState) -> Router {
+    // Firm-scoped API: every call needs an API key
+    let api = Router::new()
+        .route("/api/v1/compliance/analyze", get(analyze_risk))
+        .route("/api/v1/compliance/calendar", get(calendar::compliance_calendar))
+        .route("/api/v1/compliance/calendar.ics", get(ics::company_calendar_ics).post(ics::book_calendar_ics))
+        .route("/api/v1/compliance/fees", get(fees::fee_schedule))
+        .route("/api/v1/compliance/portfolio", post(portfolio::analyze_portfolio))
+        .route("/api/v1/companies", get(companies::list_companies).post(companies::create_company))
+        .route("/api/v1/companies/:cin", get(companies::get_company).put(companies::update_company).delete(companies::delete_company))
+        .route("/api/v1/companies/:cin/filings", get(companies::list_filings).post(companies::create_filing))
+        .route("/api/v1/companies/:cin/filings/:id", put(companies::update_filing).delete(companies::delete_filing))
+        .route("/api/v1/companies/:cin/risk", post(companies::assess_risk))
+        .route("/api/v1/companies/:cin/risk-snapshots", get(companies::list_snapshots))
+        .route("/api/v1/gst/due-dates", get(gst::gst_due_dates))
+        .route("/api/v1/gst/late-fee", get(gst::gst_late_fee))
+        .route("/api/v1/gst/interest", get(gst::gst_interest))
+        .route("/api/v1/income-tax/interest", post(income_tax::interest_234))
+        .route("/api/v1/tds/defaults", post(tds::tds_defaults))
+        .route("/api/v1/tools/gst-mismatch", post(tools::gst_mismatch))
+        .route("/api/v1/tools/sft-mismatch", post(tools::sft_mismatch))
+        .route("/api/v1/tools/tds-shortfall", post(tools::tds_shortfall))
+        .route("/api/v1/tools/mca-notice", post(tools::mca_notice))
+        .route("/api/v1/tools/board-risk", post(tools::board_risk))
+        .route("/api/v1/tools/trust-score", post(tools::trust_score))
+        .route("/api/v1/tools/msme-interest", post(tools::msme_interest))
+        .route("/api/v1/tools/gratuity", post(tools::gratuity))
+        .route("/api/v1/tools/tax-regime", post(tools::tax_regime))
+        .route("/api/v1/tools/crypto-tax", post(tools::crypto_tax))
+        .route("/api/v1/tools/hra", post(tools::hra))
+        .route("/api/v1/tools/pmla", post(tools::pmla))
+        .route("/api/v1/tools/angel-tax", post(tools::angel_tax))
+        .route("/api/v1/tools/buyback-tax", post(tools::buyback_tax))
+        .route("/api/v1/tools/capital-gains", post(tools::capital_gains))
+        .route("/api/v1/tools/shell-risk", post(tools::shell_risk))
+        .route("/api/v1/tools/esg", post(tools::esg))
+        .route("/api/v1/tools/udin", post(tools::udin))
+        .route("/api/v1/tools/audit-rotation", post(tools::audit_rotation))
+        .route("/api/v1/tools/net-worth", post(tools::net_worth))
+        .route("/api/v1/tools/export-realisation", post(tools::export_realisation))
+        .route("/api/v1/tools/partner-dissolution", post(tools::partner_dissolution))
+        .route("/api/v1/tools/lease", post(tools::lease))
+        .route("/api/v1/tools/depreciation", post(tools::depreciation))
+        .route("/api/v1/tools/emi", post(tools::emi))
+        .route("/api/v1/tools/burn-rate", post(tools::burn_rate))
+        .route("/api/v1/tools/simple-interest", post(tools::simple_interest))
+        .route("/api/v1/tools/cagr", post(tools::cagr))
+        .route("/api/v1/tools/break-even", post(tools::break_even))
+        .route_layer(middleware::from_fn_with_state(state.clone(), auth::require_key));
+
+    // Operator API for onboarding firms, issuing or revoking their keys and issuing desktop licenses
+    let admin = Router::new()
+        .route("/api/v1/admin/firms", get(auth::list_firms).post(auth::create_firm))
+        .route("/api/v1/admin/firms/:firm/keys", get(auth::list_keys).post(auth::issue_key))
+        .route("/api/v1/admin/firms/:firm/keys/:id", delete(auth::revoke_key))
+        .route("/api/v1/admin/licenses", get(licenses::list_licenses).post(licenses::issue_license))
+        .route_layer(middleware::from_fn_with_state(state.clone(), auth::require_admin));
+
+    let public = Router::new()
+        .route("/api/v1/openapi.json", get(openapi::openapi_json))
+        .route("/api/v1/license/public-key", get(licenses::public_key))
+        .route("/api/v1/plans", get(payments::list_plans))
+        .route("/api/v1/payments/orders", post(payments::create_order))
+        .route("/api/v1/payments/orders/:txn_ref", get(payments::get_order))
+        .route("/api/v1/payments/orders/:txn_ref/qr.png", get(payments::order_qr))
+        .route("/healthz", get(ops::healthz))
+        .route("/readyz", get(ops::readyz))
+        .route("/metrics", get(ops::metrics));
+
+    api.merge(admin).merge(public)
+        .layer(middleware::from_fn_with_state(state.clone(), ops::track))
+        .with_state(state)
 }
 
 #[tokio::main]
 async fn main() {
-    tracing_subscriber::fmt::init();
+    let config = match config::Config::load() {
+        Ok(config) => config,
+        Err(errors) => {
+            eprintln!("Invalid configuration:");
+            for e in errors {
+                eprintln!("  - {}", e);
+            }
+            std::process::exit(2);
+        }
+    };
+    if config.export_openapi {
+        println!("{}", openapi::document().to_pretty_json().expect("OpenAPI document serializes"));
+        return;
+    }
+    tracing_subscriber::fmt().with_env_filter(EnvFilter::new(&config.log_level)).init();
+    match &config.source {
+        Some(path) => tracing::info!("Loaded config from {}", path.display()),
+        None => tracing::info!("No config file, using defaults, environment and flags"),
+    }
 
-    let app = Router::new()
-        .route("/api/v1/compliance/analyze", get(analyze_risk))
-        .layer(CorsLayer::permissive());
+    let circulars = config.circulars.clone();
+    tracing::info!("Loaded circular registry v{} ({} entries)", circulars.version, circulars.circulars.len());
+    let store = Store::open(&config.data_dir).unwrap_or_else(|e| {
+        eprintln!("Could not open the company store: {}", e);
+        std::process::exit(2);
+    });
+    let admin_token_hash = std::env::var(auth::ADMIN_TOKEN_ENV).ok()
+        .filter(|t| !t.is_empty())
+        .map(|t| Arc::new(auth::hash_key(&t)));
+    if admin_token_hash.is_none() {
+        tracing::warn!("{} not set, admin API disabled", auth::ADMIN_TOKEN_ENV);
+    }
+    let license_key = licenses::load_signing_key(&config.data_dir).unwrap_or_else(|e| {
+        eprintln!("Could not load the license signing key: {}", e);
+        std::process::exit(2);
+    });
+    tracing::info!("License public key {}", licenses::public_key_hex(&license_key.verifying_key()));
+    let state = AppState {
+        circulars,
+        store,
+        admin_token_hash,
+        license_key: Arc::new(license_key),
+        upi: config.upi.clone().map(Arc::new),
+        order_limiter: Arc::new(payments::OrderLimiter::new(config.trusted_proxy_hops)),
+        metrics: Arc::new(ops::Metrics::new()),
+        draining: Arc::new(AtomicBool::new(false)),
+    };
+
+    match reminders::ReminderConfig::load(&config.data_dir) {
+        Ok(Some(config)) => {
+            tracing::info!("Reminder scheduler: {} companies, lead days {:?}, daily at {}", config.companies.len(), config.lead_days, config.run_at);
+            reminders::ReminderScheduler::new(config, state.circulars.clone()).spawn();
+        }
+        Ok(None) => tracing::info!("No reminder config found, scheduler disabled"),
+        Err(errors) => {
+            eprintln!("Invalid reminder configuration:");
+            for e in errors {
+                eprintln!("  - {}", e);
+            }
+            std::process::exit(2);
+        }
+    }
 
-    let addr = SocketAddr::from(([0, 0, 0, 0], 8080));
-    println!("🚀 Backend listening on {}", addr);
-    let listener = tokio::net::TcpListener::bind(addr).await.unwrap();
-    axum::serve(listener, app).await.unwrap();
-}
\ No newline at end of file
+    let app = router(state.clone()).layer(config.cors_layer());
+
+    // On SIGTERM stop accepting connections and let in-flight requests finish
+    let handle = Handle::new();
+    let drain = handle.clone();
+    let drain_delay = config.drain_delay;
+    tokio::spawn(async move {
+        ops::shutdown_signal(state.draining.clone(), drain_delay).await;
+        drain.graceful_shutdown(Some(ops::DRAIN_TIMEOUT));
+    });
+
+    match &config.tls {
+        Some(tls) => {
+            rustls::crypto::ring::default_provider().install_default().expect("rustls crypto provider already installed");
+            let rustls = RustlsConfig::from_config(tls.clone());
+            println!("🚀 Backend listening on https://{}", config.listen);
+            axum_server::bind_rustls(config.listen, rustls).handle(handle).serve(app.into_make_service_with_connect_info::<SocketAddr>()).await
+        }
+        None => {
+            println!("🚀 Backend listening on http://{}", config.listen);
+            axum_server::bind(config.listen).handle(handle).serve(app.into_make_service_with_connect_info::<SocketAddr>()).await
+        }
+    }
+    .unwrap_or_else(|e| {
+        eprintln!("Could not serve on {}: {}", config.listen, e);
+        std::process::exit(2);
+    });
+    tracing::info!("Shut down cleanly");
+}
+
+// Shared by the handler tests: an in-memory store, a throwaway signing key and the full router
+#[cfg(test)]
+mod testing {
+    use super::*;
+    use axum::body::{to_bytes, Body};
+    use axum::http::{Request, StatusCode};
+    use tower::ServiceExt;
+
+    pub const ADMIN_TOKEN: &str = "admin-secret";
+
+    // Tests that set or remove environment variables hold this, so they never interleave
+    static ENV_LOCK: std::sync::Mutex<()> = std::sync::Mutex::new(());
+
+    pub fn env_lock() -> std::sync::MutexGuard<'static, ()> {
+        ENV_LOCK.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    pub fn state() -> AppState {
+        AppState {
+            circulars: Arc::new(circulars::CircularRegistry::bundled()),
+            store: Store::in_memory(),
+            admin_token_hash: Some(Arc::new(auth::hash_key(ADMIN_TOKEN))),
+            license_key: Arc::new(ed25519_dalek::SigningKey::from_bytes(&[7u8; 32])),
+            upi: Some(Arc::new(config::UpiConfig { vpa: "pratyaksh@okaxis".to_string(), payee_name: "Pratyaksh AI".to_string() })),
+            order_limiter: Arc::new(payments::OrderLimiter::default()),
+            metrics: Arc::new(ops::Metrics::new()),
+            draining: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    // Issues a key for a new firm straight through the store and returns its secret
+    pub fn key_for(state: &AppState, firm: &str, scope: store::Scope) -> String {
+        if !state.store.firm_exists(firm).unwrap() {
+            state.store.insert_firm(firm, firm).unwrap();
+        }
+        let secret = format!("pk_test_{}_{:?}", firm, scope);
+        state.store.insert_key(firm, "test", scope, &auth::hash_key(&secret)).unwrap();
+        secret
+    }
+
+    pub fn request(method: &str, uri: &str, token: Option<&str>, body: Option<serde_json::Value>) -> Request<Body> {
+        let mut builder = Request::builder().method(method).uri(uri);
+        if let Some(token) = token {
+            builder = builder.header("authorization", format!("Bearer {}", token));
+        }
+        match body {
+            Some(json) => builder.header("content-type", "application/json").body(Body::from(json.to_string())),
+            None => builder.body(Body::empty()),
+        }
+        .unwrap()
+    }
+
+    // Sends one request through the router; non-JSON bodies come back as Null
+    pub async fn send(state: &AppState, req: Request<Body>) -> (StatusCode, serde_json::Value) {
+        let response = router(state.clone()).oneshot(req).await.unwrap();
+        let status = response.status();
+        let bytes = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, serde_json::from_slice(&bytes).unwrap_or(serde_json::Value::Null))
+    }
+}
@@ -0,0 +1,232 @@
+use axum::Json;
+use utoipa::{
+    openapi::security::{HttpAuthScheme, HttpBuilder, SecurityScheme},
+    Modify, OpenApi,
+};
+
+use crate::{auth, calendar, circulars, companies, errors, fees, gst, ics, income_tax, licenses, ops, payments, portfolio, store, tds, tools};
+use pratyaksh_core::{risk, trace};
+
+// OpenAPI 3 document generated from the handler annotations and the request/response types.
+// pratyaksh_client is generated from the exported copy; re-export with --export-openapi after API changes.
+
+#[derive(OpenApi)]
+#[openapi(
+    info(title = "Pratyaksh compliance API", description = "MCA, GST, income-tax and TDS compliance engine. Every response carries the statutory rules it applied."),
+    paths(
+        crate::analyze_risk,
+        calendar::compliance_calendar,
+        ics::company_calendar_ics,
+        ics::book_calendar_ics,
+        fees::fee_schedule,
+        portfolio::analyze_portfolio,
+        companies::list_companies,
+        companies::create_company,
+        companies::get_company,
+        companies::update_company,
+        companies::delete_company,
+        companies::list_filings,
+        companies::create_filing,
+        companies::update_filing,
+        companies::delete_filing,
+        companies::assess_risk,
+        companies::list_snapshots,
+        gst::gst_due_dates,
+        gst::gst_late_fee,
+        gst::gst_interest,
+        income_tax::interest_234,
+        tds::tds_defaults,
+        tools::gst_mismatch,
+        tools::sft_mismatch,
+        tools::tds_shortfall,
+        tools::mca_notice,
+        tools::board_risk,
+        tools::trust_score,
+        tools::msme_interest,
+        tools::gratuity,
+        tools::tax_regime,
+        tools::crypto_tax,
+        tools::hra,
+        tools::pmla,
+        tools::angel_tax,
+        tools::buyback_tax,
+        tools::capital_gains,
+        tools::shell_risk,
+        tools::esg,
+        tools::udin,
+        tools::audit_rotation,
+        tools::net_worth,
+        tools::export_realisation,
+        tools::partner_dissolution,
+        tools::lease,
+        tools::depreciation,
+        tools::emi,
+        tools::burn_rate,
+        tools::simple_interest,
+        tools::cagr,
+        tools::break_even,
+        auth::list_firms,
+        auth::create_firm,
+        auth::list_keys,
+        auth::issue_key,
+        auth::revoke_key,
+        licenses::issue_license,
+        licenses::list_licenses,
+        licenses::public_key,
+        payments::list_plans,
+        payments::create_order,
+        payments::get_order,
+        payments::order_qr,
+        ops::healthz,
+        ops::readyz,
+        ops::metrics,
+    ),
+    components(schemas(
+        risk::ComplianceRisk,
+        trace::RuleTrace,
+        circulars::CircularCitation,
+        errors::ProblemDetails,
+        errors::InvalidParam,
+        calendar::CompanyType,
+        calendar::CalendarParams,
+        calendar::CalendarEntry,
+        calendar::ComplianceCalendar,
+        ics::BookCalendarRequest,
+        fees::FeeBreakdown,
+        fees::FeeProjection,
+        portfolio::PortfolioCompany,
+        portfolio::PortfolioRequest,
+        portfolio::CompanyRisk,
+        portfolio::PortfolioSummary,
+        portfolio::PortfolioReport,
+        store::Company,
+        store::NewFiling,
+        store::Filing,
+        store::RiskSnapshot,
+        store::Scope,
+        store::Firm,
+        store::ApiKey,
+        companies::RiskRequest,
+        gst::FilingScheme,
+        gst::StateGroup,
+        gst::InterestGround,
+        gst::GstDueDate,
+        gst::GstDueDates,
+        gst::GstLateFee,
+        gst::GstInterest,
+        income_tax::TaxPaymentRequest,
+        income_tax::InterestRequest,
+        income_tax::InterestLine,
+        income_tax::IncomeTaxInterest,
+        tds::TdsEntryRequest,
+        tds::TdsRequest,
+        tds::TdsEntryDefault,
+        tds::TdsStatementFee,
+        tds::TdsDefaults,
+        tools::GstMismatchInput,
+        tools::GstMismatch,
+        tools::SftMismatchInput,
+        tools::SftMismatch,
+        tools::TdsShortfallInput,
+        tools::TdsShortfall,
+        tools::McaNoticeInput,
+        tools::McaNotice,
+        tools::BoardRiskInput,
+        tools::BoardRisk,
+        tools::TrustScoreInput,
+        tools::TrustScore,
+        tools::MsmeInterestInput,
+        tools::MsmeInterest,
+        tools::GratuityInput,
+        tools::Gratuity,
+        tools::TaxRegimeInput,
+        tools::TaxRegime,
+        tools::CryptoTaxInput,
+        tools::CryptoTax,
+        tools::HraInput,
+        tools::Hra,
+        tools::PmlaInput,
+        tools::Pmla,
+        tools::AngelTaxInput,
+        tools::AngelTax,
+        tools::BuybackTaxInput,
+        tools::BuybackTax,
+        tools::IndexedCostInput,
+        tools::IndexedCost,
+        tools::ShellRiskInput,
+        tools::ShellRisk,
+        tools::EsgInput,
+        tools::Esg,
+        tools::UdinInput,
+        tools::Udin,
+        tools::AuditRotationInput,
+        tools::AuditRotation,
+        tools::NetWorthInput,
+        tools::NetWorth,
+        tools::ExportRealisationInput,
+        tools::ExportRealisation,
+        tools::PartnerDissolutionInput,
+        tools::PartnerDissolution,
+        tools::LeaseInput,
+        tools::Lease,
+        tools::DepreciationInput,
+        tools::Depreciation,
+        tools::EmiInput,
+        tools::Emi,
+        tools::BurnRateInput,
+        tools::BurnRate,
+        tools::SimpleInterestInput,
+        tools::SimpleInterest,
+        tools::CagrInput,
+        tools::Cagr,
+        tools::BreakEvenInput,
+        tools::BreakEven,
+        auth::NewFirm,
+        auth::NewKey,
+        auth::IssuedKey,
+        licenses::NewLicense,
+        licenses::LicenseClaims,
+        licenses::LicenseFile,
+        licenses::IssuedLicense,
+        licenses::LicensePublicKey,
+        payments::Plan,
+        payments::NewOrder,
+        payments::OrderIntent,
+        store::PaymentOrder,
+        store::License,
+        ops::Health,
+    )),
+    modifiers(&SecuritySchemes),
+    tags(
+        (name = "compliance", description = "Companies Act deadlines, fees and risk"),
+        (name = "companies", description = "The firm's stored companies, filings and risk snapshots"),
+        (name = "gst", description = "CGST Act due dates, Sec 47 late fee and Sec 50 interest"),
+        (name = "income-tax", description = "Sec 234A/234B/234C interest"),
+        (name = "tds", description = "Sec 201(1A) interest and Sec 234E fee"),
+        (name = "tools", description = "Smart Tools: notice predictors and tax, payroll and finance calculators"),
+        (name = "admin", description = "Operator API for firms and their API keys"),
+        (name = "licenses", description = "Signed offline licenses for the desktop client"),
+        (name = "payments", description = "Subscription plans and UPI payment orders"),
+        (name = "ops", description = "Liveness, readiness and Prometheus metrics"),
+    )
+)]
+pub struct ApiDoc;
+
+struct SecuritySchemes;
+
+impl Modify for SecuritySchemes {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        let components = openapi.components.get_or_insert_with(Default::default);
+        // Both are also accepted in X-API-Key; the spec advertises the bearer form
+        components.add_security_scheme("api_key", SecurityScheme::Http(HttpBuilder::new().scheme(HttpAuthScheme::Bearer).bearer_format("pk_...").build()));
+        components.add_security_scheme("admin_token", SecurityScheme::Http(HttpBuilder::new().scheme(HttpAuthScheme::Bearer).build()));
+    }
+}
+
+pub fn document() -> utoipa::openapi::OpenApi {
+    ApiDoc::openapi()
+}
+
+pub async fn openapi_json() -> Json<utoipa::openapi::OpenApi> {
+    Json(document())
+}
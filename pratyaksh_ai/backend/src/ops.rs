@@ -0,0 +1,170 @@
+use axum::{
+    extract::{MatchedPath, Request, State},
+    http::{header, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Json,
+};
+use prometheus::{Encoder, HistogramOpts, HistogramVec, IntCounterVec, Opts, Registry, TextEncoder};
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use utoipa::ToSchema;
+
+use crate::AppState;
+
+// Operational endpoints for systemd, load balancers and Prometheus. None of them need an API key.
+
+pub const DRAIN_TIMEOUT: Duration = Duration::from_secs(30); // In-flight requests get this long after SIGTERM
+
+pub struct Metrics {
+    registry: Registry,
+    requests: IntCounterVec,
+    errors: IntCounterVec,
+    latency: HistogramVec,
+}
+
+impl Metrics {
+    pub fn new() -> Self {
+        let requests = IntCounterVec::new(Opts::new("pratyaksh_http_requests_total", "HTTP requests by route and status"), &["method", "route", "status"])
+            .expect("valid metric");
+        let errors = IntCounterVec::new(Opts::new("pratyaksh_http_errors_total", "HTTP responses with a 4xx or 5xx status"), &["method", "route", "class"])
+            .expect("valid metric");
+        let latency = HistogramVec::new(HistogramOpts::new("pratyaksh_http_request_duration_seconds", "Time to produce the response"), &["method", "route"])
+            .expect("valid metric");
+
+        let registry = Registry::new();
+        registry.register(Box::new(requests.clone())).expect("unique metric");
+        registry.register(Box::new(errors.clone())).expect("unique metric");
+        registry.register(Box::new(latency.clone())).expect("unique metric");
+        Metrics { registry, requests, errors, latency }
+    }
+}
+
+// Labels by route template (/api/v1/companies/:cin), not the raw path, so a CIN never becomes a series
+pub async fn track(State(state): State<AppState>, req: Request, next: Next) -> Response {
+    let route = req.extensions().get::<MatchedPath>().map(|p| p.as_str().to_string()).unwrap_or_else(|| "unmatched".to_string());
+    let method = req.method().to_string();
+    let started = Instant::now();
+
+    let response = next.run(req).await;
+
+    let status = response.status();
+    let metrics = &state.metrics;
+    metrics.latency.with_label_values(&[&method, &route]).observe(started.elapsed().as_secs_f64());
+    metrics.requests.with_label_values(&[&method, &route, status.as_str()]).inc();
+    if status.is_client_error() || status.is_server_error() {
+        let class = if status.is_server_error() { "5xx" } else { "4xx" };
+        metrics.errors.with_label_values(&[&method, &route, class]).inc();
+    }
+    response
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct Health {
+    status: &'static str, // "ok", "ready", "draining" or "unavailable"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    detail: Option<String>,
+}
+
+// OPS
+// Liveness: the process is up and serving
+#[utoipa::path(get, path = "/healthz", tag = "ops", responses((status = 200, body = Health)))]
+pub async fn healthz() -> Json<Health> {
+    Json(Health { status: "ok", detail: None })
+}
+
+// Readiness: the store answers and the server is not draining for shutdown
+#[utoipa::path(
+    get,
+    path = "/readyz",
+    tag = "ops",
+    responses(
+        (status = 200, body = Health),
+        (status = 503, description = "Draining or the store is unavailable", body = Health),
+    )
+)]
+pub async fn readyz(State(state): State<AppState>) -> (StatusCode, Json<Health>) {
+    if state.draining.load(Ordering::Relaxed) {
+        return (StatusCode::SERVICE_UNAVAILABLE, Json(Health { status: "draining", detail: None }));
+    }
+    match state.store.run(|s| s.ping()).await {
+        Ok(()) => (StatusCode::OK, Json(Health { status: "ready", detail: None })),
+        Err(_) => {
+            tracing::error!("Readiness check failed");
+            (StatusCode::SERVICE_UNAVAILABLE, Json(Health { status: "unavailable", detail: Some("store not reachable".to_string()) }))
+        }
+    }
+}
+
+// Prometheus text exposition format
+#[utoipa::path(get, path = "/metrics", tag = "ops", responses((status = 200, body = String, content_type = "text/plain")))]
+pub async fn metrics(State(state): State<AppState>) -> Response {
+    let encoder = TextEncoder::new();
+    let mut body = Vec::new();
+    if let Err(e) = encoder.encode(&state.metrics.registry.gather(), &mut body) {
+        tracing::error!("Could not encode metrics: {}", e);
+        return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+    }
+    ([(header::CONTENT_TYPE, encoder.format_type().to_string())], body).into_response()
+}
+
+// Resolves on SIGTERM (systemd stop) or Ctrl-C, after marking the server as draining for /readyz.
+// On SIGTERM the listener stays open for `delay` so load balancers see the 503 and stop routing here first.
+pub async fn shutdown_signal(draining: Arc<AtomicBool>, delay: Duration) {
+    let ctrl_c = async {
+        tokio::signal::ctrl_c().await.expect("Ctrl-C handler");
+    };
+    #[cfg(unix)]
+    let terminate = async {
+        tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()).expect("SIGTERM handler").recv().await;
+    };
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    let terminated = tokio::select! {
+        _ = ctrl_c => { tracing::info!("Ctrl-C received"); false }
+        _ = terminate => { tracing::info!("SIGTERM received"); true }
+    };
+    draining.store(true, Ordering::Relaxed);
+    if terminated && !delay.is_zero() {
+        tracing::info!("Reporting not ready for {}s before closing the listener", delay.as_secs());
+        tokio::time::sleep(delay).await;
+    }
+    tracing::info!("Draining in-flight requests (up to {}s)", DRAIN_TIMEOUT.as_secs());
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::testing::{request, send, state};
+    use axum::body::to_bytes;
+    use axum::http::StatusCode;
+    use serde_json::json;
+    use std::sync::atomic::Ordering;
+    use tower::ServiceExt;
+
+    #[tokio::test]
+    async fn readyz_reports_draining_while_healthz_stays_up() {
+        let state = state();
+        let (status, body) = send(&state, request("GET", "/readyz", None, None)).await;
+        assert_eq!((status, body), (StatusCode::OK, json!({ "status": "ready" })));
+
+        state.draining.store(true, Ordering::Relaxed);
+        let (status, body) = send(&state, request("GET", "/readyz", None, None)).await;
+        assert_eq!((status, body), (StatusCode::SERVICE_UNAVAILABLE, json!({ "status": "draining" })));
+        // Liveness is unaffected, so systemd does not restart a draining instance
+        let (status, _) = send(&state, request("GET", "/healthz", None, None)).await;
+        assert_eq!(status, StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn metrics_label_routes_by_template() {
+        let state = state();
+        send(&state, request("GET", "/api/v1/companies/U12345", None, None)).await;
+        let response = crate::router(state.clone()).oneshot(request("GET", "/metrics", None, None)).await.unwrap();
+        let text = String::from_utf8(to_bytes(response.into_body(), usize::MAX).await.unwrap().to_vec()).unwrap();
+        assert!(text.contains(r#"route="/api/v1/companies/:cin",status="401""#), "{}", text);
+        assert!(!text.contains("U12345"));
+    }
+}
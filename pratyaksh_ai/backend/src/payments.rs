@@ -0,0 +1,366 @@
+use axum::{
+    extract::{rejection::{JsonRejection, PathRejection}, ConnectInfo, Path, State},
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use chrono::Utc;
+use pratyaksh_core::money::format_inr;
+use qrcode::{Color, QrCode};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use utoipa::ToSchema;
+
+use crate::errors::{ApiError, FieldError};
+use crate::store::PaymentOrder;
+use crate::AppState;
+
+pub use pratyaksh_core::upi::*;
+
+// UPI payment orders for the website checkout. Each order gets its own transaction reference,
+// which the customer's app carries into the bank statement so the payment can be matched later.
+// The route is public, so a user's unpaid order for a plan is handed back instead of minting another,
+// and new orders are rate limited per client address.
+
+const QR_MODULE_PX: usize = 8;
+const QR_QUIET_ZONE: usize = 4; // Modules of white border, per ISO/IEC 18004
+const MAX_USER_ID_LEN: usize = 128; // Firebase uids are at most 128 characters
+const ORDER_REUSE_HOURS: i64 = 24; // An unpaid order stays the user's checkout reference this long
+const ORDERS_PER_WINDOW: u32 = 10; // New orders one address may create per ORDER_WINDOW
+const ORDER_WINDOW: Duration = Duration::from_secs(3600);
+
+#[derive(Deserialize, ToSchema)]
+pub struct NewOrder {
+    pub plan: String,    // Plan id or name
+    pub user_id: String, // Website account paying for it
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct OrderIntent {
+    #[serde(flatten)]
+    pub order: PaymentOrder,
+    pub amount_display: String,
+    pub upi_uri: String, // Open on a phone with a UPI app installed
+    pub qr_png: String,  // Path of the scannable QR for the same link
+}
+
+// Fixed window per client address. With trusted_hops = 0 the server must be exposed directly and
+// the peer address is the client. Behind N trusted reverse proxies, each appending the address it
+// received from to X-Forwarded-For, the client is the Nth entry from the right; entries left of it
+// are whatever the client sent and are never trusted.
+#[derive(Default)]
+pub struct OrderLimiter {
+    windows: Mutex<HashMap<IpAddr, (Instant, u32)>>,
+    trusted_hops: usize,
+}
+
+impl OrderLimiter {
+    pub fn new(trusted_hops: usize) -> Self {
+        OrderLimiter { windows: Mutex::default(), trusted_hops }
+    }
+
+    // A request that did not pass through every trusted proxy keeps its peer address
+    fn client_ip(&self, peer: IpAddr, headers: &HeaderMap) -> IpAddr {
+        if self.trusted_hops == 0 {
+            return peer;
+        }
+        let forwarded: Vec<&str> = headers.get_all("x-forwarded-for").iter()
+            .filter_map(|v| v.to_str().ok())
+            .flat_map(|v| v.split(','))
+            .map(str::trim)
+            .collect();
+        forwarded.len().checked_sub(self.trusted_hops)
+            .and_then(|i| forwarded[i].parse().ok())
+            .unwrap_or(peer)
+    }
+
+    fn admit(&self, ip: IpAddr) -> bool {
+        let now = Instant::now();
+        let mut windows = self.windows.lock().expect("order limiter mutex poisoned");
+        windows.retain(|_, (started, _)| now.duration_since(*started) < ORDER_WINDOW);
+        let (_, count) = windows.entry(ip).or_insert((now, 0));
+        if *count >= ORDERS_PER_WINDOW {
+            return false;
+        }
+        *count += 1;
+        true
+    }
+}
+
+// "PRK" + 12 hex digits; UPI caps tr at 35 characters and some banks drop lowercase
+fn generate_txn_ref() -> String {
+    let mut bytes = [0u8; 6];
+    getrandom::getrandom(&mut bytes).expect("OS random source unavailable");
+    format!("PRK{}", hex::encode_upper(bytes))
+}
+
+fn upi_config(state: &AppState) -> Result<&crate::config::UpiConfig, ApiError> {
+    state.upi.as_deref().ok_or_else(|| ApiError::Forbidden("UPI payments disabled; set upi.vpa".to_string()))
+}
+
+fn intent(state: &AppState, order: PaymentOrder) -> Result<OrderIntent, ApiError> {
+    let upi = upi_config(state)?;
+    let plan = find_plan(&order.plan).map(|p| p.name).unwrap_or(&order.plan);
+    Ok(OrderIntent {
+        amount_display: format_inr(order.amount),
+        upi_uri: intent_uri(&upi.vpa, &upi.payee_name, order.amount, &order.txn_ref, &format!("{} {}", plan, order.txn_ref)),
+        qr_png: format!("/api/v1/payments/orders/{}/qr.png", order.txn_ref),
+        order,
+    })
+}
+
+// 8-bit grayscale PNG, black modules on white
+fn render_qr(data: &str) -> Result<Vec<u8>, ApiError> {
+    let code = QrCode::new(data.as_bytes()).map_err(|e| ApiError::Internal(e.to_string()))?;
+    let modules = code.width();
+    let colors = code.to_colors();
+    let size = (modules + 2 * QR_QUIET_ZONE) * QR_MODULE_PX;
+
+    let mut pixels = vec![255u8; size * size];
+    for (i, color) in colors.iter().enumerate() {
+        if *color != Color::Dark {
+            continue;
+        }
+        let x0 = (i % modules + QR_QUIET_ZONE) * QR_MODULE_PX;
+        let y0 = (i / modules + QR_QUIET_ZONE) * QR_MODULE_PX;
+        for y in y0..y0 + QR_MODULE_PX {
+            pixels[y * size + x0..y * size + x0 + QR_MODULE_PX].fill(0);
+        }
+    }
+
+    let mut out = Vec::new();
+    let mut encoder = png::Encoder::new(&mut out, size as u32, size as u32);
+    encoder.set_color(png::ColorType::Grayscale);
+    encoder.set_depth(png::BitDepth::Eight);
+    let mut writer = encoder.write_header().map_err(|e| ApiError::Internal(e.to_string()))?;
+    writer.write_image_data(&pixels).map_err(|e| ApiError::Internal(e.to_string()))?;
+    writer.finish().map_err(|e| ApiError::Internal(e.to_string()))?;
+    Ok(out)
+}
+
+// PLANS
+#[utoipa::path(
+    get,
+    path = "/api/v1/plans",
+    tag = "payments",
+    responses((status = 200, body = Vec<Plan>))
+)]
+pub async fn list_plans() -> Json<&'static [Plan]> {
+    Json(PLANS)
+}
+
+// PAYMENT ORDERS
+#[utoipa::path(
+    post,
+    path = "/api/v1/payments/orders",
+    tag = "payments",
+    request_body = NewOrder,
+    responses(
+        (status = 200, description = "The user's unpaid order for this plan, reused", body = OrderIntent),
+        (status = 201, description = "New order", body = OrderIntent),
+        (status = 400, description = "Malformed request", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "UPI payments are not configured", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 422, description = "Invalid parameters", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 429, description = "Too many new orders from this address", body = ProblemDetails, content_type = "application/problem+json"),
+    )
+)]
+pub async fn create_order(State(state): State<AppState>, ConnectInfo(peer): ConnectInfo<SocketAddr>, headers: HeaderMap, body: Result<Json<NewOrder>, JsonRejection>) -> Result<(StatusCode, Json<OrderIntent>), ApiError> {
+    let Json(req) = body?;
+    upi_config(&state)?;
+
+    let mut errors = Vec::new();
+    let plan = find_plan(&req.plan);
+    if plan.is_none() {
+        let ids: Vec<&str> = PLANS.iter().map(|p| p.id).collect();
+        errors.push(FieldError::OutOfRange { field: "plan".to_string(), reason: format!("must be one of {}", ids.join(", ")) });
+    }
+    let user_id = req.user_id.trim().to_string();
+    if user_id.is_empty() {
+        errors.push(FieldError::OutOfRange { field: "user_id".to_string(), reason: "must not be empty".to_string() });
+    } else if user_id.chars().count() > MAX_USER_ID_LEN || user_id.chars().any(char::is_control) {
+        errors.push(FieldError::OutOfRange { field: "user_id".to_string(), reason: format!("must be at most {} printable characters", MAX_USER_ID_LEN) });
+    }
+    let Some(plan) = plan.filter(|_| errors.is_empty()) else {
+        return Err(ApiError::Validation(errors));
+    };
+
+    let since = (Utc::now() - chrono::Duration::hours(ORDER_REUSE_HOURS)).to_rfc3339();
+    let open = state.store.run({
+        let user_id = user_id.clone();
+        move |s| s.open_order(&user_id, plan.id, &since)
+    }).await?;
+    if let Some(order) = open {
+        return Ok((StatusCode::OK, Json(intent(&state, order)?)));
+    }
+    let client = state.order_limiter.client_ip(peer.ip(), &headers);
+    if !state.order_limiter.admit(client) {
+        tracing::warn!("Payment order refused for {}: rate limit reached", client);
+        return Err(ApiError::TooManyRequests(format!("At most {} new orders per hour from one address", ORDERS_PER_WINDOW)));
+    }
+
+    let order = state.store.run(move |s| s.insert_order(&generate_txn_ref(), plan.id, plan.amount, &user_id)).await?;
+    tracing::info!("Payment order {} for {} ({}) by {}", order.txn_ref, plan.id, format_inr(order.amount), order.user_id);
+    Ok((StatusCode::CREATED, Json(intent(&state, order)?)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/payments/orders/{txn_ref}",
+    tag = "payments",
+    params(("txn_ref" = String, Path, description = "Transaction reference returned when the order was created")),
+    responses(
+        (status = 200, body = OrderIntent),
+        (status = 403, description = "UPI payments are not configured", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 404, description = "No order with that reference", body = ProblemDetails, content_type = "application/problem+json"),
+    )
+)]
+pub async fn get_order(State(state): State<AppState>, path: Result<Path<String>, PathRejection>) -> Result<Json<OrderIntent>, ApiError> {
+    let Path(txn_ref) = path?;
+    let order = state.store.run(move |s| s.order(&txn_ref)?.ok_or_else(|| ApiError::NotFound(format!("No payment order {}", txn_ref)))).await?;
+    Ok(Json(intent(&state, order)?))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/payments/orders/{txn_ref}/qr.png",
+    tag = "payments",
+    params(("txn_ref" = String, Path, description = "Transaction reference returned when the order was created")),
+    responses(
+        (status = 200, description = "QR code of the UPI link", body = Vec<u8>, content_type = "image/png"),
+        (status = 403, description = "UPI payments are not configured", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 404, description = "No order with that reference", body = ProblemDetails, content_type = "application/problem+json"),
+    )
+)]
+pub async fn order_qr(State(state): State<AppState>, path: Result<Path<String>, PathRejection>) -> Result<Response, ApiError> {
+    let Path(txn_ref) = path?;
+    let order = state.store.run(move |s| s.order(&txn_ref)?.ok_or_else(|| ApiError::NotFound(format!("No payment order {}", txn_ref)))).await?;
+    let png = render_qr(&intent(&state, order)?.upi_uri)?;
+    Ok(([(header::CONTENT_TYPE, "image/png"), (header::CACHE_CONTROL, "private, max-age=86400")], png).into_response())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{request, send, state};
+    use axum::{body::Body, http::Request};
+    use serde_json::{json, Value};
+
+    fn order_request(user_id: &str, plan: &str, peer: [u8; 4], forwarded: Option<&str>) -> Request<Body> {
+        let mut req = request("POST", "/api/v1/payments/orders", None, Some(json!({ "plan": plan, "user_id": user_id })));
+        req.extensions_mut().insert(ConnectInfo(SocketAddr::from((peer, 40000))));
+        if let Some(forwarded) = forwarded {
+            req.headers_mut().insert("x-forwarded-for", forwarded.parse().unwrap());
+        }
+        req
+    }
+
+    fn forwarded(value: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert("x-forwarded-for", value.parse().unwrap());
+        headers
+    }
+
+    fn ip(raw: &str) -> IpAddr {
+        raw.parse().unwrap()
+    }
+
+    #[test]
+    fn limiter_caps_new_orders_per_address() {
+        let limiter = OrderLimiter::default();
+        for _ in 0..ORDERS_PER_WINDOW {
+            assert!(limiter.admit(ip("203.0.113.7")));
+        }
+        assert!(!limiter.admit(ip("203.0.113.7")));
+        assert!(limiter.admit(ip("203.0.113.8")));
+    }
+
+    #[test]
+    fn client_address_comes_from_trusted_hops_only() {
+        let peer = ip("10.0.0.2");
+        // Exposed directly: a client-supplied header changes nothing
+        assert_eq!(OrderLimiter::new(0).client_ip(peer, &forwarded("198.51.100.1")), peer);
+
+        // One proxy appends the address it saw; anything left of that came from the client
+        let one = OrderLimiter::new(1);
+        assert_eq!(one.client_ip(peer, &forwarded("198.51.100.1")), ip("198.51.100.1"));
+        assert_eq!(one.client_ip(peer, &forwarded("1.2.3.4, 198.51.100.1")), ip("198.51.100.1"));
+        assert_eq!(OrderLimiter::new(2).client_ip(peer, &forwarded("1.2.3.4, 198.51.100.1, 10.0.0.9")), ip("198.51.100.1"));
+
+        // Missing hops or an unparseable entry fall back to the peer
+        assert_eq!(one.client_ip(peer, &HeaderMap::new()), peer);
+        assert_eq!(OrderLimiter::new(2).client_ip(peer, &forwarded("198.51.100.1")), peer);
+        assert_eq!(one.client_ip(peer, &forwarded("unknown")), peer);
+    }
+
+    #[tokio::test]
+    async fn unpaid_order_is_reused_until_licensed() {
+        let state = state();
+        let (status, first) = send(&state, order_request("u1", "Scale", [203, 0, 113, 7], None)).await;
+        assert_eq!(status, StatusCode::CREATED, "{}", first);
+        assert_eq!((first["plan"].as_str(), first["amount"].as_i64(), first["amount_display"].as_str()), (Some("scale"), Some(239900), Some("₹2,399.00")));
+        assert!(first["upi_uri"].as_str().unwrap().contains(&format!("&tr={}&", first["txn_ref"].as_str().unwrap())));
+
+        let (status, again) = send(&state, order_request("u1", "scale", [203, 0, 113, 7], None)).await;
+        assert_eq!((status, &again["txn_ref"]), (StatusCode::OK, &first["txn_ref"]));
+        let (status, other_plan) = send(&state, order_request("u1", "Launch", [203, 0, 113, 7], None)).await;
+        assert_eq!(status, StatusCode::CREATED);
+        assert_ne!(other_plan["txn_ref"], first["txn_ref"]);
+
+        let claims = crate::licenses::LicenseClaims {
+            license_id: "lic_1".to_string(),
+            user_id: "u1".to_string(),
+            email: "ca@example.in".to_string(),
+            plan: "Scale".to_string(),
+            device_id: "PRK-DEV-1".to_string(),
+            issued_at: Utc::now(),
+            expires_on: Utc::now().date_naive(),
+        };
+        state.store.insert_license(&crate::store::License { claims, txn_id: first["txn_ref"].as_str().map(str::to_string) }).unwrap();
+        let (status, renewal) = send(&state, order_request("u1", "Scale", [203, 0, 113, 7], None)).await;
+        assert_eq!(status, StatusCode::CREATED);
+        assert_ne!(renewal["txn_ref"], first["txn_ref"]);
+    }
+
+    #[tokio::test]
+    async fn user_ids_are_bounded_and_printable() {
+        let state = state();
+        let longest = "u".repeat(MAX_USER_ID_LEN);
+        let (status, _) = send(&state, order_request(&longest, "scale", [203, 0, 113, 7], None)).await;
+        assert_eq!(status, StatusCode::CREATED);
+
+        for user_id in ["u".repeat(MAX_USER_ID_LEN + 1), "u1\nu2".to_string(), "  ".to_string()] {
+            let (status, body) = send(&state, order_request(&user_id, "scale", [203, 0, 113, 7], None)).await;
+            assert_eq!((status, body["invalid_params"][0]["name"].as_str()), (StatusCode::UNPROCESSABLE_ENTITY, Some("user_id")));
+        }
+        let (status, body) = send(&state, order_request("u1", "Enterprise Suite", [203, 0, 113, 7], None)).await;
+        assert_eq!((status, body["invalid_params"][0]["name"].as_str()), (StatusCode::UNPROCESSABLE_ENTITY, Some("plan")));
+    }
+
+    #[tokio::test]
+    async fn rate_limit_follows_the_trusted_client_address() {
+        let mut state = state();
+        state.order_limiter = std::sync::Arc::new(OrderLimiter::new(1));
+        let proxy = [10, 0, 0, 2];
+        for n in 0..ORDERS_PER_WINDOW {
+            let (status, _) = send(&state, order_request(&format!("u{}", n), "scale", proxy, Some("198.51.100.1"))).await;
+            assert_eq!(status, StatusCode::CREATED);
+        }
+        // A spoofed leftmost entry does not buy a fresh bucket
+        let (status, _) = send(&state, order_request("spoofer", "scale", proxy, Some("1.2.3.4, 198.51.100.1"))).await;
+        assert_eq!(status, StatusCode::TOO_MANY_REQUESTS);
+        // Another client behind the same proxy is unaffected
+        let (status, _) = send(&state, order_request("neighbour", "scale", proxy, Some("198.51.100.2"))).await;
+        assert_eq!(status, StatusCode::CREATED);
+    }
+
+    #[tokio::test]
+    async fn orders_need_upi_configured() {
+        let mut state = state();
+        state.upi = None;
+        let (status, _): (StatusCode, Value) = send(&state, order_request("u1", "scale", [203, 0, 113, 7], None)).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+    }
+}
@@ -0,0 +1,150 @@
+use axum::{
+    extract::{rejection::JsonRejection, State},
+    Json,
+};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use std::collections::BTreeMap;
+
+use crate::calendar::{CompanyProfile, CompanyType};
+use crate::circulars::CircularRegistry;
+use crate::errors::{parse_date, ApiError};
+use crate::store::{Caller, FirmStore};
+use crate::AppState;
+use pratyaksh_core::risk::{calculate_risk, check_form, check_share_capital, ComplianceRisk, RiskInput};
+
+#[derive(Deserialize, ToSchema)]
+pub struct PortfolioCompany {
+    pub cin: String,
+    pub fy_end_date: String, // YYYY-MM-DD
+    pub forms: Vec<String>,
+    #[serde(default)]
+    pub company_type: CompanyType,
+    #[serde(default)]
+    pub first_fy: bool,
+    #[serde(default)]
+    pub agm_extension_months: u32,
+    pub nominal_share_capital: Option<i64>, // Sets the normal fee slab
+}
+
+impl PortfolioCompany {
+    fn profile(&self) -> CompanyProfile {
+        CompanyProfile {
+            company_type: self.company_type,
+            first_fy: self.first_fy,
+            agm_extension_months: self.agm_extension_months,
+        }
+    }
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct PortfolioRequest {
+    pub as_of: Option<String>, // YYYY-MM-DD, applies to every company; defaults to today
+    pub companies: Vec<PortfolioCompany>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct CompanyRisk {
+    pub cin: String,
+    pub overall_risk: String, // Worst risk_level across the company's forms
+    pub penalty_exposure: i64,
+    pub risks: Vec<ComplianceRisk>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct PortfolioSummary {
+    pub companies: usize,
+    pub total_penalty_exposure: i64,
+    pub companies_by_risk_level: BTreeMap<String, usize>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct PortfolioReport {
+    pub summary: PortfolioSummary,
+    pub results: Vec<CompanyRisk>,
+}
+
+const RISK_LEVELS: [&str; 3] = ["SAFE", "MODERATE", "CRITICAL"];
+
+fn severity(level: &str) -> usize {
+    RISK_LEVELS.iter().position(|l| *l == level).unwrap_or(0)
+}
+
+// Every form of one company, with filing status taken from the store; `company` carries the
+// FY, evaluation date, profile and share capital shared by all of them
+pub fn company_risk(cin: &str, forms: &[String], company: &RiskInput, registry: &CircularRegistry, store: &FirmStore) -> Result<CompanyRisk, ApiError> {
+    let mut risks = Vec::with_capacity(forms.len());
+    for form in forms {
+        let filing = store.filing_for(cin, form, company.fy_end)?;
+        risks.push(calculate_risk(&RiskInput { form_type: form.clone(), filing, ..company.clone() }, registry));
+    }
+
+    let penalty_exposure = risks.iter().map(|r| r.penalty_estimate).sum();
+    let overall_risk = risks.iter()
+        .map(|r| r.risk_level.as_str())
+        .max_by_key(|l| severity(l))
+        .unwrap_or("SAFE")
+        .to_string();
+    Ok(CompanyRisk { cin: cin.to_string(), overall_risk, penalty_exposure, risks })
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/compliance/portfolio",
+    tag = "compliance",
+    request_body = PortfolioRequest,
+    responses(
+        (status = 200, body = PortfolioReport),
+        (status = 400, description = "Malformed request", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 401, description = "Missing, unknown or revoked API key", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 422, description = "Invalid parameters", body = ProblemDetails, content_type = "application/problem+json"),
+    ),
+    security(("api_key" = []))
+)]
+pub async fn analyze_portfolio(State(state): State<AppState>, caller: Caller, body: Result<Json<PortfolioRequest>, JsonRejection>) -> Result<Json<PortfolioReport>, ApiError> {
+    let Json(req) = body?;
+
+    // Validate the whole book up front so one bad row doesn't yield a partial report
+    let mut errors = Vec::new();
+    let as_of = match &req.as_of {
+        Some(raw) => parse_date("as_of", raw, &mut errors),
+        None => Some(Utc::now().date_naive()),
+    };
+    let mut inputs = Vec::with_capacity(req.companies.len());
+    for (i, company) in req.companies.iter().enumerate() {
+        let fy_end = parse_date(&format!("companies[{}].fy_end_date", i), &company.fy_end_date, &mut errors);
+        for (j, form) in company.forms.iter().enumerate() {
+            check_form(&format!("companies[{}].forms[{}]", i, j), form, &company.profile(), &mut errors);
+        }
+        company.profile().check(&format!("companies[{}].", i), &mut errors);
+        check_share_capital(&format!("companies[{}].nominal_share_capital", i), company.nominal_share_capital, &mut errors);
+        inputs.push(fy_end);
+    }
+    let as_of = match as_of {
+        Some(date) if errors.is_empty() => date,
+        _ => return Err(ApiError::Validation(errors)),
+    };
+
+    let mut summary = PortfolioSummary {
+        companies: req.companies.len(),
+        total_penalty_exposure: 0,
+        companies_by_risk_level: RISK_LEVELS.iter().map(|l| (l.to_string(), 0)).collect(),
+    };
+    let circulars = state.circulars.clone();
+    let results = state.store.for_firm(&caller.firm_id).run(move |store| -> Result<_, ApiError> {
+        let mut results = Vec::with_capacity(req.companies.len());
+        for (company, fy_end) in req.companies.into_iter().zip(inputs) {
+            let fy_end = fy_end.expect("validated above");
+            let input = RiskInput { fy_end, form_type: String::new(), as_of, profile: company.profile(), share_capital: company.nominal_share_capital, filing: None };
+            results.push(company_risk(&company.cin, &company.forms, &input, &circulars, store)?);
+        }
+        Ok(results)
+    }).await?;
+
+    for result in &results {
+        summary.total_penalty_exposure += result.penalty_exposure;
+        *summary.companies_by_risk_level.entry(result.overall_risk.clone()).or_insert(0) += 1;
+    }
+    Ok(Json(PortfolioReport { summary, results }))
+}
@@ -0,0 +1,483 @@
+use chrono::{Local, Months, NaiveDate, NaiveTime};
+use lettre::{
+    message::{header::ContentType, Mailbox},
+    transport::smtp::authentication::Credentials,
+    AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use crate::calendar::{build_calendar, CalendarEntry, CalendarQuery};
+use crate::circulars::CircularRegistry;
+use crate::errors::FieldError;
+use crate::fees;
+
+// Daily deadline reminders over SMTP. Every sent reminder is appended to a JSON-lines
+// delivery log, which is replayed on start so a reminder is never sent twice.
+
+pub const REMINDERS_PATH_ENV: &str = "PRATYAKSH_REMINDERS";
+const REMINDERS_FILE: &str = "reminders.json";
+const LOG_FILE: &str = "reminder_log.jsonl";
+
+#[derive(Deserialize, Clone, Copy, Debug, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum SmtpSecurity {
+    #[default]
+    None, // Plain SMTP, e.g. MailHog on localhost:1025
+    StartTls,
+    Tls,
+}
+
+#[derive(Deserialize)]
+pub struct SmtpConfig {
+    pub host: String,
+    pub port: u16,
+    pub from: String,
+    #[serde(default)]
+    pub security: SmtpSecurity,
+    pub username: Option<String>,
+    pub password: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct ReminderCompany {
+    #[serde(flatten)]
+    pub calendar: CalendarQuery,
+    pub staff_email: String,          // Assigned staff member
+    pub client_email: Option<String>,
+}
+
+fn default_lead_days() -> Vec<i64> {
+    vec![7, 1]
+}
+
+fn default_run_at() -> NaiveTime {
+    NaiveTime::from_hms_opt(8, 0, 0).unwrap()
+}
+
+#[derive(Deserialize)]
+pub struct ReminderConfig {
+    #[serde(default = "default_lead_days")]
+    pub lead_days: Vec<i64>, // Days before the due date to remind
+    #[serde(default = "default_run_at")]
+    pub run_at: NaiveTime, // Local time of the daily run
+    #[serde(default)]
+    pub log_path: PathBuf, // Defaults to <data_dir>/reminder_log.jsonl
+    pub smtp: SmtpConfig,
+    pub companies: Vec<ReminderCompany>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Delivery {
+    sent_at: String,
+    cin: String,
+    form: String,
+    due_date: NaiveDate,
+    lead_days: i64,
+    recipient: String,
+}
+
+impl Delivery {
+    fn key(&self) -> String {
+        format!("{}|{}|{}|{}|{}", self.cin, self.form, self.due_date, self.lead_days, self.recipient)
+    }
+}
+
+impl ReminderConfig {
+    // Reads $PRATYAKSH_REMINDERS or <data_dir>/reminders.json; Ok(None) disables the scheduler.
+    // Companies get the same checks as the calendar endpoint, so a bad entry stops startup
+    // instead of failing silently at the first daily run.
+    pub fn load(data_dir: &Path) -> Result<Option<Self>, Vec<String>> {
+        let path = std::env::var(REMINDERS_PATH_ENV).map(PathBuf::from).unwrap_or_else(|_| data_dir.join(REMINDERS_FILE));
+        let raw = match std::fs::read_to_string(&path) {
+            Ok(raw) => raw,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(vec![format!("{}: {}", path.display(), e)]),
+        };
+        let mut config: ReminderConfig = serde_json::from_str(&raw).map_err(|e| vec![format!("{}: {}", path.display(), e)])?;
+        config.lead_days.sort_unstable();
+        if config.log_path.as_os_str().is_empty() {
+            config.log_path = data_dir.join(LOG_FILE);
+        }
+
+        let errors: Vec<String> = config.check().into_iter().map(|e| format!("{}: {}: {}", path.display(), e.field(), e.reason())).collect();
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+        Ok(Some(config))
+    }
+
+    fn check(&self) -> Vec<FieldError> {
+        let mut errors = Vec::new();
+        let mut check_mailbox = |field: String, address: &str| {
+            if let Err(e) = address.parse::<Mailbox>() {
+                errors.push(FieldError::OutOfRange { field, reason: format!("'{}' is not an email address: {}", address, e) });
+            }
+        };
+        check_mailbox("smtp.from".to_string(), &self.smtp.from);
+        for (i, company) in self.companies.iter().enumerate() {
+            check_mailbox(format!("companies[{}].staff_email", i), &company.staff_email);
+            if let Some(client) = &company.client_email {
+                check_mailbox(format!("companies[{}].client_email", i), client);
+            }
+        }
+
+        if self.lead_days.is_empty() || self.lead_days.iter().any(|d| *d < 0) {
+            errors.push(FieldError::OutOfRange { field: "lead_days".to_string(), reason: "must list one or more days, none negative".to_string() });
+        }
+        // The CIN labels each reminder and keys the delivery log, so it must tell companies apart
+        let mut seen: HashMap<&str, usize> = HashMap::new();
+        for (i, company) in self.companies.iter().enumerate() {
+            company.calendar.check(&format!("companies[{}].", i), &mut errors);
+            let field = format!("companies[{}].cin", i);
+            match company.calendar.cin.as_deref().map(str::trim) {
+                None | Some("") => errors.push(FieldError::OutOfRange { field, reason: "is required to label and de-duplicate reminders".to_string() }),
+                Some(cin) => match seen.get(cin) {
+                    Some(first) => errors.push(FieldError::OutOfRange { field, reason: format!("'{}' is already used by companies[{}]", cin, first) }),
+                    None => {
+                        seen.insert(cin, i);
+                    }
+                },
+            }
+        }
+        errors
+    }
+}
+
+// The smallest lead window the deadline has entered, so a missed run catches up without double-sending
+pub fn due_lead(lead_days: &[i64], today: NaiveDate, due: NaiveDate) -> Option<i64> {
+    let days_left = (due - today).num_days();
+    if days_left < 0 {
+        return None;
+    }
+    lead_days.iter().copied().find(|lead| days_left <= *lead)
+}
+
+// The configured FY and every later one that has closed, latest two only
+fn fy_ends(configured: NaiveDate, today: NaiveDate) -> Vec<(NaiveDate, bool)> {
+    let closed: Vec<_> = (0..)
+        .map(|years| configured + Months::new(12 * years))
+        .take_while(|d| *d < today)
+        .collect();
+    closed.iter().rev().take(2).map(|d| (*d, *d == configured)).collect()
+}
+
+fn obligations(company: &CalendarQuery, registry: &CircularRegistry, today: NaiveDate) -> Vec<CalendarEntry> {
+    fy_ends(company.fy_end_date, today).into_iter()
+        .flat_map(|(fy_end, configured)| {
+            // Rolled-forward years are no longer the first FY and carry no AGM extension
+            let mut q = company.clone();
+            q.fy_end_date = fy_end;
+            if !configured {
+                q.first_fy = false;
+                q.agm_extension_months = 0;
+            }
+            build_calendar(&q, registry).obligations
+        })
+        .collect()
+}
+
+fn transport(smtp: &SmtpConfig) -> Result<AsyncSmtpTransport<Tokio1Executor>, String> {
+    let builder = match smtp.security {
+        SmtpSecurity::None => AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(&smtp.host),
+        SmtpSecurity::StartTls => AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&smtp.host).map_err(|e| e.to_string())?,
+        SmtpSecurity::Tls => AsyncSmtpTransport::<Tokio1Executor>::relay(&smtp.host).map_err(|e| e.to_string())?,
+    };
+    let builder = builder.port(smtp.port);
+    Ok(match (&smtp.username, &smtp.password) {
+        (Some(user), Some(pass)) => builder.credentials(Credentials::new(user.clone(), pass.clone())).build(),
+        _ => builder.build(),
+    })
+}
+
+fn compose(from: &Mailbox, to: &str, cin: &str, entry: &CalendarEntry, days_left: i64) -> Result<Message, String> {
+    let to: Mailbox = to.parse().map_err(|e| format!("{}: {}", to, e))?;
+    let mut body = format!(
+        "{} for {} is due on {} ({} day(s) from today).\n\n{}\nSection: {}\nIf missed: {}\n",
+        entry.form, cin, entry.due_date, days_left, entry.description, entry.act_section, fees::late_filing_consequence(&entry.form)
+    );
+    if let Some(c) = &entry.circular {
+        body.push_str(&format!("Due date extended by {} ({})\n", c.id, c.title));
+    }
+    Message::builder()
+        .from(from.clone())
+        .to(to)
+        .subject(format!("[Pratyaksh] {} due {} for {}", entry.form, entry.due_date, cin))
+        .header(ContentType::TEXT_PLAIN)
+        .body(body)
+        .map_err(|e| e.to_string())
+}
+
+pub struct ReminderScheduler {
+    config: ReminderConfig,
+    registry: Arc<CircularRegistry>,
+    sent: HashSet<String>,
+}
+
+impl ReminderScheduler {
+    pub fn new(config: ReminderConfig, registry: Arc<CircularRegistry>) -> Self {
+        let sent = std::fs::read_to_string(&config.log_path)
+            .map(|raw| raw.lines().filter_map(|l| serde_json::from_str::<Delivery>(l).ok()).map(|d| d.key()).collect())
+            .unwrap_or_default();
+        ReminderScheduler { config, registry, sent }
+    }
+
+    fn record(&mut self, delivery: Delivery) {
+        let line = serde_json::to_string(&delivery).expect("delivery serializes");
+        let appended = append_line(&self.config.log_path, &line);
+        if let Err(e) = appended {
+            tracing::error!("Could not write reminder log {}: {}", self.config.log_path.display(), e);
+        }
+        self.sent.insert(delivery.key());
+    }
+
+    // One pass over every company; returns how many reminders went out
+    pub async fn run_once(&mut self, today: NaiveDate) -> usize {
+        let mailer = match transport(&self.config.smtp) {
+            Ok(m) => m,
+            Err(e) => {
+                tracing::error!("SMTP transport unavailable: {}", e);
+                return 0;
+            }
+        };
+        let from: Mailbox = match self.config.smtp.from.parse() {
+            Ok(f) => f,
+            Err(e) => {
+                tracing::error!("Invalid SMTP from address {}: {}", self.config.smtp.from, e);
+                return 0;
+            }
+        };
+
+        let mut pending = Vec::new();
+        for company in &self.config.companies {
+            let cin = company.calendar.cin.as_deref().unwrap_or_default().trim().to_string(); // Required and unique, see check()
+            let recipients: Vec<String> = std::iter::once(company.staff_email.clone()).chain(company.client_email.clone()).collect();
+            for entry in obligations(&company.calendar, &self.registry, today) {
+                let Some(lead) = due_lead(&self.config.lead_days, today, entry.due_date) else { continue };
+                for recipient in &recipients {
+                    let delivery = Delivery {
+                        sent_at: String::new(),
+                        cin: cin.clone(),
+                        form: entry.form.clone(),
+                        due_date: entry.due_date,
+                        lead_days: lead,
+                        recipient: recipient.clone(),
+                    };
+                    if !self.sent.contains(&delivery.key()) {
+                        pending.push((delivery, entry.clone()));
+                    }
+                }
+            }
+        }
+
+        let mut sent = 0;
+        for (mut delivery, entry) in pending {
+            let days_left = (entry.due_date - today).num_days();
+            let result = match compose(&from, &delivery.recipient, &delivery.cin, &entry, days_left) {
+                Ok(message) => mailer.send(message).await.map_err(|e| e.to_string()),
+                Err(e) => Err(e),
+            };
+            match result {
+                Ok(_) => {
+                    delivery.sent_at = Local::now().to_rfc3339();
+                    self.record(delivery);
+                    sent += 1;
+                }
+                // Left out of the log so the next run retries it
+                Err(e) => tracing::warn!("Reminder to {} for {} {} failed: {}", delivery.recipient, delivery.cin, delivery.form, e),
+            }
+        }
+        sent
+    }
+
+    // Runs immediately to catch up, then every day at `run_at`
+    pub fn spawn(mut self) {
+        tokio::spawn(async move {
+            loop {
+                let sent = self.run_once(Local::now().date_naive()).await;
+                tracing::info!("Reminder run complete: {} sent", sent);
+
+                let now = Local::now().naive_local();
+                let mut next = now.date().and_time(self.config.run_at);
+                if next <= now {
+                    next += chrono::Duration::days(1);
+                }
+                let wait = (next - now).to_std().unwrap_or_default();
+                tokio::time::sleep(wait).await;
+            }
+        });
+    }
+}
+
+fn append_line(path: &Path, line: &str) -> std::io::Result<()> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    let mut file = std::fs::OpenOptions::new().create(true).append(true).open(path)?;
+    writeln!(file, "{}", line)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn d(y: i32, m: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, day).unwrap()
+    }
+
+    fn scratch_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("pratyaksh-reminders-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn delivery(recipient: &str, lead_days: i64) -> Delivery {
+        Delivery {
+            sent_at: "2024-10-23T08:00:00+05:30".to_string(),
+            cin: "U1".to_string(),
+            form: "AOC-4".to_string(),
+            due_date: d(2024, 10, 30),
+            lead_days,
+            recipient: recipient.to_string(),
+        }
+    }
+
+    #[test]
+    fn due_lead_picks_smallest_window_entered() {
+        let leads = [1, 7];
+        let due = d(2024, 10, 30);
+        assert_eq!(due_lead(&leads, d(2024, 10, 22), due), None);
+        assert_eq!(due_lead(&leads, d(2024, 10, 23), due), Some(7));
+        // A run missed on the 23rd still sends the 7-day reminder on the 26th
+        assert_eq!(due_lead(&leads, d(2024, 10, 26), due), Some(7));
+        assert_eq!(due_lead(&leads, d(2024, 10, 29), due), Some(1));
+        assert_eq!(due_lead(&leads, due, due), Some(1));
+        assert_eq!(due_lead(&leads, d(2024, 10, 31), due), None);
+    }
+
+    #[test]
+    fn fy_ends_rolls_forward_to_the_latest_two_closed_years() {
+        assert_eq!(fy_ends(d(2024, 3, 31), d(2024, 3, 31)), []);
+        assert_eq!(fy_ends(d(2024, 3, 31), d(2024, 6, 1)), [(d(2024, 3, 31), true)]);
+        assert_eq!(fy_ends(d(2022, 3, 31), d(2025, 6, 1)), [(d(2025, 3, 31), false), (d(2024, 3, 31), false)]);
+    }
+
+    // Rolled-forward years drop the first-FY flag and the AGM extension of the configured year
+    #[test]
+    fn rolled_forward_years_use_the_regular_agm() {
+        let company: CalendarQuery = serde_json::from_value(serde_json::json!({
+            "fy_end_date": "2024-03-31",
+            "incorporation_date": "2023-05-01",
+            "company_type": "private",
+            "first_fy": true,
+        }))
+        .unwrap();
+        let aoc4: Vec<NaiveDate> = obligations(&company, &CircularRegistry::bundled(), d(2025, 6, 1)).into_iter()
+            .filter(|e| e.form == "AOC-4")
+            .map(|e| e.due_date)
+            .collect();
+        assert_eq!(aoc4, [d(2025, 10, 30), d(2025, 1, 30)]);
+    }
+
+    // The key covers everything that makes a reminder distinct except when it was sent
+    #[test]
+    fn delivery_key_ignores_send_time() {
+        let mut resent = delivery("staff@firm.example", 7);
+        resent.sent_at = "2024-10-24T08:00:00+05:30".to_string();
+        assert_eq!(delivery("staff@firm.example", 7).key(), resent.key());
+        assert_ne!(delivery("staff@firm.example", 7).key(), delivery("staff@firm.example", 1).key());
+        assert_ne!(delivery("staff@firm.example", 7).key(), delivery("client@example.com", 7).key());
+    }
+
+    #[test]
+    fn delivery_log_is_replayed_on_start() {
+        let dir = scratch_dir("log");
+        let log_path = dir.join(LOG_FILE);
+        let first = serde_json::to_string(&delivery("staff@firm.example", 7)).unwrap();
+        std::fs::write(&log_path, format!("{}\nnot json\n", first)).unwrap();
+
+        let config: ReminderConfig = serde_json::from_value(serde_json::json!({
+            "log_path": log_path,
+            "smtp": { "host": "localhost", "port": 1025, "from": "reminders@firm.example" },
+            "companies": [],
+        }))
+        .unwrap();
+        let mut scheduler = ReminderScheduler::new(config, Arc::new(CircularRegistry::bundled()));
+        assert!(scheduler.sent.contains(&delivery("staff@firm.example", 7).key()));
+        assert_eq!(scheduler.sent.len(), 1);
+
+        scheduler.record(delivery("staff@firm.example", 1));
+        let replayed = ReminderScheduler::new(ReminderConfig { log_path, ..scheduler.config }, Arc::new(CircularRegistry::bundled()));
+        assert_eq!(replayed.sent.len(), 2);
+        std::fs::remove_dir_all(dir).ok();
+    }
+
+    #[test]
+    fn load_reports_every_invalid_entry() {
+        let dir = scratch_dir("invalid");
+        std::fs::write(dir.join(REMINDERS_FILE), serde_json::json!({
+            "lead_days": [7, -1],
+            "smtp": { "host": "localhost", "port": 1025, "from": "not an address" },
+            "companies": [{
+                "fy_end_date": "2024-03-31",
+                "incorporation_date": "2024-06-01",
+                "company_type": "opc",
+                "agm_extension_months": 2,
+                "staff_email": "staff@firm.example",
+                "client_email": "nobody",
+            }],
+        }).to_string()).unwrap();
+
+        let errors = ReminderConfig::load(&dir).err().unwrap();
+        let fields: Vec<&str> = errors.iter().map(|e| e.split(": ").nth(1).unwrap()).collect();
+        assert_eq!(fields, [
+            "smtp.from",
+            "companies[0].client_email",
+            "lead_days",
+            "companies[0].incorporation_date",
+            "companies[0].agm_extension_months",
+            "companies[0].cin",
+        ]);
+        std::fs::remove_dir_all(dir).ok();
+    }
+
+    // Without a distinct CIN two companies would share delivery-log keys and suppress each other's reminders
+    #[test]
+    fn every_company_needs_its_own_cin() {
+        let company = |cin: Option<&str>| serde_json::json!({
+            "cin": cin,
+            "fy_end_date": "2024-03-31",
+            "incorporation_date": "2020-06-01",
+            "company_type": "private",
+            "staff_email": "staff@firm.example",
+        });
+        let config: ReminderConfig = serde_json::from_value(serde_json::json!({
+            "smtp": { "host": "localhost", "port": 1025, "from": "reminders@firm.example" },
+            "companies": [company(Some("U1")), company(None), company(Some(" ")), company(Some("U1"))],
+        }))
+        .unwrap();
+        let errors: Vec<(String, String)> = config.check().iter().map(|e| (e.field().to_string(), e.reason())).collect();
+        assert_eq!(errors, [
+            ("companies[1].cin".to_string(), "is required to label and de-duplicate reminders".to_string()),
+            ("companies[2].cin".to_string(), "is required to label and de-duplicate reminders".to_string()),
+            ("companies[3].cin".to_string(), "'U1' is already used by companies[0]".to_string()),
+        ]);
+    }
+
+    #[test]
+    fn load_defaults_and_sorts_lead_days() {
+        let dir = scratch_dir("valid");
+        assert!(ReminderConfig::load(&dir).unwrap().is_none());
+
+        std::fs::copy(concat!(env!("CARGO_MANIFEST_DIR"), "/data/reminders.example.json"), dir.join(REMINDERS_FILE)).unwrap();
+        let config = ReminderConfig::load(&dir).unwrap().unwrap();
+        assert_eq!(config.lead_days, [1, 7]);
+        assert_eq!(config.log_path, dir.join(LOG_FILE));
+        assert_eq!(config.companies.len(), 1);
+        std::fs::remove_dir_all(dir).ok();
+    }
+}
@@ -0,0 +1,718 @@
+use chrono::{NaiveDate, Utc};
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use crate::calendar::{CompanyProfile, CompanyType};
+use crate::errors::ApiError;
+use pratyaksh_core::license::LicenseClaims;
+
+pub use pratyaksh_core::risk::Filing;
+
+// Embedded SQLite store for firms, their API keys, companies, the filings made for them and risk snapshots,
+// plus the desktop licenses issued by the operator and the UPI payment orders behind them.
+// Every company row belongs to one firm; FirmStore is the only way handlers reach tenant data.
+
+pub const DB_PATH_ENV: &str = "PRATYAKSH_DB";
+const DB_FILE: &str = "pratyaksh.db";
+
+const SCHEMA: &str = "
+    PRAGMA foreign_keys = ON;
+    CREATE TABLE IF NOT EXISTS firms (
+        id TEXT PRIMARY KEY,
+        name TEXT NOT NULL,
+        created_at TEXT NOT NULL
+    );
+    CREATE TABLE IF NOT EXISTS api_keys (
+        id INTEGER PRIMARY KEY,
+        firm_id TEXT NOT NULL REFERENCES firms(id) ON DELETE CASCADE,
+        name TEXT NOT NULL,
+        key_hash TEXT NOT NULL UNIQUE,
+        scope TEXT NOT NULL,
+        created_at TEXT NOT NULL,
+        revoked_at TEXT
+    );
+    CREATE TABLE IF NOT EXISTS companies (
+        firm_id TEXT NOT NULL REFERENCES firms(id) ON DELETE CASCADE,
+        cin TEXT NOT NULL,
+        name TEXT NOT NULL,
+        fy_end_date TEXT NOT NULL,
+        incorporation_date TEXT NOT NULL,
+        company_type TEXT NOT NULL,
+        first_fy INTEGER NOT NULL,
+        agm_extension_months INTEGER NOT NULL,
+        has_subsidiaries INTEGER NOT NULL,
+        PRIMARY KEY (firm_id, cin)
+    );
+    CREATE TABLE IF NOT EXISTS filings (
+        id INTEGER PRIMARY KEY,
+        firm_id TEXT NOT NULL,
+        cin TEXT NOT NULL,
+        form TEXT NOT NULL,
+        fy_end_date TEXT NOT NULL,
+        srn TEXT NOT NULL,
+        filing_date TEXT NOT NULL,
+        UNIQUE (firm_id, srn),
+        FOREIGN KEY (firm_id, cin) REFERENCES companies(firm_id, cin) ON DELETE CASCADE
+    );
+    CREATE TABLE IF NOT EXISTS risk_snapshots (
+        id INTEGER PRIMARY KEY,
+        firm_id TEXT NOT NULL,
+        cin TEXT NOT NULL,
+        taken_at TEXT NOT NULL,
+        as_of TEXT NOT NULL,
+        overall_risk TEXT NOT NULL,
+        penalty_exposure INTEGER NOT NULL,
+        report TEXT NOT NULL,
+        FOREIGN KEY (firm_id, cin) REFERENCES companies(firm_id, cin) ON DELETE CASCADE
+    );
+    CREATE TABLE IF NOT EXISTS licenses (
+        id TEXT PRIMARY KEY,
+        user_id TEXT NOT NULL,
+        email TEXT NOT NULL,
+        plan TEXT NOT NULL,
+        device_id TEXT NOT NULL,
+        txn_id TEXT,
+        issued_at TEXT NOT NULL,
+        expires_on TEXT NOT NULL
+    );
+    CREATE UNIQUE INDEX IF NOT EXISTS licenses_txn_id ON licenses (txn_id);
+    CREATE TABLE IF NOT EXISTS payment_orders (
+        txn_ref TEXT PRIMARY KEY,
+        plan TEXT NOT NULL,
+        amount INTEGER NOT NULL,
+        user_id TEXT NOT NULL,
+        created_at TEXT NOT NULL
+    );
+";
+
+#[derive(Deserialize, Serialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum Scope {
+    Read,  // GET endpoints and the stateless calculators
+    Write, // Also creates, updates and deletes companies and filings
+}
+
+impl Scope {
+    fn as_str(self) -> &'static str {
+        match self {
+            Scope::Read => "read",
+            Scope::Write => "write",
+        }
+    }
+
+    fn parse(raw: &str) -> Scope {
+        if raw == "write" { Scope::Write } else { Scope::Read }
+    }
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct Firm {
+    pub id: String,
+    pub name: String,
+    pub created_at: String,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct ApiKey {
+    pub id: i64,
+    pub firm_id: String,
+    pub name: String,
+    pub scope: Scope,
+    pub created_at: String,
+    pub revoked_at: Option<String>,
+}
+
+// The firm and scope an unrevoked key grants; attached to each authenticated request
+#[derive(Clone, Debug)]
+pub struct Caller {
+    pub key_id: i64,
+    pub firm_id: String,
+    pub scope: Scope,
+}
+
+#[derive(Deserialize, Serialize, Clone, ToSchema)]
+pub struct Company {
+    pub cin: String,
+    pub name: String,
+    pub fy_end_date: NaiveDate,
+    pub incorporation_date: NaiveDate,
+    pub company_type: CompanyType,
+    #[serde(default)]
+    pub first_fy: bool,
+    #[serde(default)]
+    pub agm_extension_months: u32,
+    #[serde(default)]
+    pub has_subsidiaries: bool,
+}
+
+impl Company {
+    pub fn profile(&self) -> CompanyProfile {
+        CompanyProfile {
+            company_type: self.company_type,
+            first_fy: self.first_fy,
+            agm_extension_months: self.agm_extension_months,
+        }
+    }
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct NewFiling {
+    pub form: String,
+    pub fy_end_date: NaiveDate, // Financial year the filing relates to
+    pub srn: String,            // Service Request Number issued by the MCA portal
+    pub filing_date: NaiveDate,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct RiskSnapshot {
+    pub id: i64,
+    pub cin: String,
+    pub taken_at: String,
+    pub as_of: NaiveDate,
+    pub overall_risk: String,
+    pub penalty_exposure: i64,
+    pub report: serde_json::Value,
+}
+
+// Issued desktop license; the signed file itself is only returned at issue time
+#[derive(Serialize, ToSchema)]
+pub struct License {
+    #[serde(flatten)]
+    pub claims: LicenseClaims,
+    pub txn_id: Option<String>, // Payment the license was issued against
+}
+
+// One UPI payment attempt for a plan; txn_ref is what appears on the settlement
+#[derive(Serialize, ToSchema)]
+pub struct PaymentOrder {
+    pub txn_ref: String,
+    pub plan: String,
+    pub amount: i64, // Paise, fixed from the plan when the order is created
+    pub user_id: String,
+    pub created_at: String,
+}
+
+#[derive(Clone)]
+pub struct Store {
+    conn: Arc<Mutex<Connection>>,
+}
+
+// Store view limited to one firm's companies, filings and snapshots
+#[derive(Clone)]
+pub struct FirmStore {
+    conn: Arc<Mutex<Connection>>,
+    firm: String,
+}
+
+fn company_type_str(t: CompanyType) -> String {
+    serde_json::to_value(t).ok().and_then(|v| v.as_str().map(str::to_string)).unwrap_or_default()
+}
+
+// An unrecognised company_type fails the read rather than quietly becoming the default type
+fn company_from_row(row: &Row) -> rusqlite::Result<Company> {
+    let raw: String = row.get(4)?;
+    let company_type = serde_json::from_value(serde_json::Value::String(raw))
+        .map_err(|e| rusqlite::Error::FromSqlConversionFailure(4, rusqlite::types::Type::Text, Box::new(e)))?;
+    Ok(Company {
+        cin: row.get(0)?,
+        name: row.get(1)?,
+        fy_end_date: row.get(2)?,
+        incorporation_date: row.get(3)?,
+        company_type,
+        first_fy: row.get(5)?,
+        agm_extension_months: row.get(6)?,
+        has_subsidiaries: row.get(7)?,
+    })
+}
+
+fn key_from_row(row: &Row) -> rusqlite::Result<ApiKey> {
+    let scope: String = row.get(3)?;
+    Ok(ApiKey {
+        id: row.get(0)?,
+        firm_id: row.get(1)?,
+        name: row.get(2)?,
+        scope: Scope::parse(&scope),
+        created_at: row.get(4)?,
+        revoked_at: row.get(5)?,
+    })
+}
+
+fn license_from_row(row: &Row) -> rusqlite::Result<License> {
+    Ok(License {
+        claims: LicenseClaims {
+            license_id: row.get(0)?,
+            user_id: row.get(1)?,
+            email: row.get(2)?,
+            plan: row.get(3)?,
+            device_id: row.get(4)?,
+            issued_at: row.get(5)?,
+            expires_on: row.get(6)?,
+        },
+        txn_id: row.get(7)?,
+    })
+}
+
+fn filing_from_row(row: &Row) -> rusqlite::Result<Filing> {
+    Ok(Filing {
+        id: row.get(0)?,
+        cin: row.get(1)?,
+        form: row.get(2)?,
+        fy_end_date: row.get(3)?,
+        srn: row.get(4)?,
+        filing_date: row.get(5)?,
+    })
+}
+
+const COMPANY_COLUMNS: &str = "cin, name, fy_end_date, incorporation_date, company_type, first_fy, agm_extension_months, has_subsidiaries";
+const FILING_COLUMNS: &str = "id, cin, form, fy_end_date, srn, filing_date";
+const KEY_COLUMNS: &str = "id, firm_id, name, scope, created_at, revoked_at";
+const LICENSE_COLUMNS: &str = "id, user_id, email, plan, device_id, issued_at, expires_on, txn_id";
+
+// rusqlite blocks on disk I/O and the connection mutex, so handlers run store work on Tokio's
+// blocking pool; a slow query then holds a pool thread instead of an async worker
+async fn run_blocking<T, E>(f: impl FnOnce() -> Result<T, E> + Send + 'static) -> Result<T, ApiError>
+where
+    T: Send + 'static,
+    E: Send + 'static,
+    ApiError: From<E>,
+{
+    match tokio::task::spawn_blocking(f).await {
+        Ok(result) => result.map_err(ApiError::from),
+        Err(e) => {
+            tracing::error!("Store task failed: {}", e);
+            Err(ApiError::Internal("the request could not be completed".to_string()))
+        }
+    }
+}
+
+impl Store {
+    // Opens $PRATYAKSH_DB or <data_dir>/pratyaksh.db, creating the schema on first use
+    pub fn open(data_dir: &Path) -> rusqlite::Result<Self> {
+        let path = std::env::var(DB_PATH_ENV).map(PathBuf::from).unwrap_or_else(|_| data_dir.join(DB_FILE));
+        Self::open_at(&path)
+    }
+
+    pub fn open_at(path: &Path) -> rusqlite::Result<Self> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).ok();
+        }
+        let conn = Connection::open(path)?;
+        conn.execute_batch(SCHEMA)?;
+        Ok(Store { conn: Arc::new(Mutex::new(conn)) })
+    }
+
+    #[cfg(test)]
+    pub fn in_memory() -> Self {
+        Self::open_at(Path::new(":memory:")).expect("in-memory store")
+    }
+
+    fn conn(&self) -> std::sync::MutexGuard<'_, Connection> {
+        self.conn.lock().expect("store mutex poisoned")
+    }
+
+    pub fn for_firm(&self, firm_id: &str) -> FirmStore {
+        FirmStore { conn: self.conn.clone(), firm: firm_id.to_string() }
+    }
+
+    pub async fn run<T, E>(&self, f: impl FnOnce(&Store) -> Result<T, E> + Send + 'static) -> Result<T, ApiError>
+    where
+        T: Send + 'static,
+        E: Send + 'static,
+        ApiError: From<E>,
+    {
+        let store = self.clone();
+        run_blocking(move || f(&store)).await
+    }
+
+    // Readiness probe: the database file is still open and readable
+    pub fn ping(&self) -> rusqlite::Result<()> {
+        self.conn().query_row("SELECT COUNT(*) FROM firms", [], |_| Ok(()))
+    }
+
+    // FIRMS
+    pub fn insert_firm(&self, id: &str, name: &str) -> rusqlite::Result<Firm> {
+        let created_at = Utc::now().to_rfc3339();
+        self.conn().execute("INSERT INTO firms (id, name, created_at) VALUES (?1, ?2, ?3)", params![id, name, created_at])?;
+        Ok(Firm { id: id.to_string(), name: name.to_string(), created_at })
+    }
+
+    pub fn firms(&self) -> rusqlite::Result<Vec<Firm>> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare("SELECT id, name, created_at FROM firms ORDER BY id")?;
+        let rows = stmt.query_map([], |row| Ok(Firm { id: row.get(0)?, name: row.get(1)?, created_at: row.get(2)? }))?;
+        rows.collect()
+    }
+
+    pub fn firm_exists(&self, id: &str) -> rusqlite::Result<bool> {
+        Ok(self.conn().query_row("SELECT 1 FROM firms WHERE id = ?1", params![id], |_| Ok(())).optional()?.is_some())
+    }
+
+    // API KEYS
+    // Only the SHA-256 of a key is kept; the plaintext is shown once at creation
+    pub fn insert_key(&self, firm_id: &str, name: &str, scope: Scope, key_hash: &str) -> rusqlite::Result<ApiKey> {
+        let created_at = Utc::now().to_rfc3339();
+        let conn = self.conn();
+        conn.execute(
+            "INSERT INTO api_keys (firm_id, name, key_hash, scope, created_at) VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![firm_id, name, key_hash, scope.as_str(), created_at],
+        )?;
+        Ok(ApiKey { id: conn.last_insert_rowid(), firm_id: firm_id.to_string(), name: name.to_string(), scope, created_at, revoked_at: None })
+    }
+
+    pub fn keys(&self, firm_id: &str) -> rusqlite::Result<Vec<ApiKey>> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare(&format!("SELECT {} FROM api_keys WHERE firm_id = ?1 ORDER BY id", KEY_COLUMNS))?;
+        let rows = stmt.query_map(params![firm_id], key_from_row)?;
+        rows.collect()
+    }
+
+    // None when there is no such key; revoking twice keeps the first revocation time
+    pub fn revoke_key(&self, firm_id: &str, id: i64) -> rusqlite::Result<Option<ApiKey>> {
+        let conn = self.conn();
+        conn.execute(
+            "UPDATE api_keys SET revoked_at = COALESCE(revoked_at, ?3) WHERE firm_id = ?1 AND id = ?2",
+            params![firm_id, id, Utc::now().to_rfc3339()],
+        )?;
+        conn.query_row(&format!("SELECT {} FROM api_keys WHERE firm_id = ?1 AND id = ?2", KEY_COLUMNS), params![firm_id, id], key_from_row)
+            .optional()
+    }
+
+    pub fn grant(&self, key_hash: &str) -> rusqlite::Result<Option<Caller>> {
+        self.conn()
+            .query_row(
+                "SELECT id, firm_id, scope FROM api_keys WHERE key_hash = ?1 AND revoked_at IS NULL",
+                params![key_hash],
+                |row| {
+                    let scope: String = row.get(2)?;
+                    Ok(Caller { key_id: row.get(0)?, firm_id: row.get(1)?, scope: Scope::parse(&scope) })
+                },
+            )
+            .optional()
+    }
+
+    // LICENSES
+    pub fn insert_license(&self, l: &License) -> rusqlite::Result<()> {
+        let c = &l.claims;
+        self.conn().execute(
+            &format!("INSERT INTO licenses ({}) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)", LICENSE_COLUMNS),
+            params![c.license_id, c.user_id, c.email, c.plan, c.device_id, c.issued_at, c.expires_on, l.txn_id],
+        )?;
+        Ok(())
+    }
+
+    // The license already issued against a payment, if any
+    pub fn license_for_txn(&self, txn_id: &str) -> rusqlite::Result<Option<License>> {
+        self.conn()
+            .query_row(&format!("SELECT {} FROM licenses WHERE txn_id = ?1", LICENSE_COLUMNS), params![txn_id], license_from_row)
+            .optional()
+    }
+
+    pub fn licenses(&self) -> rusqlite::Result<Vec<License>> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare(&format!("SELECT {} FROM licenses ORDER BY issued_at DESC", LICENSE_COLUMNS))?;
+        let rows = stmt.query_map([], license_from_row)?;
+        rows.collect()
+    }
+
+    // PAYMENT ORDERS
+    pub fn insert_order(&self, txn_ref: &str, plan: &str, amount: i64, user_id: &str) -> rusqlite::Result<PaymentOrder> {
+        let created_at = Utc::now().to_rfc3339();
+        self.conn().execute(
+            "INSERT INTO payment_orders (txn_ref, plan, amount, user_id, created_at) VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![txn_ref, plan, amount, user_id, created_at],
+        )?;
+        Ok(PaymentOrder { txn_ref: txn_ref.to_string(), plan: plan.to_string(), amount, user_id: user_id.to_string(), created_at })
+    }
+
+    // Latest order of this user for the plan, created since `since` (RFC 3339) and not yet licensed
+    pub fn open_order(&self, user_id: &str, plan: &str, since: &str) -> rusqlite::Result<Option<PaymentOrder>> {
+        self.conn()
+            .query_row(
+                "SELECT txn_ref, plan, amount, user_id, created_at FROM payment_orders o
+                 WHERE user_id = ?1 AND plan = ?2 AND created_at >= ?3
+                   AND NOT EXISTS (SELECT 1 FROM licenses l WHERE l.txn_id = o.txn_ref)
+                 ORDER BY created_at DESC LIMIT 1",
+                params![user_id, plan, since],
+                |row| Ok(PaymentOrder { txn_ref: row.get(0)?, plan: row.get(1)?, amount: row.get(2)?, user_id: row.get(3)?, created_at: row.get(4)? }),
+            )
+            .optional()
+    }
+
+    pub fn order(&self, txn_ref: &str) -> rusqlite::Result<Option<PaymentOrder>> {
+        self.conn()
+            .query_row(
+                "SELECT txn_ref, plan, amount, user_id, created_at FROM payment_orders WHERE txn_ref = ?1",
+                params![txn_ref],
+                |row| Ok(PaymentOrder { txn_ref: row.get(0)?, plan: row.get(1)?, amount: row.get(2)?, user_id: row.get(3)?, created_at: row.get(4)? }),
+            )
+            .optional()
+    }
+}
+
+impl FirmStore {
+    fn conn(&self) -> std::sync::MutexGuard<'_, Connection> {
+        self.conn.lock().expect("store mutex poisoned")
+    }
+
+    pub async fn run<T, E>(&self, f: impl FnOnce(&FirmStore) -> Result<T, E> + Send + 'static) -> Result<T, ApiError>
+    where
+        T: Send + 'static,
+        E: Send + 'static,
+        ApiError: From<E>,
+    {
+        let store = self.clone();
+        run_blocking(move || f(&store)).await
+    }
+
+    // COMPANIES
+    pub fn insert_company(&self, c: &Company) -> rusqlite::Result<()> {
+        self.conn().execute(
+            &format!("INSERT INTO companies (firm_id, {}) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)", COMPANY_COLUMNS),
+            params![self.firm, c.cin, c.name, c.fy_end_date, c.incorporation_date, company_type_str(c.company_type), c.first_fy, c.agm_extension_months, c.has_subsidiaries],
+        )?;
+        Ok(())
+    }
+
+    // false when the firm has no company with that CIN
+    pub fn update_company(&self, c: &Company) -> rusqlite::Result<bool> {
+        let changed = self.conn().execute(
+            "UPDATE companies SET name = ?3, fy_end_date = ?4, incorporation_date = ?5, company_type = ?6, first_fy = ?7, agm_extension_months = ?8, has_subsidiaries = ?9 WHERE firm_id = ?1 AND cin = ?2",
+            params![self.firm, c.cin, c.name, c.fy_end_date, c.incorporation_date, company_type_str(c.company_type), c.first_fy, c.agm_extension_months, c.has_subsidiaries],
+        )?;
+        Ok(changed > 0)
+    }
+
+    pub fn delete_company(&self, cin: &str) -> rusqlite::Result<bool> {
+        Ok(self.conn().execute("DELETE FROM companies WHERE firm_id = ?1 AND cin = ?2", params![self.firm, cin])? > 0)
+    }
+
+    pub fn company(&self, cin: &str) -> rusqlite::Result<Option<Company>> {
+        self.conn()
+            .query_row(&format!("SELECT {} FROM companies WHERE firm_id = ?1 AND cin = ?2", COMPANY_COLUMNS), params![self.firm, cin], company_from_row)
+            .optional()
+    }
+
+    pub fn companies(&self) -> rusqlite::Result<Vec<Company>> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare(&format!("SELECT {} FROM companies WHERE firm_id = ?1 ORDER BY cin", COMPANY_COLUMNS))?;
+        let rows = stmt.query_map(params![self.firm], company_from_row)?;
+        rows.collect()
+    }
+
+    // FILINGS
+    pub fn insert_filing(&self, cin: &str, f: &NewFiling) -> rusqlite::Result<Filing> {
+        let conn = self.conn();
+        conn.execute(
+            "INSERT INTO filings (firm_id, cin, form, fy_end_date, srn, filing_date) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![self.firm, cin, f.form, f.fy_end_date, f.srn, f.filing_date],
+        )?;
+        Ok(Filing {
+            id: conn.last_insert_rowid(),
+            cin: cin.to_string(),
+            form: f.form.clone(),
+            fy_end_date: f.fy_end_date,
+            srn: f.srn.clone(),
+            filing_date: f.filing_date,
+        })
+    }
+
+    pub fn update_filing(&self, cin: &str, id: i64, f: &NewFiling) -> rusqlite::Result<Option<Filing>> {
+        let changed = self.conn().execute(
+            "UPDATE filings SET form = ?4, fy_end_date = ?5, srn = ?6, filing_date = ?7 WHERE firm_id = ?1 AND cin = ?2 AND id = ?3",
+            params![self.firm, cin, id, f.form, f.fy_end_date, f.srn, f.filing_date],
+        )?;
+        if changed == 0 {
+            return Ok(None);
+        }
+        Ok(Some(Filing { id, cin: cin.to_string(), form: f.form.clone(), fy_end_date: f.fy_end_date, srn: f.srn.clone(), filing_date: f.filing_date }))
+    }
+
+    pub fn delete_filing(&self, cin: &str, id: i64) -> rusqlite::Result<bool> {
+        Ok(self.conn().execute("DELETE FROM filings WHERE firm_id = ?1 AND cin = ?2 AND id = ?3", params![self.firm, cin, id])? > 0)
+    }
+
+    pub fn filings(&self, cin: &str) -> rusqlite::Result<Vec<Filing>> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare(&format!("SELECT {} FROM filings WHERE firm_id = ?1 AND cin = ?2 ORDER BY filing_date, id", FILING_COLUMNS))?;
+        let rows = stmt.query_map(params![self.firm, cin], filing_from_row)?;
+        rows.collect()
+    }
+
+    // Earliest filing of `form` for the FY, which is the one that stops the additional fee
+    pub fn filing_for(&self, cin: &str, form: &str, fy_end: NaiveDate) -> rusqlite::Result<Option<Filing>> {
+        self.conn()
+            .query_row(
+                &format!("SELECT {} FROM filings WHERE firm_id = ?1 AND cin = ?2 AND form = ?3 AND fy_end_date = ?4 ORDER BY filing_date LIMIT 1", FILING_COLUMNS),
+                params![self.firm, cin, form, fy_end],
+                filing_from_row,
+            )
+            .optional()
+    }
+
+    // RISK SNAPSHOTS
+    pub fn insert_snapshot(&self, cin: &str, as_of: NaiveDate, overall_risk: &str, penalty_exposure: i64, report: &serde_json::Value) -> rusqlite::Result<RiskSnapshot> {
+        let taken_at = Utc::now().to_rfc3339();
+        let conn = self.conn();
+        conn.execute(
+            "INSERT INTO risk_snapshots (firm_id, cin, taken_at, as_of, overall_risk, penalty_exposure, report) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![self.firm, cin, taken_at, as_of, overall_risk, penalty_exposure, report.to_string()],
+        )?;
+        Ok(RiskSnapshot {
+            id: conn.last_insert_rowid(),
+            cin: cin.to_string(),
+            taken_at,
+            as_of,
+            overall_risk: overall_risk.to_string(),
+            penalty_exposure,
+            report: report.clone(),
+        })
+    }
+
+    pub fn snapshots(&self, cin: &str) -> rusqlite::Result<Vec<RiskSnapshot>> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare("SELECT id, cin, taken_at, as_of, overall_risk, penalty_exposure, report FROM risk_snapshots WHERE firm_id = ?1 AND cin = ?2 ORDER BY id DESC")?;
+        let rows = stmt.query_map(params![self.firm, cin], |row| {
+            let report: String = row.get(6)?;
+            Ok(RiskSnapshot {
+                id: row.get(0)?,
+                cin: row.get(1)?,
+                taken_at: row.get(2)?,
+                as_of: row.get(3)?,
+                overall_risk: row.get(4)?,
+                penalty_exposure: row.get(5)?,
+                report: serde_json::from_str(&report).unwrap_or(serde_json::Value::Null),
+            })
+        })?;
+        rows.collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn d(y: i32, m: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, day).unwrap()
+    }
+
+    fn company(cin: &str) -> Company {
+        Company {
+            cin: cin.to_string(),
+            name: "Acme Private Limited".to_string(),
+            fy_end_date: d(2024, 3, 31),
+            incorporation_date: d(2018, 4, 1),
+            company_type: CompanyType::Private,
+            first_fy: false,
+            agm_extension_months: 0,
+            has_subsidiaries: false,
+        }
+    }
+
+    fn filing(srn: &str, filing_date: NaiveDate) -> NewFiling {
+        NewFiling { form: "AOC-4".to_string(), fy_end_date: d(2024, 3, 31), srn: srn.to_string(), filing_date }
+    }
+
+    fn firm_store() -> FirmStore {
+        let store = Store::in_memory();
+        store.insert_firm("sharma", "Sharma & Associates").unwrap();
+        store.for_firm("sharma")
+    }
+
+    #[test]
+    fn company_round_trip() {
+        let store = firm_store();
+        store.insert_company(&company("U1")).unwrap();
+        let mut updated = company("U1");
+        updated.company_type = CompanyType::Opc;
+        updated.agm_extension_months = 0;
+        assert!(store.update_company(&updated).unwrap());
+        assert!(!store.update_company(&company("U2")).unwrap());
+
+        let stored = store.company("U1").unwrap().unwrap();
+        assert_eq!((stored.company_type, stored.fy_end_date), (CompanyType::Opc, d(2024, 3, 31)));
+        assert_eq!(store.companies().unwrap().len(), 1);
+        assert!(store.company("U2").unwrap().is_none());
+    }
+
+    // The earliest filing of the form for the FY is the one that stopped the additional fee
+    #[test]
+    fn filing_for_returns_the_earliest_filing() {
+        let store = firm_store();
+        store.insert_company(&company("U1")).unwrap();
+        store.insert_filing("U1", &filing("F2", d(2024, 12, 2))).unwrap();
+        let first = store.insert_filing("U1", &filing("F1", d(2024, 11, 5))).unwrap();
+
+        let found = store.filing_for("U1", "AOC-4", d(2024, 3, 31)).unwrap().unwrap();
+        assert_eq!((found.id, found.srn.as_str()), (first.id, "F1"));
+        assert!(store.filing_for("U1", "MGT-7", d(2024, 3, 31)).unwrap().is_none());
+        assert_eq!(store.filings("U1").unwrap().iter().map(|f| f.srn.as_str()).collect::<Vec<_>>(), ["F1", "F2"]);
+    }
+
+    #[test]
+    fn deleting_a_company_removes_its_filings_and_snapshots() {
+        let store = firm_store();
+        store.insert_company(&company("U1")).unwrap();
+        store.insert_filing("U1", &filing("F1", d(2024, 11, 5))).unwrap();
+        store.insert_snapshot("U1", d(2024, 12, 1), "SAFE", 0, &serde_json::json!({})).unwrap();
+
+        assert!(store.delete_company("U1").unwrap());
+        assert!(store.filings("U1").unwrap().is_empty());
+        assert!(store.snapshots("U1").unwrap().is_empty());
+        assert!(!store.delete_company("U1").unwrap());
+    }
+
+    #[test]
+    fn duplicate_srn_conflicts_without_naming_the_schema() {
+        let store = firm_store();
+        store.insert_company(&company("U1")).unwrap();
+        store.insert_filing("U1", &filing("F1", d(2024, 11, 5))).unwrap();
+
+        let error = ApiError::from(store.insert_filing("U1", &filing("F1", d(2024, 11, 6))).unwrap_err());
+        match error {
+            ApiError::Conflict(detail) => assert_eq!(detail, "A filing with this SRN already exists"),
+            other => panic!("expected a conflict, got {:?}", other),
+        }
+
+        // Filing against a company the firm does not have
+        let error = ApiError::from(store.insert_filing("U9", &filing("F9", d(2024, 11, 5))).unwrap_err());
+        assert!(matches!(error, ApiError::Conflict(detail) if !detail.contains("constraint")));
+    }
+
+    // Backstop for two approvals racing past the handler's check
+    #[test]
+    fn a_payment_backs_at_most_one_license() {
+        let store = Store::in_memory();
+        let license = |id: &str, txn_id: Option<&str>| License {
+            claims: LicenseClaims {
+                license_id: id.to_string(),
+                user_id: "u1".to_string(),
+                email: "ca@example.in".to_string(),
+                plan: "Scale".to_string(),
+                device_id: "PRK-DEV-1".to_string(),
+                issued_at: Utc::now(),
+                expires_on: d(2025, 1, 1),
+            },
+            txn_id: txn_id.map(str::to_string),
+        };
+        store.insert_license(&license("lic_1", Some("PRK1"))).unwrap();
+        store.insert_license(&license("lic_2", None)).unwrap();
+        store.insert_license(&license("lic_3", None)).unwrap();
+        assert_eq!(store.license_for_txn("PRK1").unwrap().map(|l| l.claims.license_id).as_deref(), Some("lic_1"));
+
+        let error = ApiError::from(store.insert_license(&license("lic_4", Some("PRK1"))).unwrap_err());
+        assert!(matches!(error, ApiError::Conflict(detail) if detail == "A license was already issued for this payment"));
+    }
+
+    // A company_type the code no longer knows fails the read instead of becoming "private"
+    #[test]
+    fn unknown_company_type_fails_the_read() {
+        let store = firm_store();
+        store.insert_company(&company("U1")).unwrap();
+        store.conn().execute("UPDATE companies SET company_type = 'section8' WHERE cin = 'U1'", []).unwrap();
+        assert!(store.company("U1").is_err());
+        assert!(store.companies().is_err());
+    }
+}
@@ -0,0 +1,82 @@
+use axum::{extract::rejection::JsonRejection, Json};
+use serde::Deserialize;
+use utoipa::ToSchema;
+
+use crate::errors::{check_amount, parse_date, ApiError, FieldError};
+
+pub use pratyaksh_core::tds::*;
+
+#[derive(Deserialize, ToSchema)]
+pub struct TdsEntryRequest {
+    pub reference: Option<String>, // Deductee PAN, invoice or challan, echoed back
+    pub amount: i64,               // TDS amount
+    pub payment_date: String,      // Date of payment/credit to the payee, when tax became deductible
+    pub deduction_date: String,
+    pub deposit_date: String,
+    pub return_filing_date: String, // Filing date of the quarterly TDS statement
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct TdsRequest {
+    pub entries: Vec<TdsEntryRequest>,
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/tds/defaults",
+    tag = "tds",
+    request_body = TdsRequest,
+    responses(
+        (status = 200, body = TdsDefaults),
+        (status = 400, description = "Malformed request", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 401, description = "Missing, unknown or revoked API key", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 422, description = "Invalid parameters", body = ProblemDetails, content_type = "application/problem+json"),
+    ),
+    security(("api_key" = []))
+)]
+pub async fn tds_defaults(body: Result<Json<TdsRequest>, JsonRejection>) -> Result<Json<TdsDefaults>, ApiError> {
+    let Json(req) = body?;
+
+    let mut errors = Vec::new();
+    let mut entries = Vec::with_capacity(req.entries.len());
+    for (i, e) in req.entries.into_iter().enumerate() {
+        let field = |name: &str| format!("entries[{}].{}", i, name);
+        check_amount(&field("amount"), e.amount, &mut errors);
+        let payment = parse_date(&field("payment_date"), &e.payment_date, &mut errors);
+        let deduction = parse_date(&field("deduction_date"), &e.deduction_date, &mut errors);
+        let deposit = parse_date(&field("deposit_date"), &e.deposit_date, &mut errors);
+        let filing = parse_date(&field("return_filing_date"), &e.return_filing_date, &mut errors);
+        if let (Some(deduction), Some(deposit)) = (deduction, deposit) {
+            if deposit < deduction {
+                errors.push(FieldError::OutOfRange { field: field("deposit_date"), reason: "must not be before deduction_date".to_string() });
+            }
+        }
+        if let (Some(payment_date), Some(deduction_date), Some(deposit_date), Some(return_filing_date)) = (payment, deduction, deposit, filing) {
+            entries.push(TdsEntry { reference: e.reference, amount: e.amount, payment_date, deduction_date, deposit_date, return_filing_date });
+        }
+    }
+    if !errors.is_empty() {
+        return Err(ApiError::Validation(errors));
+    }
+
+    Ok(Json(compute_defaults(entries)))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::store::Scope;
+    use crate::testing::{key_for, request, send, state};
+    use axum::http::StatusCode;
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn oversized_amounts_are_invalid_params() {
+        let state = state();
+        let key = key_for(&state, "sharma", Scope::Read);
+        let entry = json!({ "amount": 5_000_000_000_000_000_000i64, "payment_date": "2024-05-20", "deduction_date": "2024-05-20", "deposit_date": "2024-07-10", "return_filing_date": "2024-07-31" });
+        let (status, body) = send(&state, request("POST", "/api/v1/tds/defaults", Some(&key), Some(json!({ "entries": [entry.clone(), entry] })))).await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        let names: Vec<&str> = body["invalid_params"].as_array().unwrap().iter().map(|p| p["name"].as_str().unwrap()).collect();
+        assert_eq!(names, ["entries[0].amount", "entries[1].amount"]);
+    }
+}
@@ -0,0 +1,114 @@
+use axum::{extract::rejection::JsonRejection, Json};
+
+use crate::errors::ApiError;
+
+pub use pratyaksh_core::tools::*;
+
+// Smart Tools over HTTP: one POST per desktop calculator, same inputs, same rules, same results.
+// The penalty, LLP fee, GST interest, TDS interest and advance tax tools are served by
+// /compliance/fees, /gst/interest, /tds/defaults and /income-tax/interest.
+
+fn calculate<C: Calculator>(body: Result<Json<C>, JsonRejection>) -> Result<Json<C::Output>, ApiError> {
+    let Json(input) = body?;
+    let errors = input.check();
+    if !errors.is_empty() {
+        return Err(ApiError::Validation(errors));
+    }
+    Ok(Json(input.evaluate()))
+}
+
+macro_rules! tool {
+    ($handler:ident, $path:literal, $input:ty, $output:ty) => {
+        #[utoipa::path(
+            post,
+            path = $path,
+            tag = "tools",
+            request_body = $input,
+            responses(
+                (status = 200, body = $output),
+                (status = 400, description = "Malformed request", body = ProblemDetails, content_type = "application/problem+json"),
+                (status = 401, description = "Missing, unknown or revoked API key", body = ProblemDetails, content_type = "application/problem+json"),
+                (status = 422, description = "Invalid parameters", body = ProblemDetails, content_type = "application/problem+json"),
+            ),
+            security(("api_key" = []))
+        )]
+        pub async fn $handler(body: Result<Json<$input>, JsonRejection>) -> Result<Json<$output>, ApiError> {
+            calculate(body)
+        }
+    };
+}
+
+// NOTICE PREDICTORS
+tool!(gst_mismatch, "/api/v1/tools/gst-mismatch", GstMismatchInput, GstMismatch);
+tool!(sft_mismatch, "/api/v1/tools/sft-mismatch", SftMismatchInput, SftMismatch);
+tool!(tds_shortfall, "/api/v1/tools/tds-shortfall", TdsShortfallInput, TdsShortfall);
+tool!(mca_notice, "/api/v1/tools/mca-notice", McaNoticeInput, McaNotice);
+tool!(board_risk, "/api/v1/tools/board-risk", BoardRiskInput, BoardRisk);
+tool!(trust_score, "/api/v1/tools/trust-score", TrustScoreInput, TrustScore);
+
+// TAX AND PAYROLL
+tool!(msme_interest, "/api/v1/tools/msme-interest", MsmeInterestInput, MsmeInterest);
+tool!(gratuity, "/api/v1/tools/gratuity", GratuityInput, Gratuity);
+tool!(tax_regime, "/api/v1/tools/tax-regime", TaxRegimeInput, TaxRegime);
+tool!(crypto_tax, "/api/v1/tools/crypto-tax", CryptoTaxInput, CryptoTax);
+tool!(hra, "/api/v1/tools/hra", HraInput, Hra);
+tool!(pmla, "/api/v1/tools/pmla", PmlaInput, Pmla);
+tool!(angel_tax, "/api/v1/tools/angel-tax", AngelTaxInput, AngelTax);
+tool!(buyback_tax, "/api/v1/tools/buyback-tax", BuybackTaxInput, BuybackTax);
+tool!(capital_gains, "/api/v1/tools/capital-gains", IndexedCostInput, IndexedCost);
+
+// CORPORATE
+tool!(shell_risk, "/api/v1/tools/shell-risk", ShellRiskInput, ShellRisk);
+tool!(esg, "/api/v1/tools/esg", EsgInput, Esg);
+tool!(udin, "/api/v1/tools/udin", UdinInput, Udin);
+tool!(audit_rotation, "/api/v1/tools/audit-rotation", AuditRotationInput, AuditRotation);
+tool!(net_worth, "/api/v1/tools/net-worth", NetWorthInput, NetWorth);
+tool!(export_realisation, "/api/v1/tools/export-realisation", ExportRealisationInput, ExportRealisation);
+tool!(partner_dissolution, "/api/v1/tools/partner-dissolution", PartnerDissolutionInput, PartnerDissolution);
+
+// FINANCE
+tool!(lease, "/api/v1/tools/lease", LeaseInput, Lease);
+tool!(depreciation, "/api/v1/tools/depreciation", DepreciationInput, Depreciation);
+tool!(emi, "/api/v1/tools/emi", EmiInput, Emi);
+tool!(burn_rate, "/api/v1/tools/burn-rate", BurnRateInput, BurnRate);
+tool!(simple_interest, "/api/v1/tools/simple-interest", SimpleInterestInput, SimpleInterest);
+tool!(cagr, "/api/v1/tools/cagr", CagrInput, Cagr);
+tool!(break_even, "/api/v1/tools/break-even", BreakEvenInput, BreakEven);
+
+#[cfg(test)]
+mod tests {
+    use crate::store::Scope;
+    use crate::testing::{key_for, request, send, state};
+    use axum::http::StatusCode;
+    use serde_json::json;
+
+    // Calculators are stateless, so a read-only key is enough
+    #[tokio::test]
+    async fn tools_answer_with_the_core_result() {
+        let state = state();
+        let key = key_for(&state, "sharma", Scope::Read);
+        let (status, body) = send(&state, request("POST", "/api/v1/tools/gratuity", Some(&key), Some(json!({ "monthly_salary": 26000.0, "years_of_service": 10.0 })))).await;
+        assert_eq!((status, body["gratuity"].as_f64()), (StatusCode::OK, Some(150000.0)));
+        assert_eq!(body["explanation"][0]["reference"], "Sec 4(2), Payment of Gratuity Act 1972");
+    }
+
+    #[tokio::test]
+    async fn invalid_inputs_list_every_field() {
+        let state = state();
+        let key = key_for(&state, "sharma", Scope::Read);
+        let (status, body) = send(&state, request("POST", "/api/v1/tools/emi", Some(&key), Some(json!({ "principal": -1.0, "annual_rate_percent": 9.0, "years": 0.0 })))).await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        let names: Vec<&str> = body["invalid_params"].as_array().unwrap().iter().map(|p| p["name"].as_str().unwrap()).collect();
+        assert_eq!(names, ["principal", "years"]);
+    }
+
+    #[tokio::test]
+    async fn malformed_and_unauthenticated_calls_are_rejected() {
+        let state = state();
+        let key = key_for(&state, "sharma", Scope::Read);
+        let (status, _) = send(&state, request("POST", "/api/v1/tools/emi", Some(&key), Some(json!({ "principal": "ten lakh" })))).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        let (status, _) = send(&state, request("POST", "/api/v1/tools/emi", None, Some(json!({ "principal": 1.0, "annual_rate_percent": 9.0, "years": 1.0 })))).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+    }
+}
@@ -0,0 +1,221 @@
+use serde_json::{Map, Value};
+use std::fmt::Write;
+
+// Generates the request/response types and one async method per operation from openapi.json,
+// the document exported by `pratyaksh_backend --export-openapi`. Covers the subset of OpenAPI 3.0
+// that utoipa emits for the backend: objects, string enums, arrays, maps, $ref and allOf.
+
+const SPEC: &str = "openapi.json";
+
+const KEYWORDS: &[&str] = &["type", "match", "ref", "move", "fn", "impl", "mod", "struct", "enum", "use", "where", "loop", "self", "crate"];
+
+fn pascal(name: &str) -> String {
+    name.split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .map(|part| {
+            let mut chars = part.chars();
+            let first = chars.next().unwrap().to_ascii_uppercase();
+            std::iter::once(first).chain(chars).collect::<String>()
+        })
+        .collect()
+}
+
+fn snake(name: &str) -> String {
+    let mut out = String::new();
+    for (i, c) in name.chars().enumerate() {
+        if c.is_ascii_uppercase() {
+            if i > 0 {
+                out.push('_');
+            }
+            out.push(c.to_ascii_lowercase());
+        } else if c.is_ascii_alphanumeric() {
+            out.push(c);
+        } else {
+            out.push('_');
+        }
+    }
+    out
+}
+
+fn ident(name: &str) -> String {
+    if KEYWORDS.contains(&name) { format!("r#{}", name) } else { name.to_string() }
+}
+
+fn ref_name(schema: &Value) -> Option<String> {
+    schema.get("$ref")?.as_str()?.rsplit('/').next().map(str::to_string)
+}
+
+fn nullable(schema: &Value) -> bool {
+    schema.get("nullable").and_then(Value::as_bool).unwrap_or(false)
+}
+
+fn rust_type(schema: &Value) -> String {
+    if let Some(name) = ref_name(schema) {
+        return name;
+    }
+    // utoipa wraps a nullable $ref as allOf with one member
+    if let Some(all) = schema.get("allOf").and_then(Value::as_array) {
+        if all.len() == 1 {
+            return rust_type(&all[0]);
+        }
+    }
+    match schema.get("type").and_then(Value::as_str) {
+        Some("string") => match schema.get("format").and_then(Value::as_str) {
+            Some("date") => "chrono::NaiveDate".to_string(),
+            _ => "String".to_string(),
+        },
+        Some("integer") => match (schema.get("format").and_then(Value::as_str), schema.get("minimum").is_some()) {
+            (Some("int64"), _) => "i64".to_string(),
+            (Some("int32"), true) => "u32".to_string(),
+            (Some("int32"), false) => "i32".to_string(),
+            _ => "u64".to_string(),
+        },
+        Some("number") => "f64".to_string(),
+        Some("boolean") => "bool".to_string(),
+        Some("array") => format!("Vec<{}>", rust_type(&schema["items"])),
+        Some("object") if schema.get("additionalProperties").is_some() => {
+            format!("std::collections::BTreeMap<String, {}>", rust_type(&schema["additionalProperties"]))
+        }
+        _ => "serde_json::Value".to_string(),
+    }
+}
+
+fn write_fields(out: &mut String, object: &Value) {
+    let required: Vec<&str> = object.get("required").and_then(Value::as_array)
+        .map(|r| r.iter().filter_map(Value::as_str).collect())
+        .unwrap_or_default();
+    let empty = Map::new();
+    let properties = object.get("properties").and_then(Value::as_object).unwrap_or(&empty);
+    for (name, schema) in properties {
+        let field = snake(name);
+        let ty = rust_type(schema);
+        if field != *name {
+            writeln!(out, "    #[serde(rename = \"{}\")]", name).unwrap();
+        }
+        if required.contains(&name.as_str()) && !nullable(schema) {
+            writeln!(out, "    pub {}: {},", ident(&field), ty).unwrap();
+        } else {
+            writeln!(out, "    #[serde(default, skip_serializing_if = \"Option::is_none\")]").unwrap();
+            writeln!(out, "    pub {}: Option<{}>,", ident(&field), ty).unwrap();
+        }
+    }
+}
+
+fn write_schema(out: &mut String, name: &str, schema: &Value) {
+    if let Some(variants) = schema.get("enum").and_then(Value::as_array) {
+        writeln!(out, "#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]\npub enum {} {{", name).unwrap();
+        for v in variants.iter().filter_map(Value::as_str) {
+            writeln!(out, "    #[serde(rename = \"{}\")]\n    {},", v, pascal(v)).unwrap();
+        }
+        writeln!(out, "}}\n").unwrap();
+        return;
+    }
+
+    writeln!(out, "#[derive(Serialize, Deserialize, Clone, Debug)]\npub struct {} {{", name).unwrap();
+    match schema.get("allOf").and_then(Value::as_array) {
+        // #[serde(flatten)] on the server side: referenced members flatten, inline members merge
+        Some(members) => {
+            for member in members {
+                match ref_name(member) {
+                    Some(r) => writeln!(out, "    #[serde(flatten)]\n    pub {}: {},", snake(&r), r).unwrap(),
+                    None => write_fields(out, member),
+                }
+            }
+        }
+        None => write_fields(out, schema),
+    }
+    writeln!(out, "}}\n").unwrap();
+}
+
+fn success(responses: &Map<String, Value>) -> (String, &'static str) {
+    let Some((_, response)) = responses.iter().find(|(code, _)| code.starts_with('2')) else {
+        return ("()".to_string(), "send_empty");
+    };
+    match response.get("content").and_then(Value::as_object) {
+        Some(content) => match content.get("application/json") {
+            Some(json) => (rust_type(&json["schema"]), "send_json"),
+            None => ("String".to_string(), "send_text"),
+        },
+        None => ("()".to_string(), "send_empty"),
+    }
+}
+
+fn write_operation(out: &mut String, params_out: &mut String, path: &str, method: &str, op: &Value) {
+    let name = op["operationId"].as_str().expect("operationId");
+    let parameters = op.get("parameters").and_then(Value::as_array).cloned().unwrap_or_default();
+
+    let mut args = Vec::new();
+    let mut url = format!("\"{}\"", path);
+    let path_params: Vec<&Value> = parameters.iter().filter(|p| p["in"] == "path").collect();
+    if !path_params.is_empty() {
+        let mut fmt = path.to_string();
+        let mut values = Vec::new();
+        for p in &path_params {
+            let pname = p["name"].as_str().unwrap();
+            fmt = fmt.replace(&format!("{{{}}}", pname), "{}");
+            let ty = rust_type(&p["schema"]);
+            if ty == "String" {
+                args.push(format!("{}: &str", ident(pname)));
+                values.push(format!("encode({})", ident(pname)));
+            } else {
+                args.push(format!("{}: {}", ident(pname), ty));
+                values.push(ident(pname));
+            }
+        }
+        url = format!("&format!(\"{}\", {})", fmt, values.join(", "));
+    }
+
+    let query: Vec<&Value> = parameters.iter().filter(|p| p["in"] == "query").collect();
+    let query_type = format!("{}Params", pascal(name));
+    if !query.is_empty() {
+        writeln!(params_out, "#[derive(Serialize, Deserialize, Clone, Debug)]\npub struct {} {{", query_type).unwrap();
+        let object = Value::Object(Map::from_iter([
+            ("required".to_string(), Value::Array(query.iter().filter(|p| p["required"] == true).map(|p| p["name"].clone()).collect())),
+            ("properties".to_string(), Value::Object(query.iter().map(|p| (p["name"].as_str().unwrap().to_string(), p["schema"].clone())).collect())),
+        ]));
+        write_fields(params_out, &object);
+        writeln!(params_out, "}}\n").unwrap();
+        args.push(format!("params: &{}", query_type));
+    }
+
+    let body = op.pointer("/requestBody/content/application~1json/schema").map(rust_type);
+    if let Some(ty) = &body {
+        args.push(format!("body: &{}", ty));
+    }
+
+    let (ret, send) = success(op["responses"].as_object().expect("responses"));
+    let summary = format!("{} {}", method.to_uppercase(), path);
+    writeln!(out, "    // {}", summary).unwrap();
+    writeln!(out, "    pub async fn {}(&self{}) -> Result<{}, Error> {{", name, args.iter().map(|a| format!(", {}", a)).collect::<String>(), ret).unwrap();
+    write!(out, "        let request = self.request(reqwest::Method::{}, {})", method.to_uppercase(), url).unwrap();
+    if !query.is_empty() {
+        write!(out, ".query(params)").unwrap();
+    }
+    if body.is_some() {
+        write!(out, ".json(body)").unwrap();
+    }
+    writeln!(out, ";\n        self.{}(request).await\n    }}\n", send).unwrap();
+}
+
+fn main() {
+    println!("cargo:rerun-if-changed={}", SPEC);
+    let raw = std::fs::read_to_string(SPEC).expect("openapi.json missing; export it with pratyaksh_backend --export-openapi");
+    let spec: Value = serde_json::from_str(&raw).expect("openapi.json is not valid JSON");
+
+    let mut types = String::from("// @generated from openapi.json by build.rs\n\n");
+    for (name, schema) in spec["components"]["schemas"].as_object().expect("components.schemas") {
+        write_schema(&mut types, name, schema);
+    }
+
+    let mut params = String::new();
+    let mut methods = String::from("impl Client {\n");
+    for (path, item) in spec["paths"].as_object().expect("paths") {
+        for (method, op) in item.as_object().unwrap() {
+            write_operation(&mut methods, &mut params, path, method, op);
+        }
+    }
+    methods.push_str("}\n");
+
+    let out = std::path::Path::new(&std::env::var("OUT_DIR").unwrap()).join("generated.rs");
+    std::fs::write(out, types + &params + &methods).expect("write generated.rs");
+}
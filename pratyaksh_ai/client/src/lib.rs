@@ -0,0 +1,94 @@
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+// Typed client for the Pratyaksh compliance API. Types and methods are generated by build.rs from
+// openapi.json; this file holds the transport they share.
+
+include!(concat!(env!("OUT_DIR"), "/generated.rs"));
+
+#[derive(Debug)]
+pub enum Error {
+    Http(reqwest::Error),                                 // Connection, TLS or decoding failure
+    Api { status: u16, problem: Option<ProblemDetails> }, // Non-2xx response, with its RFC 7807 body when present
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Http(e) => write!(f, "{}", e),
+            Error::Api { status, problem: Some(p) } => write!(f, "{} {}: {}", status, p.title, p.detail),
+            Error::Api { status, problem: None } => write!(f, "HTTP {}", status),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<reqwest::Error> for Error {
+    fn from(e: reqwest::Error) -> Self {
+        Error::Http(e)
+    }
+}
+
+#[derive(Clone)]
+pub struct Client {
+    base_url: String,
+    token: Option<String>, // Firm API key, or the admin token for the admin operations
+    http: reqwest::Client,
+}
+
+// Percent-encodes a path segment such as a CIN
+fn encode(segment: &str) -> String {
+    segment.bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => (b as char).to_string(),
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+impl Client {
+    // e.g. Client::new("https://api.pratyaksh.ai").with_token(key)
+    pub fn new(base_url: &str) -> Self {
+        Client { base_url: base_url.trim_end_matches('/').to_string(), token: None, http: reqwest::Client::new() }
+    }
+
+    pub fn with_token(mut self, token: &str) -> Self {
+        self.token = Some(token.to_string());
+        self
+    }
+
+    fn request(&self, method: reqwest::Method, path: &str) -> reqwest::RequestBuilder {
+        let request = self.http.request(method, format!("{}{}", self.base_url, path));
+        match &self.token {
+            Some(token) => request.bearer_auth(token),
+            None => request,
+        }
+    }
+
+    async fn send(&self, request: reqwest::RequestBuilder) -> Result<reqwest::Response, Error> {
+        let response = request.send().await?;
+        let status = response.status();
+        if status.is_success() {
+            return Ok(response);
+        }
+        let problem = response.json::<ProblemDetails>().await.ok();
+        Err(Error::Api { status: status.as_u16(), problem })
+    }
+
+    async fn send_json<T: DeserializeOwned>(&self, request: reqwest::RequestBuilder) -> Result<T, Error> {
+        Ok(self.send(request).await?.json().await?)
+    }
+
+    async fn send_text(&self, request: reqwest::RequestBuilder) -> Result<String, Error> {
+        Ok(self.send(request).await?.text().await?)
+    }
+
+    async fn send_empty(&self, request: reqwest::RequestBuilder) -> Result<(), Error> {
+        self.send(request).await.map(|_| ())
+    }
+
+    // The API's own OpenAPI document, to check the server matches this client
+    pub async fn openapi(&self) -> Result<serde_json::Value, Error> {
+        self.send_json(self.request(reqwest::Method::GET, "/api/v1/openapi.json")).await
+    }
+}
@@ -0,0 +1,472 @@
+use chrono::{Datelike, Duration, Months, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+use crate::circulars::{CircularCitation, CircularRegistry};
+use crate::errors::FieldError;
+use crate::llp;
+use crate::trace::RuleTrace;
+
+#[derive(Deserialize, Serialize, Clone, Copy, PartialEq, Debug, Default)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum CompanyType {
+    #[default]
+    Private,
+    Public,
+    Opc,   // One Person Company, Sec 2(62)
+    Small, // Small Company, Sec 2(85)
+    Llp,   // Limited Liability Partnership, LLP Act 2008
+}
+
+// Facts about the company that move its AGM and filing deadlines
+#[derive(Clone, Copy, Debug, Default)]
+pub struct CompanyProfile {
+    pub company_type: CompanyType,
+    pub first_fy: bool,            // First financial year after incorporation
+    pub agm_extension_months: u32, // Extension granted by the ROC under Sec 96(1)
+}
+
+impl CompanyProfile {
+    pub fn check(&self, prefix: &str, errors: &mut Vec<FieldError>) {
+        let field = format!("{}agm_extension_months", prefix);
+        if self.agm_extension_months > 3 {
+            errors.push(FieldError::OutOfRange { field, reason: "the ROC can extend the AGM by at most 3 months".to_string() });
+        } else if self.agm_extension_months > 0 && self.first_fy {
+            errors.push(FieldError::OutOfRange { field, reason: "the first AGM cannot be extended".to_string() });
+        } else if self.agm_extension_months > 0 && self.company_type == CompanyType::Opc {
+            errors.push(FieldError::OutOfRange { field, reason: "an OPC does not hold an AGM".to_string() });
+        } else if self.agm_extension_months > 0 && self.company_type == CompanyType::Llp {
+            errors.push(FieldError::OutOfRange { field, reason: "an LLP does not hold an AGM".to_string() });
+        }
+    }
+
+    // Company forms and LLP forms are not interchangeable
+    pub fn check_form(&self, field: &str, form: &str, errors: &mut Vec<FieldError>) {
+        let is_llp = self.company_type == CompanyType::Llp;
+        if is_llp != llp::is_llp_form(form) {
+            let reason = if is_llp {
+                format!("'{}' is not filed by an LLP; expected one of {}", form, llp::LLP_FORMS.join(", "))
+            } else {
+                format!("'{}' is filed by LLPs; set company_type to llp", form)
+            };
+            errors.push(FieldError::OutOfRange { field: field.to_string(), reason });
+        }
+    }
+}
+
+#[derive(Deserialize, Clone)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema, utoipa::IntoParams), into_params(parameter_in = Query))]
+pub struct CalendarQuery {
+    pub cin: Option<String>,           // Labels exported events
+    pub fy_end_date: NaiveDate,        // YYYY-MM-DD
+    pub incorporation_date: NaiveDate, // YYYY-MM-DD
+    pub company_type: CompanyType,
+    #[serde(default)]
+    pub has_subsidiaries: bool,
+    pub board_meeting_date: Option<NaiveDate>, // Board meeting approving the accounts
+    #[serde(default)]
+    pub first_fy: bool,
+    #[serde(default)]
+    pub agm_extension_months: u32,
+}
+
+impl CalendarQuery {
+    pub fn check(&self, prefix: &str, errors: &mut Vec<FieldError>) {
+        if self.incorporation_date > self.fy_end_date {
+            errors.push(FieldError::OutOfRange {
+                field: format!("{}incorporation_date", prefix),
+                reason: "must not be after fy_end_date".to_string(),
+            });
+        }
+        self.profile().check(prefix, errors);
+    }
+
+    pub fn profile(&self) -> CompanyProfile {
+        CompanyProfile {
+            company_type: self.company_type,
+            first_fy: self.first_fy,
+            agm_extension_months: self.agm_extension_months,
+        }
+    }
+}
+
+#[derive(Serialize, Clone)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct CalendarEntry {
+    pub form: String,
+    pub description: String,
+    pub due_date: NaiveDate,
+    pub act_section: String,
+    pub circular: Option<CircularCitation>, // Set when an MCA circular moved the due date
+}
+
+#[derive(Serialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ComplianceCalendar {
+    pub fy_end_date: NaiveDate,
+    pub company_type: CompanyType,
+    pub agm_due_date: Option<NaiveDate>, // None for OPCs (Sec 96(1)) and LLPs
+    pub obligations: Vec<CalendarEntry>,
+    pub explanation: Vec<RuleTrace>,
+}
+
+// Sec 96(1): AGM within six months of FY close; first AGM within nine months;
+// ROC extension of up to three months (not for the first AGM); OPCs are exempt
+pub fn agm_due_date(fy_end: NaiveDate, profile: &CompanyProfile) -> Option<NaiveDate> {
+    if matches!(profile.company_type, CompanyType::Opc | CompanyType::Llp) {
+        return None;
+    }
+    let months = if profile.first_fy { 9 } else { 6 + profile.agm_extension_months };
+    Some(fy_end + Months::new(months))
+}
+
+// Date the AGM-linked periods run from; for OPCs, the day the AGM would otherwise have been due
+fn agm_reference_date(fy_end: NaiveDate, profile: &CompanyProfile) -> NaiveDate {
+    agm_due_date(fy_end, profile).unwrap_or(fy_end + Months::new(6))
+}
+
+// Sec 137: AOC-4 within 30 days of AGM; OPC within 180 days of FY close
+pub fn financial_statements_due(fy_end: NaiveDate, profile: &CompanyProfile) -> NaiveDate {
+    if profile.company_type == CompanyType::Opc {
+        fy_end + Duration::days(180)
+    } else {
+        agm_reference_date(fy_end, profile) + Duration::days(30)
+    }
+}
+
+// Sec 92(4): annual return within 60 days of AGM
+pub fn annual_return_due(fy_end: NaiveDate, profile: &CompanyProfile) -> NaiveDate {
+    agm_reference_date(fy_end, profile) + Duration::days(60)
+}
+
+// First occurrence of the given day/month strictly after `after`; 29 February moves to the next leap year.
+// None when no year has that date, e.g. 31 April
+pub fn next_fixed_date(after: NaiveDate, month: u32, day: u32) -> Option<NaiveDate> {
+    // Leap years are at most eight years apart (1896 to 1904)
+    (after.year()..=after.year() + 8)
+        .filter_map(|year| NaiveDate::from_ymd_opt(year, month, day))
+        .find(|date| *date > after)
+}
+
+pub fn entry(form: &str, description: &str, due_date: NaiveDate, act_section: &str) -> CalendarEntry {
+    CalendarEntry {
+        form: form.to_string(),
+        description: description.to_string(),
+        due_date,
+        act_section: act_section.to_string(),
+        circular: None,
+    }
+}
+
+fn with_profile(trace: RuleTrace, fy_end: NaiveDate, profile: &CompanyProfile) -> RuleTrace {
+    trace.input("fy_end_date", fy_end)
+        .input("company_type", format!("{:?}", profile.company_type).to_lowercase())
+        .input("first_fy", profile.first_fy)
+        .input("agm_extension_months", profile.agm_extension_months)
+}
+
+pub fn agm_trace(fy_end: NaiveDate, profile: &CompanyProfile) -> RuleTrace {
+    let threshold = match profile.company_type {
+        CompanyType::Opc => "OPC exempt from holding an AGM".to_string(),
+        CompanyType::Llp => "LLPs do not hold an AGM".to_string(),
+        _ if profile.first_fy => "First AGM within 9 months of close of first FY".to_string(),
+        _ if profile.agm_extension_months > 0 => format!("6 months of FY close + {} month(s) ROC extension", profile.agm_extension_months),
+        _ => "6 months of FY close".to_string(),
+    };
+    let outcome = agm_due_date(fy_end, profile).map_or("No AGM due".to_string(), |d| format!("AGM due by {}", d));
+    with_profile(RuleTrace::new("AGM deadline", threshold, outcome, "Sec 96(1)"), fy_end, profile)
+}
+
+pub fn financial_statements_trace(fy_end: NaiveDate, profile: &CompanyProfile) -> RuleTrace {
+    let threshold = if profile.company_type == CompanyType::Opc { "180 days of FY close (OPC)" } else { "30 days of AGM" };
+    let outcome = format!("Financial statements due by {}", financial_statements_due(fy_end, profile));
+    with_profile(RuleTrace::new("AOC-4 due date", threshold, outcome, "Sec 137(1)"), fy_end, profile)
+}
+
+pub fn annual_return_trace(fy_end: NaiveDate, profile: &CompanyProfile) -> RuleTrace {
+    let threshold = if profile.company_type == CompanyType::Opc { "60 days from the date the AGM would have been due (OPC)" } else { "60 days of AGM" };
+    let outcome = format!("Annual return due by {}", annual_return_due(fy_end, profile));
+    with_profile(RuleTrace::new("MGT-7 due date", threshold, outcome, "Sec 92(4)"), fy_end, profile)
+}
+
+// ROC CALENDAR ENGINE
+pub fn build_calendar(q: &CalendarQuery, registry: &CircularRegistry) -> ComplianceCalendar {
+    let fy_end = q.fy_end_date;
+    let fy_start = fy_end - Months::new(12);
+    let profile = q.profile();
+    if q.company_type == CompanyType::Llp {
+        return build_llp_calendar(q, registry);
+    }
+    let mgt7a = matches!(q.company_type, CompanyType::Opc | CompanyType::Small);
+
+    let agm_deadline = agm_reference_date(fy_end, &profile);
+    let mut obligations = Vec::new();
+
+    let aoc4_due = financial_statements_due(fy_end, &profile);
+    obligations.push(entry("AOC-4", "Filing of financial statements", aoc4_due, "Sec 137"));
+
+    // Sec 129(3): consolidated statements for companies with subsidiaries
+    if q.has_subsidiaries && q.company_type != CompanyType::Small {
+        obligations.push(entry("AOC-4 CFS", "Filing of consolidated financial statements", aoc4_due, "Sec 129(3) r/w Sec 137"));
+    }
+
+    // OPC and small companies file the annual return on MGT-7A
+    let (annual_form, annual_section) = if mgt7a {
+        ("MGT-7A", "Sec 92(4) r/w Rule 11(1)")
+    } else {
+        ("MGT-7", "Sec 92(4)")
+    };
+    obligations.push(entry(annual_form, "Annual return", annual_return_due(fy_end, &profile), annual_section));
+
+    // Sec 139(1): auditor appointment intimated within 15 days of the AGM
+    obligations.push(entry("ADT-1", "Intimation of auditor appointment", agm_deadline + Duration::days(15), "Sec 139(1)"));
+
+    // Sec 117(3)(g): board resolution approving accounts; private companies are exempt
+    if q.company_type == CompanyType::Public {
+        // Without a meeting date, assume the latest board meeting that still allows 21 clear days' AGM notice
+        let board_date = q.board_meeting_date.unwrap_or(agm_deadline - Duration::days(21));
+        obligations.push(entry("MGT-14", "Board resolution approving financial statements", board_date + Duration::days(30), "Sec 117(3)(g) r/w Sec 179(3)(g)"));
+    }
+
+    // Rule 16, Companies (Acceptance of Deposits) Rules, 2014: return as on 31 March, by 30 June
+    obligations.push(entry("DPT-3", "Return of deposits", next_fixed_date(fy_end, 6, 30).expect("30 June exists"), "Sec 73 r/w Rule 16"));
+
+    // Rule 12A, Companies (Appointment and Qualification of Directors) Rules, 2014
+    obligations.push(entry("DIR-3 KYC", "KYC of every director holding a DIN", next_fixed_date(fy_end, 9, 30).expect("30 September exists"), "Sec 152 r/w Rule 12A"));
+
+    // MSME-1 half-yearly: Apr-Sep due 31 Oct, Oct-Mar due 30 Apr
+    for year in [fy_end.year() - 1, fy_end.year()] {
+        let halves = [
+            (NaiveDate::from_ymd_opt(year, 3, 31).unwrap(), NaiveDate::from_ymd_opt(year, 4, 30).unwrap(), "Oct-Mar"),
+            (NaiveDate::from_ymd_opt(year, 9, 30).unwrap(), NaiveDate::from_ymd_opt(year, 10, 31).unwrap(), "Apr-Sep"),
+        ];
+        for (period_end, due, label) in halves {
+            if period_end > fy_start && period_end <= fy_end && period_end >= q.incorporation_date {
+                let desc = format!("Outstanding payments to MSE suppliers ({} half-year)", label);
+                obligations.push(entry("MSME-1", &desc, due, "Sec 405"));
+            }
+        }
+    }
+
+    for e in obligations.iter_mut() {
+        let (due, circular) = registry.extended_due_date(&e.form, fy_end, e.due_date);
+        e.due_date = due;
+        e.circular = circular;
+    }
+    obligations.sort_by_key(|e| e.due_date);
+
+    let mut explanation = vec![agm_trace(fy_end, &profile), financial_statements_trace(fy_end, &profile), annual_return_trace(fy_end, &profile)];
+    explanation.extend(obligations.iter().filter_map(|e| e.circular.as_ref().map(|c| {
+        RuleTrace::new("MCA circular extension", "Due date extended by circular", &c.effect, format!("{} ({})", c.id, c.title)).input("form", &e.form)
+    })));
+
+    ComplianceCalendar {
+        fy_end_date: fy_end,
+        company_type: q.company_type,
+        agm_due_date: agm_due_date(fy_end, &profile),
+        obligations,
+        explanation,
+    }
+}
+
+fn build_llp_calendar(q: &CalendarQuery, registry: &CircularRegistry) -> ComplianceCalendar {
+    let fy_end = q.fy_end_date;
+    let mut obligations = llp::obligations(fy_end);
+    for e in obligations.iter_mut() {
+        let (due, circular) = registry.extended_due_date(&e.form, fy_end, e.due_date);
+        e.due_date = due;
+        e.circular = circular;
+    }
+    obligations.sort_by_key(|e| e.due_date);
+
+    let mut explanation: Vec<RuleTrace> = llp::LLP_FORMS.iter().map(|f| llp::due_date_trace(f, fy_end)).collect();
+    explanation.extend(obligations.iter().filter_map(|e| e.circular.as_ref().map(|c| {
+        RuleTrace::new("MCA circular extension", "Due date extended by circular", &c.effect, format!("{} ({})", c.id, c.title)).input("form", &e.form)
+    })));
+
+    ComplianceCalendar {
+        fy_end_date: fy_end,
+        company_type: q.company_type,
+        agm_due_date: None,
+        obligations,
+        explanation,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn d(y: i32, m: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, day).unwrap()
+    }
+
+    fn query(company_type: CompanyType) -> CalendarQuery {
+        CalendarQuery {
+            cin: None,
+            fy_end_date: d(2024, 3, 31),
+            incorporation_date: d(2015, 6, 1),
+            company_type,
+            has_subsidiaries: false,
+            board_meeting_date: None,
+            first_fy: false,
+            agm_extension_months: 0,
+        }
+    }
+
+    fn forms(calendar: &ComplianceCalendar) -> Vec<&str> {
+        calendar.obligations.iter().map(|e| e.form.as_str()).collect()
+    }
+
+    fn due(calendar: &ComplianceCalendar, form: &str) -> NaiveDate {
+        calendar.obligations.iter().find(|e| e.form == form).map(|e| e.due_date).unwrap()
+    }
+
+    fn empty_registry() -> CircularRegistry {
+        CircularRegistry { version: "test".to_string(), circulars: Vec::new() }
+    }
+
+    #[test]
+    fn next_fixed_date_is_strictly_after() {
+        assert_eq!(next_fixed_date(d(2024, 3, 31), 6, 30), Some(d(2024, 6, 30)));
+        assert_eq!(next_fixed_date(d(2024, 6, 30), 6, 30), Some(d(2025, 6, 30)));
+        assert_eq!(next_fixed_date(d(2024, 12, 31), 9, 30), Some(d(2025, 9, 30)));
+    }
+
+    #[test]
+    fn next_fixed_date_handles_dates_missing_from_the_year() {
+        assert_eq!(next_fixed_date(d(2024, 3, 31), 2, 29), Some(d(2028, 2, 29)));
+        assert_eq!(next_fixed_date(d(2023, 1, 1), 2, 29), Some(d(2024, 2, 29)));
+        assert_eq!(next_fixed_date(d(2024, 3, 31), 4, 31), None);
+        assert_eq!(next_fixed_date(d(2024, 3, 31), 13, 1), None);
+    }
+
+    // FY 2023-24 of a private company: AGM by 30 Sep, so AOC-4 by 30 Oct, MGT-7 by 29 Nov, ADT-1 by 15 Oct
+    #[test]
+    fn private_company_calendar() {
+        let calendar = build_calendar(&query(CompanyType::Private), &empty_registry());
+        assert_eq!(calendar.agm_due_date, Some(d(2024, 9, 30)));
+        assert_eq!(due(&calendar, "AOC-4"), d(2024, 10, 30));
+        assert_eq!(due(&calendar, "MGT-7"), d(2024, 11, 29));
+        assert_eq!(due(&calendar, "ADT-1"), d(2024, 10, 15));
+        assert_eq!(due(&calendar, "DPT-3"), d(2024, 6, 30));
+        assert_eq!(due(&calendar, "DIR-3 KYC"), d(2024, 9, 30));
+        assert!(!forms(&calendar).contains(&"AOC-4 CFS"));
+
+        // Sorted by due date, both MSME-1 half-years of the FY included
+        assert!(calendar.obligations.windows(2).all(|w| w[0].due_date <= w[1].due_date));
+        let msme: Vec<NaiveDate> = calendar.obligations.iter().filter(|e| e.form == "MSME-1").map(|e| e.due_date).collect();
+        assert_eq!(msme, [d(2023, 10, 31), d(2024, 4, 30)]);
+    }
+
+    // Sec 117(3)(g) read with the Sec 179(3)(g) exemption: only public companies file MGT-14 for accounts
+    #[test]
+    fn mgt14_only_for_public_companies() {
+        for company_type in [CompanyType::Private, CompanyType::Small, CompanyType::Opc] {
+            let calendar = build_calendar(&query(company_type), &empty_registry());
+            assert!(!forms(&calendar).contains(&"MGT-14"), "{:?} should not file MGT-14", company_type);
+        }
+
+        // No board meeting date: latest meeting allowing 21 days' notice before the 30 Sep AGM, then 30 days
+        let calendar = build_calendar(&query(CompanyType::Public), &empty_registry());
+        assert_eq!(due(&calendar, "MGT-14"), d(2024, 10, 9));
+
+        let q = CalendarQuery { board_meeting_date: Some(d(2024, 8, 10)), ..query(CompanyType::Public) };
+        assert_eq!(due(&build_calendar(&q, &empty_registry()), "MGT-14"), d(2024, 9, 9));
+    }
+
+    #[test]
+    fn consolidated_statements_need_subsidiaries() {
+        let q = CalendarQuery { has_subsidiaries: true, ..query(CompanyType::Private) };
+        assert_eq!(due(&build_calendar(&q, &empty_registry()), "AOC-4 CFS"), d(2024, 10, 30));
+
+        // Small companies cannot hold subsidiaries under Sec 2(85)
+        let q = CalendarQuery { has_subsidiaries: true, ..query(CompanyType::Small) };
+        assert!(!forms(&build_calendar(&q, &empty_registry())).contains(&"AOC-4 CFS"));
+    }
+
+    // Incorporated in the second half of the FY: only the Oct-Mar MSME-1 return applies
+    #[test]
+    fn msme1_skips_half_years_before_incorporation() {
+        let q = CalendarQuery { incorporation_date: d(2023, 11, 15), ..query(CompanyType::Private) };
+        let calendar = build_calendar(&q, &empty_registry());
+        let msme: Vec<NaiveDate> = calendar.obligations.iter().filter(|e| e.form == "MSME-1").map(|e| e.due_date).collect();
+        assert_eq!(msme, [d(2024, 4, 30)]);
+    }
+
+    fn profile(company_type: CompanyType, first_fy: bool, agm_extension_months: u32) -> CompanyProfile {
+        CompanyProfile { company_type, first_fy, agm_extension_months }
+    }
+
+    // Sec 96(1): six months from FY close, nine for the first AGM, plus up to three months' ROC extension
+    #[test]
+    fn agm_due_date_by_profile() {
+        let fy_end = d(2024, 3, 31);
+        assert_eq!(agm_due_date(fy_end, &profile(CompanyType::Private, false, 0)), Some(d(2024, 9, 30)));
+        assert_eq!(agm_due_date(fy_end, &profile(CompanyType::Public, true, 0)), Some(d(2024, 12, 31)));
+        assert_eq!(agm_due_date(fy_end, &profile(CompanyType::Small, false, 3)), Some(d(2024, 12, 31)));
+        assert_eq!(agm_due_date(fy_end, &profile(CompanyType::Private, false, 1)), Some(d(2024, 10, 31)));
+        assert_eq!(agm_due_date(fy_end, &profile(CompanyType::Opc, false, 0)), None);
+        assert_eq!(agm_due_date(fy_end, &profile(CompanyType::Llp, false, 0)), None);
+    }
+
+    // An extended AGM moves AOC-4, MGT-7 and ADT-1 with it
+    #[test]
+    fn agm_extension_shifts_linked_filings() {
+        let extended = profile(CompanyType::Private, false, 2);
+        assert_eq!(financial_statements_due(d(2024, 3, 31), &extended), d(2024, 12, 30));
+        assert_eq!(annual_return_due(d(2024, 3, 31), &extended), d(2025, 1, 29));
+    }
+
+    // Sec 137(1) proviso: an OPC files AOC-4 within 180 days of FY close, not 30 days of an AGM it never holds
+    #[test]
+    fn opc_files_financial_statements_within_180_days() {
+        let opc = profile(CompanyType::Opc, false, 0);
+        assert_eq!(financial_statements_due(d(2024, 3, 31), &opc), d(2024, 9, 27));
+        // MGT-7A still runs from the date the AGM would have been due
+        assert_eq!(annual_return_due(d(2024, 3, 31), &opc), d(2024, 11, 29));
+
+        let calendar = build_calendar(&query(CompanyType::Opc), &empty_registry());
+        assert_eq!(calendar.agm_due_date, None);
+        assert_eq!(due(&calendar, "AOC-4"), d(2024, 9, 27));
+        assert!(forms(&calendar).contains(&"MGT-7A"));
+    }
+
+    #[test]
+    fn llp_calendar_has_no_agm() {
+        let calendar = build_calendar(&query(CompanyType::Llp), &empty_registry());
+        assert_eq!(calendar.agm_due_date, None);
+        assert_eq!(forms(&calendar), ["Form 11", "DIR-3 KYC", "Form 8"]);
+    }
+
+    #[test]
+    fn extension_rejected_where_no_agm_can_be_extended() {
+        let reasons = |p: CompanyProfile| {
+            let mut errors = Vec::new();
+            p.check("", &mut errors);
+            errors.iter().map(|e| e.reason()).collect::<Vec<_>>()
+        };
+        assert!(reasons(profile(CompanyType::Private, false, 3)).is_empty());
+        assert_eq!(reasons(profile(CompanyType::Private, false, 4)), ["the ROC can extend the AGM by at most 3 months"]);
+        assert_eq!(reasons(profile(CompanyType::Public, true, 1)), ["the first AGM cannot be extended"]);
+        assert_eq!(reasons(profile(CompanyType::Opc, false, 1)), ["an OPC does not hold an AGM"]);
+        assert_eq!(reasons(profile(CompanyType::Llp, false, 1)), ["an LLP does not hold an AGM"]);
+    }
+
+    #[test]
+    fn explanation_states_the_agm_rule_applied() {
+        let calendar = build_calendar(&query(CompanyType::Opc), &empty_registry());
+        let summary: Vec<(&str, &str)> = calendar.explanation.iter().map(|t| (t.rule.as_str(), t.threshold.as_str())).collect();
+        assert_eq!(summary, [
+            ("AGM deadline", "OPC exempt from holding an AGM"),
+            ("AOC-4 due date", "180 days of FY close (OPC)"),
+            ("MGT-7 due date", "60 days from the date the AGM would have been due (OPC)"),
+        ]);
+        assert_eq!(calendar.explanation[0].outcome, "No AGM due");
+        assert_eq!(calendar.explanation[0].inputs["company_type"], "opc");
+
+        let trace = agm_trace(d(2024, 3, 31), &profile(CompanyType::Private, false, 2));
+        assert_eq!(trace.threshold, "6 months of FY close + 2 month(s) ROC extension");
+        assert_eq!(trace.outcome, "AGM due by 2024-11-30");
+    }
+}
@@ -0,0 +1,168 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+// MCA General Circulars that move statutory deadlines or waive additional fees.
+// The registry is a versioned JSON file so it can be updated without a rebuild.
+
+const BUNDLED_REGISTRY: &str = include_str!("../data/circulars.json");
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Relief {
+    // Due date for the given FY moves to `extended_due_date`
+    Extension { fy_end_date: NaiveDate, extended_due_date: NaiveDate },
+    // No additional fee on filings made within the window for documents due on or before the cut-off
+    FeeWaiver { effective_from: NaiveDate, effective_to: NaiveDate, due_on_or_before: Option<NaiveDate> },
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct Circular {
+    pub id: String,
+    pub title: String,
+    pub issued_on: NaiveDate,
+    pub forms: Vec<String>,
+    #[serde(flatten)]
+    pub relief: Relief,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+pub struct CircularRegistry {
+    pub version: String,
+    pub circulars: Vec<Circular>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct CircularCitation {
+    pub id: String,
+    pub title: String,
+    pub effect: String,
+    pub registry_version: String,
+}
+
+impl CircularRegistry {
+    // Registry compiled into the binary; the backend prefers the copy in its data dir when present
+    pub fn bundled() -> Self {
+        Self::from_json(BUNDLED_REGISTRY).expect("Bundled circular registry is invalid")
+    }
+
+    pub fn from_json(raw: &str) -> serde_json::Result<Self> {
+        serde_json::from_str(raw)
+    }
+
+    fn cite(&self, circular: &Circular, effect: String) -> CircularCitation {
+        CircularCitation {
+            id: circular.id.clone(),
+            title: circular.title.clone(),
+            effect,
+            registry_version: self.version.clone(),
+        }
+    }
+
+    // Latest extension of `form` for the FY, if it pushes the statutory due date out
+    pub fn extended_due_date(&self, form: &str, fy_end: NaiveDate, statutory_due: NaiveDate) -> (NaiveDate, Option<CircularCitation>) {
+        let latest = self.circulars.iter()
+            .filter(|c| c.forms.iter().any(|f| f == form))
+            .filter_map(|c| match c.relief {
+                Relief::Extension { fy_end_date, extended_due_date } if fy_end_date == fy_end && extended_due_date > statutory_due => Some((c, extended_due_date)),
+                _ => None,
+            })
+            .max_by_key(|(c, date)| (*date, c.issued_on));
+
+        match latest {
+            Some((c, date)) => (date, Some(self.cite(c, format!("{} due date extended from {} to {}", form, statutory_due, date)))),
+            None => (statutory_due, None),
+        }
+    }
+
+    // Waiver covering a filing of `form` made on `filing_date` for a document due on `due_date`
+    pub fn fee_waiver(&self, form: &str, due_date: NaiveDate, filing_date: NaiveDate) -> Option<CircularCitation> {
+        self.circulars.iter()
+            .filter(|c| c.forms.iter().any(|f| f == form))
+            .find(|c| match c.relief {
+                Relief::FeeWaiver { effective_from, effective_to, due_on_or_before } => {
+                    filing_date >= effective_from && filing_date <= effective_to && due_on_or_before.is_none_or(|cutoff| due_date <= cutoff)
+                }
+                _ => false,
+            })
+            .map(|c| self.cite(c, format!("Additional fee on {} waived for filings made on {}", form, filing_date)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn d(y: i32, m: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, day).unwrap()
+    }
+
+    fn circular(id: &str, issued_on: NaiveDate, relief: Relief) -> Circular {
+        Circular { id: id.to_string(), title: format!("Circular {}", id), issued_on, forms: vec!["AOC-4".to_string()], relief }
+    }
+
+    fn registry(circulars: Vec<Circular>) -> CircularRegistry {
+        CircularRegistry { version: "test".to_string(), circulars }
+    }
+
+    #[test]
+    fn bundled_registry_parses() {
+        let bundled = CircularRegistry::bundled();
+        assert!(!bundled.version.is_empty());
+        assert!(!bundled.circulars.is_empty());
+    }
+
+    // FY 2020-21 MGT-7: GC 17/2021 moved it to 28 Feb 2022, GC 22/2021 further to 31 Mar 2022
+    #[test]
+    fn latest_extension_wins() {
+        let (due, citation) = CircularRegistry::bundled().extended_due_date("MGT-7", d(2021, 3, 31), d(2021, 11, 29));
+        assert_eq!(due, d(2022, 3, 31));
+        let citation = citation.unwrap();
+        assert_eq!(citation.id, "GC 22/2021");
+        assert_eq!(citation.registry_version, "2025.1");
+
+        // Other financial years keep the statutory date
+        let (due, citation) = CircularRegistry::bundled().extended_due_date("MGT-7", d(2022, 3, 31), d(2022, 11, 29));
+        assert_eq!(due, d(2022, 11, 29));
+        assert!(citation.is_none());
+    }
+
+    #[test]
+    fn extension_never_shortens_the_statutory_date() {
+        let early = circular("GC-EARLY", d(2024, 9, 1), Relief::Extension { fy_end_date: d(2024, 3, 31), extended_due_date: d(2024, 10, 15) });
+        let (due, citation) = registry(vec![early]).extended_due_date("AOC-4", d(2024, 3, 31), d(2024, 10, 30));
+        assert_eq!(due, d(2024, 10, 30));
+        assert!(citation.is_none());
+    }
+
+    // A fee waiver never moves a due date and an extension never waives a fee, even for the same form
+    #[test]
+    fn extension_and_fee_waiver_apply_independently() {
+        let registry = registry(vec![
+            circular("GC-WAIVE", d(2024, 11, 1), Relief::FeeWaiver { effective_from: d(2024, 11, 1), effective_to: d(2024, 12, 31), due_on_or_before: None }),
+            circular("GC-EXTEND", d(2024, 10, 20), Relief::Extension { fy_end_date: d(2024, 3, 31), extended_due_date: d(2024, 11, 30) }),
+        ]);
+
+        let (due, citation) = registry.extended_due_date("AOC-4", d(2024, 3, 31), d(2024, 10, 30));
+        assert_eq!((due, citation.map(|c| c.id)), (d(2024, 11, 30), Some("GC-EXTEND".to_string())));
+
+        let waiver = registry.fee_waiver("AOC-4", due, d(2024, 12, 10)).map(|c| c.id);
+        assert_eq!(waiver, Some("GC-WAIVE".to_string()));
+        assert!(registry.fee_waiver("AOC-4", due, d(2025, 1, 2)).is_none());
+        assert!(registry.fee_waiver("MGT-7", due, d(2024, 12, 10)).is_none());
+    }
+
+    // The waiver cut-off is tested against the due date after any extension, so an extension
+    // that pushes the deadline past the cut-off takes the form out of the waiver
+    #[test]
+    fn extended_due_date_is_checked_against_waiver_cutoff() {
+        let registry = registry(vec![
+            circular("GC-WAIVE", d(2024, 11, 1), Relief::FeeWaiver { effective_from: d(2024, 11, 1), effective_to: d(2025, 1, 31), due_on_or_before: Some(d(2024, 10, 31)) }),
+            circular("GC-EXTEND", d(2024, 10, 20), Relief::Extension { fy_end_date: d(2024, 3, 31), extended_due_date: d(2024, 11, 30) }),
+        ]);
+        assert!(registry.fee_waiver("AOC-4", d(2024, 10, 30), d(2024, 12, 10)).is_some());
+
+        let (extended, _) = registry.extended_due_date("AOC-4", d(2024, 3, 31), d(2024, 10, 30));
+        assert!(registry.fee_waiver("AOC-4", extended, d(2024, 12, 10)).is_none());
+    }
+}
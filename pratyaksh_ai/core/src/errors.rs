@@ -0,0 +1,51 @@
+use chrono::NaiveDate;
+
+// Field-level validation failures, reported together so clients can fix every input in one round trip
+#[derive(Debug, Clone, PartialEq)]
+pub enum FieldError {
+    InvalidDate { field: String, value: String },
+    UnsupportedForm { field: String, value: String, accepted: &'static [&'static str] },
+    OutOfRange { field: String, reason: String },
+}
+
+impl FieldError {
+    pub fn field(&self) -> &str {
+        match self {
+            FieldError::InvalidDate { field, .. } => field,
+            FieldError::UnsupportedForm { field, .. } => field,
+            FieldError::OutOfRange { field, .. } => field,
+        }
+    }
+
+    pub fn reason(&self) -> String {
+        match self {
+            FieldError::InvalidDate { value, .. } => format!("'{}' is not a valid YYYY-MM-DD date", value),
+            FieldError::UnsupportedForm { value, accepted, .. } => format!("'{}' is not a supported form; expected one of {}", value, accepted.join(", ")),
+            FieldError::OutOfRange { reason, .. } => reason.clone(),
+        }
+    }
+}
+
+// Parses a YYYY-MM-DD field, recording a validation error instead of failing fast
+pub fn parse_date(field: &str, raw: &str, errors: &mut Vec<FieldError>) -> Option<NaiveDate> {
+    let parsed = NaiveDate::parse_from_str(raw, "%Y-%m-%d").ok();
+    if parsed.is_none() {
+        errors.push(FieldError::InvalidDate { field: field.to_string(), value: raw.to_string() });
+    }
+    parsed
+}
+
+// ₹10^15: far above any real liability, and low enough that sums and percentages of amounts stay within i64
+pub const MAX_AMOUNT: i64 = 1_000_000_000_000_000;
+
+// Rejects a rupee amount that is negative or above MAX_AMOUNT
+pub fn check_amount(field: &str, amount: i64, errors: &mut Vec<FieldError>) {
+    let reason = if amount < 0 {
+        "must not be negative".to_string()
+    } else if amount > MAX_AMOUNT {
+        format!("must not exceed {}", MAX_AMOUNT)
+    } else {
+        return;
+    };
+    errors.push(FieldError::OutOfRange { field: field.to_string(), reason });
+}
@@ -0,0 +1,341 @@
+use chrono::{Duration, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+use crate::circulars::{CircularCitation, CircularRegistry};
+use crate::llp;
+use crate::trace::RuleTrace;
+
+// Companies (Registration Offices and Fees) Rules, 2014 - Annexure, Table of Fees
+
+// Forms charged ₹100 per day of delay instead of the slab multiple (Rule 12, proviso)
+const PER_DAY_FORMS: [&str; 5] = ["AOC-4", "AOC-4 CFS", "AOC-4 XBRL", "MGT-7", "MGT-7A"];
+const PER_DAY_ADDITIONAL_FEE: i64 = 100;
+
+// Every form the fee endpoint will price: per-day forms, the slab-charged ROC forms and LLP annual filings
+pub const FEE_FORMS: &[&str] = &["AOC-4", "AOC-4 CFS", "AOC-4 XBRL", "MGT-7", "MGT-7A", "ADT-1", "DPT-3", "MGT-14", "Form 8", "Form 11"];
+
+#[derive(Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::IntoParams), into_params(parameter_in = Query))]
+pub struct FeeQuery {
+    pub form: String,
+    pub nominal_share_capital: Option<i64>, // Omit for companies without share capital
+    pub contribution: Option<i64>,          // LLP forms only: total partners' contribution
+    pub due_date: NaiveDate,                // Statutory due date
+    pub fy_end_date: Option<NaiveDate>,     // FY the filing is for; MCA extensions for that FY move the due date
+    pub filing_date: NaiveDate,
+}
+
+#[derive(Serialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct FeeBreakdown {
+    pub form: String,
+    pub due_date: NaiveDate, // After any MCA extension
+    pub days_late: i64,
+    pub normal_fee: i64,
+    pub additional_fee: i64,
+    pub total_fee: i64,
+    pub basis: String,
+    pub circular: Option<CircularCitation>,  // Fee waiver applied, if any
+    pub extension: Option<CircularCitation>, // Due date extension applied, if any
+    pub explanation: Vec<RuleTrace>,
+}
+
+// Normal fee by nominal share capital slab (Table A, item I)
+pub fn normal_fee(nominal_share_capital: Option<i64>) -> i64 {
+    match nominal_share_capital {
+        None => 200,
+        Some(c) if c < 100000 => 200,
+        Some(c) if c < 500000 => 300,
+        Some(c) if c < 2500000 => 400,
+        Some(c) if c < 10000000 => 500,
+        Some(_) => 600,
+    }
+}
+
+pub fn is_per_day_form(form: &str) -> bool {
+    PER_DAY_FORMS.contains(&form)
+}
+
+// Multiple of the normal fee charged on other forms (Table B)
+pub fn slab_multiple(days_late: i64) -> i64 {
+    match days_late {
+        i64::MIN..=0 => 0,
+        1..=30 => 2,
+        31..=60 => 4,
+        61..=90 => 6,
+        91..=180 => 10,
+        _ => 12,
+    }
+}
+
+pub fn additional_fee(form: &str, normal_fee: i64, days_late: i64) -> i64 {
+    if days_late <= 0 {
+        0
+    } else if llp::is_llp_form(form) {
+        llp::additional_fee(days_late)
+    } else if is_per_day_form(form) {
+        PER_DAY_ADDITIONAL_FEE * days_late
+    } else {
+        normal_fee * slab_multiple(days_late)
+    }
+}
+
+pub fn normal_fee_trace(nominal_share_capital: Option<i64>) -> RuleTrace {
+    let fee = normal_fee(nominal_share_capital);
+    let (capital, threshold) = match nominal_share_capital {
+        None => ("none".to_string(), "Company not having share capital".to_string()),
+        Some(c) => (c.to_string(), format!("Nominal share capital slab charging ₹{}", fee)),
+    };
+    RuleTrace::new("Normal filing fee", threshold, format!("₹{}", fee), "Table of Fees, Companies (Registration Offices and Fees) Rules, 2014")
+        .input("nominal_share_capital", capital)
+}
+
+pub fn additional_fee_trace(form: &str, normal_fee: i64, days_late: i64, waiver: Option<&CircularCitation>) -> RuleTrace {
+    let reference = if llp::is_llp_form(form) {
+        "LLP (Amendment) Rules, 2022"
+    } else {
+        "Rule 12, Companies (Registration Offices and Fees) Rules, 2014"
+    };
+    let trace = if let Some(c) = waiver {
+        RuleTrace::new("Additional fee", "Waived by MCA circular", "₹0", format!("{} ({})", c.id, c.title))
+    } else if days_late <= 0 {
+        RuleTrace::new("Additional fee", "Filed on or before due date", "₹0", reference)
+    } else if llp::is_llp_form(form) {
+        let fee = llp::additional_fee(days_late);
+        RuleTrace::new("Additional fee", format!("₹{} per day of delay for {}, no cap", llp::LLP_ADDITIONAL_FEE_PER_DAY, form), format!("₹{}", fee), reference)
+    } else if is_per_day_form(form) {
+        let fee = additional_fee(form, normal_fee, days_late);
+        RuleTrace::new("Additional fee", format!("₹{} per day of delay for {}", PER_DAY_ADDITIONAL_FEE, form), format!("₹{}", fee), reference)
+    } else {
+        let fee = additional_fee(form, normal_fee, days_late);
+        RuleTrace::new("Additional fee", format!("{} times normal fee for {} days delay", slab_multiple(days_late), days_late), format!("₹{}", fee), reference)
+            .input("normal_fee", normal_fee)
+    };
+    trace.input("form", form).input("days_late", days_late.max(0))
+}
+
+// What a missed deadline costs, for reminders and calendar exports
+pub fn late_filing_consequence(form: &str) -> String {
+    match form {
+        f if llp::is_llp_form(f) => format!("Additional fee of ₹{} per day, no cap", llp::LLP_ADDITIONAL_FEE_PER_DAY),
+        f if is_per_day_form(f) => format!("Additional fee of ₹{} per day of delay", PER_DAY_ADDITIONAL_FEE),
+        "ADT-1" | "DPT-3" | "MGT-14" => "Additional fee of 2x to 12x the normal fee, by days of delay".to_string(),
+        "DIR-3 KYC" => "DIN deactivated; ₹5000 fee to reactivate".to_string(),
+        "MSME-1" => "Penalty under Sec 405(4): ₹20000 plus ₹1000 per day, up to ₹300000".to_string(),
+        _ => "Additional fee under Rule 12".to_string(),
+    }
+}
+
+// Days-late points at which a new Table B slab starts
+const SLAB_STARTS: [i64; 5] = [1, 31, 61, 91, 181];
+// Waiting horizons, in days from the evaluation date
+const PROJECTION_HORIZONS: [i64; 5] = [30, 60, 90, 180, 270];
+
+#[derive(Serialize, Clone)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct FeeProjection {
+    pub date: NaiveDate,
+    pub days_late: i64,
+    pub additional_fee: i64,
+}
+
+// Additional fee if the form were filed on each horizon date and on the first day of every slab
+pub fn project_additional_fee(form: &str, normal_fee: i64, due_date: NaiveDate, as_of: NaiveDate, registry: &CircularRegistry) -> Vec<FeeProjection> {
+    let mut dates: Vec<NaiveDate> = PROJECTION_HORIZONS.iter().map(|d| as_of + Duration::days(*d))
+        .chain(SLAB_STARTS.iter().map(|d| due_date + Duration::days(*d)))
+        .filter(|d| *d > as_of)
+        .collect();
+    dates.sort();
+    dates.dedup();
+
+    dates.into_iter()
+        .map(|date| {
+            let days_late = (date - due_date).num_days().max(0);
+            let fee = match registry.fee_waiver(form, due_date, date) {
+                Some(_) => 0,
+                None => additional_fee(form, normal_fee, days_late),
+            };
+            FeeProjection { date, days_late, additional_fee: fee }
+        })
+        .collect()
+}
+
+pub fn compute_fees(q: &FeeQuery, registry: &CircularRegistry) -> FeeBreakdown {
+    let (form, filing_date) = (q.form.as_str(), q.filing_date);
+    let (due_date, extension) = match q.fy_end_date {
+        Some(fy_end) => registry.extended_due_date(form, fy_end, q.due_date),
+        None => (q.due_date, None),
+    };
+    let days_late = (filing_date - due_date).num_days().max(0);
+    let (normal, normal_trace) = if llp::is_llp_form(form) {
+        (llp::normal_fee(q.contribution), llp::normal_fee_trace(q.contribution))
+    } else {
+        (normal_fee(q.nominal_share_capital), normal_fee_trace(q.nominal_share_capital))
+    };
+    let circular = if days_late > 0 { registry.fee_waiver(form, due_date, filing_date) } else { None };
+    let additional = if circular.is_some() { 0 } else { additional_fee(form, normal, days_late) };
+
+    let basis = if days_late == 0 {
+        "Filed within due date".to_string()
+    } else if let Some(c) = &circular {
+        format!("Additional fee waived under {}", c.id)
+    } else if llp::is_llp_form(form) {
+        format!("₹{}/day x {} days", llp::LLP_ADDITIONAL_FEE_PER_DAY, days_late)
+    } else if is_per_day_form(form) {
+        format!("₹{}/day x {} days", PER_DAY_ADDITIONAL_FEE, days_late)
+    } else {
+        format!("{}x normal fee for {} days delay", slab_multiple(days_late), days_late)
+    };
+
+    let mut explanation = vec![normal_trace];
+    if let (Some(c), Some(fy_end)) = (&extension, q.fy_end_date) {
+        explanation.push(RuleTrace::new("Due date extension", "MCA circular for the financial year", c.effect.clone(), format!("{} ({})", c.id, c.title))
+            .input("statutory_due_date", q.due_date)
+            .input("fy_end_date", fy_end));
+    }
+    explanation.extend([
+        RuleTrace::new("Filing delay", format!("Filed after {}", due_date), format!("{} days late", days_late), "Due date under the Act")
+            .input("due_date", due_date)
+            .input("filing_date", filing_date),
+        additional_fee_trace(form, normal, days_late, circular.as_ref()),
+    ]);
+
+    FeeBreakdown {
+        form: form.to_string(),
+        due_date,
+        days_late,
+        normal_fee: normal,
+        additional_fee: additional,
+        total_fee: normal + additional,
+        basis,
+        circular,
+        extension,
+        explanation,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::circulars::{Circular, Relief};
+
+    fn d(y: i32, m: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, day).unwrap()
+    }
+
+    fn registry(circulars: Vec<Circular>) -> CircularRegistry {
+        CircularRegistry { version: "test".to_string(), circulars }
+    }
+
+    fn query(form: &str, capital: Option<i64>, days_late: i64) -> FeeQuery {
+        FeeQuery {
+            form: form.to_string(),
+            nominal_share_capital: capital,
+            contribution: None,
+            due_date: d(2024, 10, 30),
+            fy_end_date: None,
+            filing_date: d(2024, 10, 30) + Duration::days(days_late),
+        }
+    }
+
+    // Table of Fees, item I: < ₹1 lakh ₹200; ₹1-5 lakh ₹300; ₹5-25 lakh ₹400; ₹25 lakh-1 crore ₹500; ₹1 crore+ ₹600
+    #[test]
+    fn normal_fee_slab_boundaries() {
+        assert_eq!(normal_fee(None), 200);
+        assert_eq!(normal_fee(Some(99999)), 200);
+        assert_eq!(normal_fee(Some(100000)), 300);
+        assert_eq!(normal_fee(Some(499999)), 300);
+        assert_eq!(normal_fee(Some(500000)), 400);
+        assert_eq!(normal_fee(Some(2500000)), 500);
+        assert_eq!(normal_fee(Some(9999999)), 500);
+        assert_eq!(normal_fee(Some(10000000)), 600);
+    }
+
+    // Table B: up to 30 days 2x, 31-60 4x, 61-90 6x, 91-180 10x, beyond 180 12x
+    #[test]
+    fn slab_multiple_boundaries() {
+        assert_eq!(slab_multiple(0), 0);
+        assert_eq!(slab_multiple(30), 2);
+        assert_eq!(slab_multiple(31), 4);
+        assert_eq!(slab_multiple(60), 4);
+        assert_eq!(slab_multiple(90), 6);
+        assert_eq!(slab_multiple(91), 10);
+        assert_eq!(slab_multiple(180), 10);
+        assert_eq!(slab_multiple(181), 12);
+    }
+
+    // AOC-4 of a ₹10 lakh company filed 45 days late: ₹400 + ₹100 x 45
+    #[test]
+    fn aoc4_charged_per_day() {
+        let fees = compute_fees(&query("AOC-4", Some(1000000), 45), &registry(Vec::new()));
+        assert_eq!(fees.days_late, 45);
+        assert_eq!(fees.normal_fee, 400);
+        assert_eq!(fees.additional_fee, 4500);
+        assert_eq!(fees.total_fee, 4900);
+    }
+
+    // ADT-1 of a ₹10 lakh company filed 45 days late: 4 x ₹400
+    #[test]
+    fn adt1_charged_by_slab() {
+        let fees = compute_fees(&query("ADT-1", Some(1000000), 45), &registry(Vec::new()));
+        assert_eq!(fees.additional_fee, 1600);
+        assert_eq!(fees.total_fee, 2000);
+    }
+
+    // Form 11 with ₹1 lakh contribution filed 10 days late: ₹50 + ₹100 x 10
+    #[test]
+    fn llp_form_uses_contribution_slab() {
+        let q = FeeQuery { contribution: Some(100000), ..query("Form 11", None, 10) };
+        let fees = compute_fees(&q, &registry(Vec::new()));
+        assert_eq!(fees.normal_fee, 50);
+        assert_eq!(fees.additional_fee, 1000);
+    }
+
+    #[test]
+    fn filed_on_time_pays_normal_fee_only() {
+        let fees = compute_fees(&query("MGT-7", Some(1000000), 0), &registry(Vec::new()));
+        assert_eq!(fees.additional_fee, 0);
+        assert_eq!(fees.total_fee, 400);
+    }
+
+    #[test]
+    fn fee_waiver_within_window() {
+        let waiver = Circular {
+            id: "GC-TEST".to_string(),
+            title: "Waiver".to_string(),
+            issued_on: d(2024, 11, 1),
+            forms: vec!["AOC-4".to_string()],
+            relief: Relief::FeeWaiver { effective_from: d(2024, 11, 1), effective_to: d(2024, 12, 31), due_on_or_before: Some(d(2024, 10, 30)) },
+        };
+        let fees = compute_fees(&query("AOC-4", Some(1000000), 45), &registry(vec![waiver.clone()]));
+        assert_eq!(fees.additional_fee, 0);
+        assert_eq!(fees.circular.map(|c| c.id), Some("GC-TEST".to_string()));
+
+        // Filed after the window closes: full additional fee
+        let fees = compute_fees(&query("AOC-4", Some(1000000), 90), &registry(vec![waiver]));
+        assert_eq!(fees.additional_fee, 9000);
+    }
+
+    // FY 2020-21 AOC-4, statutory due 30 Oct 2021, extended to 15 Feb 2022 by GC 22/2021
+    #[test]
+    fn extension_moves_due_date() {
+        let q = FeeQuery {
+            due_date: d(2021, 10, 30),
+            fy_end_date: Some(d(2021, 3, 31)),
+            filing_date: d(2022, 2, 20),
+            ..query("AOC-4", Some(1000000), 0)
+        };
+        let fees = compute_fees(&q, &CircularRegistry::bundled());
+        assert_eq!(fees.due_date, d(2022, 2, 15));
+        assert_eq!(fees.days_late, 5);
+        assert_eq!(fees.additional_fee, 500);
+        assert_eq!(fees.extension.map(|c| c.id), Some("GC 22/2021".to_string()));
+
+        let within = FeeQuery { filing_date: d(2022, 2, 15), ..q };
+        assert_eq!(compute_fees(&within, &CircularRegistry::bundled()).additional_fee, 0);
+
+        // Without the FY no extension can be looked up: late from the statutory date
+        let statutory = FeeQuery { fy_end_date: None, ..within };
+        assert_eq!(compute_fees(&statutory, &CircularRegistry::bundled()).days_late, 108);
+    }
+}
@@ -0,0 +1,410 @@
+use chrono::{Datelike, Months, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+use crate::errors::FieldError;
+use crate::tools::Calculator;
+use crate::trace::RuleTrace;
+
+// CGST Act, 2017 - return due dates (Sec 37, 39, 44), late fee (Sec 47) and interest (Sec 50)
+
+// Returns the late-fee endpoint prices; GSTR-9C has no separate Sec 47 fee, it is charged on GSTR-9
+pub const LATE_FEE_RETURNS: &[&str] = &["GSTR-1", "GSTR-3B", "GSTR-9"];
+
+#[derive(Deserialize, Serialize, Clone, Copy, PartialEq, Debug, Default)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum FilingScheme {
+    #[default]
+    Monthly,
+    Qrmp, // Quarterly Return Monthly Payment, turnover up to ₹5 crore
+}
+
+// QRMP GSTR-3B due date depends on the state of registration (N.No. 82/2020-CT)
+#[derive(Deserialize, Serialize, Clone, Copy, PartialEq, Debug)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum StateGroup {
+    A, // CG, MP, GJ, DD, DNH, MH, KA, GA, LD, KL, TN, PY, AN, TS, AP: 22nd
+    B, // All other states and UTs: 24th
+}
+
+#[derive(Deserialize, Serialize, Clone, Copy, PartialEq, Debug, Default)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum InterestGround {
+    #[default]
+    LatePayment, // Sec 50(1): tax paid after the due date
+    ExcessItc,   // Sec 50(3): ITC wrongly availed and utilised
+}
+
+#[derive(Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::IntoParams), into_params(parameter_in = Query))]
+pub struct GstDueDateQuery {
+    pub period: String, // YYYY-MM, the tax period (quarter-end month under QRMP)
+    #[serde(default)]
+    pub scheme: FilingScheme,
+    pub state_group: Option<StateGroup>, // Required for QRMP
+    pub aggregate_turnover: Option<i64>, // Previous FY; decides GSTR-9 / GSTR-9C applicability
+}
+
+#[derive(Serialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct GstDueDate {
+    pub return_type: String,
+    pub description: String,
+    pub due_date: NaiveDate,
+    pub section: String,
+}
+
+#[derive(Serialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct GstDueDates {
+    pub period: String,
+    pub scheme: FilingScheme,
+    pub returns: Vec<GstDueDate>,
+    pub explanation: Vec<RuleTrace>,
+}
+
+#[derive(Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::IntoParams), into_params(parameter_in = Query))]
+pub struct GstLateFeeQuery {
+    pub return_type: String,
+    pub due_date: NaiveDate,
+    pub filing_date: NaiveDate,
+    #[serde(default)]
+    pub nil_return: bool,
+    pub aggregate_turnover: Option<i64>, // Required for GSTR-9
+}
+
+#[derive(Serialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct GstLateFee {
+    pub return_type: String,
+    pub days_late: i64,
+    pub per_day: i64,
+    pub cap: i64,
+    pub late_fee: i64, // CGST + SGST combined
+    pub capped: bool,
+    pub explanation: Vec<RuleTrace>,
+}
+
+#[derive(Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::IntoParams), into_params(parameter_in = Query))]
+pub struct GstInterestQuery {
+    pub tax_amount: i64,
+    pub due_date: NaiveDate,
+    pub payment_date: NaiveDate,
+    #[serde(default)]
+    pub ground: InterestGround,
+}
+
+#[derive(Serialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct GstInterest {
+    pub ground: InterestGround,
+    pub rate_percent: i64,
+    pub days: i64,
+    pub interest: i64,
+    pub explanation: Vec<RuleTrace>,
+}
+
+const CRORE: i64 = 10000000;
+
+fn day_of_next_month(period: NaiveDate, day: u32) -> NaiveDate {
+    (period + Months::new(1)).with_day(day).unwrap()
+}
+
+// Sec 37 r/w Rule 59: GSTR-1 by the 11th; QRMP by the 13th after the quarter
+pub fn gstr1_due(period: NaiveDate, scheme: FilingScheme) -> NaiveDate {
+    match scheme {
+        FilingScheme::Monthly => day_of_next_month(period, 11),
+        FilingScheme::Qrmp => day_of_next_month(period, 13),
+    }
+}
+
+// Sec 39 r/w Rule 61: GSTR-3B by the 20th; QRMP by the 22nd or 24th after the quarter
+pub fn gstr3b_due(period: NaiveDate, scheme: FilingScheme, state_group: StateGroup) -> NaiveDate {
+    match (scheme, state_group) {
+        (FilingScheme::Monthly, _) => day_of_next_month(period, 20),
+        (FilingScheme::Qrmp, StateGroup::A) => day_of_next_month(period, 22),
+        (FilingScheme::Qrmp, StateGroup::B) => day_of_next_month(period, 24),
+    }
+}
+
+// Sec 44 r/w Rule 80: GSTR-9 and GSTR-9C by 31 December after the FY
+pub fn annual_return_due(fy_end: NaiveDate) -> NaiveDate {
+    NaiveDate::from_ymd_opt(fy_end.year(), 12, 31).unwrap()
+}
+
+fn due(return_type: &str, description: &str, due_date: NaiveDate, section: &str) -> GstDueDate {
+    GstDueDate {
+        return_type: return_type.to_string(),
+        description: description.to_string(),
+        due_date,
+        section: section.to_string(),
+    }
+}
+
+pub fn due_dates(period: NaiveDate, scheme: FilingScheme, state_group: Option<StateGroup>, turnover: Option<i64>) -> (Vec<GstDueDate>, Vec<RuleTrace>) {
+    let mut returns = Vec::new();
+    let mut explanation = Vec::new();
+    let label = period.format("%Y-%m").to_string();
+
+    let gstr1 = gstr1_due(period, scheme);
+    returns.push(due("GSTR-1", "Outward supplies", gstr1, "Sec 37 r/w Rule 59"));
+    let threshold = if scheme == FilingScheme::Qrmp { "13th of the month after the quarter" } else { "11th of the following month" };
+    explanation.push(RuleTrace::new("GSTR-1 due date", threshold, format!("GSTR-1 due by {}", gstr1), "Sec 37(1) r/w Rule 59(2)")
+        .input("period", &label)
+        .input("scheme", format!("{:?}", scheme).to_lowercase()));
+
+    let group = state_group.unwrap_or(StateGroup::B);
+    let gstr3b = gstr3b_due(period, scheme, group);
+    returns.push(due("GSTR-3B", "Summary return and tax payment", gstr3b, "Sec 39 r/w Rule 61"));
+    let threshold = match (scheme, group) {
+        (FilingScheme::Monthly, _) => "20th of the following month",
+        (FilingScheme::Qrmp, StateGroup::A) => "22nd of the month after the quarter (state group A)",
+        (FilingScheme::Qrmp, StateGroup::B) => "24th of the month after the quarter (state group B)",
+    };
+    let mut trace = RuleTrace::new("GSTR-3B due date", threshold, format!("GSTR-3B due by {}", gstr3b), "Sec 39(1) r/w Rule 61(5)")
+        .input("period", &label)
+        .input("scheme", format!("{:?}", scheme).to_lowercase());
+    if scheme == FilingScheme::Qrmp {
+        trace = trace.input("state_group", format!("{:?}", group));
+    }
+    explanation.push(trace);
+
+    // March closes the FY, so the annual returns for it fall due on 31 December
+    if period.month() == 3 {
+        let fy_end = NaiveDate::from_ymd_opt(period.year(), 3, 31).unwrap();
+        let annual = annual_return_due(fy_end);
+        let optional = turnover.is_some_and(|t| t <= 2 * CRORE);
+        let desc = if optional { "Annual return (optional up to ₹2 crore turnover)" } else { "Annual return" };
+        returns.push(due("GSTR-9", desc, annual, "Sec 44 r/w Rule 80(1)"));
+        explanation.push(RuleTrace::new("GSTR-9 due date", "31 December after the FY; optional up to ₹2 crore turnover", format!("GSTR-9 due by {}", annual), "Sec 44 r/w Rule 80(1)")
+            .input("fy_end_date", fy_end)
+            .input("aggregate_turnover", turnover.map_or("not given".to_string(), |t| t.to_string())));
+
+        if turnover.is_none_or(|t| t > 5 * CRORE) {
+            returns.push(due("GSTR-9C", "Self-certified reconciliation statement", annual, "Sec 44 r/w Rule 80(3)"));
+            explanation.push(RuleTrace::new("GSTR-9C due date", "Turnover above ₹5 crore; filed with GSTR-9", format!("GSTR-9C due by {}", annual), "Sec 44 r/w Rule 80(3)")
+                .input("aggregate_turnover", turnover.map_or("not given".to_string(), |t| t.to_string())));
+        }
+    }
+
+    returns.sort_by_key(|r| r.due_date);
+    (returns, explanation)
+}
+
+// Sec 47 as relaxed by N.No. 19/2021-CT (GSTR-3B), 20/2021-CT (GSTR-1) and 7/2023-CT (GSTR-9).
+// Amounts are CGST + SGST together.
+fn late_fee_slab(return_type: &str, nil_return: bool, turnover: Option<i64>) -> (i64, i64, String) {
+    match (return_type, turnover) {
+        // 0.04% is t / 2500 and 0.5% is t / 200; dividing rather than multiplying cannot overflow
+        ("GSTR-9", Some(t)) if t <= 5 * CRORE => (50, t / 2500, "₹50/day up to 0.04% of turnover (turnover up to ₹5 crore)".to_string()),
+        ("GSTR-9", Some(t)) if t <= 20 * CRORE => (100, t / 2500, "₹100/day up to 0.04% of turnover (turnover ₹5-20 crore)".to_string()),
+        ("GSTR-9", Some(t)) => (200, t / 200, "₹200/day up to 0.5% of turnover (turnover above ₹20 crore)".to_string()),
+        // Rejected by check; unchecked callers get the highest rate with no turnover cap rather than ₹0
+        ("GSTR-9", None) => (200, i64::MAX, "₹200/day, uncapped (turnover not given)".to_string()),
+        _ if nil_return => (20, 500, "₹20/day up to ₹500 (nil return)".to_string()),
+        (_, Some(t)) if t <= 15 * CRORE / 10 => (50, 2000, "₹50/day up to ₹2000 (turnover up to ₹1.5 crore)".to_string()),
+        (_, Some(t)) if t <= 5 * CRORE => (50, 5000, "₹50/day up to ₹5000 (turnover ₹1.5-5 crore)".to_string()),
+        (_, Some(_)) => (50, 10000, "₹50/day up to ₹10000 (turnover above ₹5 crore)".to_string()),
+        (_, None) => (50, 10000, "₹50/day up to the statutory ₹10000 (turnover not given)".to_string()),
+    }
+}
+
+impl Calculator for GstLateFeeQuery {
+    type Output = GstLateFee;
+
+    fn check(&self) -> Vec<FieldError> {
+        let mut errors = Vec::new();
+        if !LATE_FEE_RETURNS.contains(&self.return_type.as_str()) {
+            errors.push(FieldError::UnsupportedForm { field: "return_type".to_string(), value: self.return_type.clone(), accepted: LATE_FEE_RETURNS });
+        }
+        // The GSTR-9 cap is a share of turnover, so without it there is no fee to compute
+        if self.return_type == "GSTR-9" && self.aggregate_turnover.is_none() {
+            errors.push(FieldError::OutOfRange { field: "aggregate_turnover".to_string(), reason: "required for GSTR-9".to_string() });
+        }
+        if self.aggregate_turnover.is_some_and(|t| t < 0) {
+            errors.push(FieldError::OutOfRange { field: "aggregate_turnover".to_string(), reason: "must not be negative".to_string() });
+        }
+        errors
+    }
+
+    fn evaluate(&self) -> GstLateFee {
+        late_fee(self)
+    }
+}
+
+fn late_fee(q: &GstLateFeeQuery) -> GstLateFee {
+    let days_late = (q.filing_date - q.due_date).num_days().max(0);
+    let (per_day, cap, threshold) = late_fee_slab(&q.return_type, q.nil_return, q.aggregate_turnover);
+    let uncapped = per_day * days_late;
+    let fee = uncapped.min(cap);
+
+    let mut trace = RuleTrace::new("GST late fee", threshold, format!("₹{}", fee), "Sec 47")
+        .input("return_type", &q.return_type)
+        .input("days_late", days_late)
+        .input("nil_return", q.nil_return);
+    if let Some(t) = q.aggregate_turnover {
+        trace = trace.input("aggregate_turnover", t);
+    }
+
+    GstLateFee {
+        return_type: q.return_type.clone(),
+        days_late,
+        per_day,
+        cap,
+        late_fee: fee,
+        capped: uncapped > cap,
+        explanation: vec![
+            RuleTrace::new("Filing delay", format!("Filed after {}", q.due_date), format!("{} days late", days_late), "Sec 47(1)")
+                .input("due_date", q.due_date)
+                .input("filing_date", q.filing_date),
+            trace,
+        ],
+    }
+}
+
+pub fn interest_rate(ground: InterestGround) -> i64 {
+    match ground {
+        InterestGround::LatePayment => 18,
+        InterestGround::ExcessItc => 24,
+    }
+}
+
+// Simple interest for each day from the day after the due date up to the payment date
+pub fn interest(tax_amount: i64, ground: InterestGround, due_date: NaiveDate, payment_date: NaiveDate) -> GstInterest {
+    let days = (payment_date - due_date).num_days().max(0);
+    let rate = interest_rate(ground);
+    // Sec 170: rounded off to the nearest rupee
+    let amount = (tax_amount as f64 * rate as f64 / 100.0 * days as f64 / 365.0).round() as i64;
+
+    let (threshold, reference) = match ground {
+        InterestGround::LatePayment => ("18% p.a. on tax paid after the due date", "Sec 50(1)"),
+        InterestGround::ExcessItc => ("24% p.a. on ITC wrongly availed and utilised", "Sec 50(3)"),
+    };
+
+    GstInterest {
+        ground,
+        rate_percent: rate,
+        days,
+        interest: amount,
+        explanation: vec![
+            RuleTrace::new("GST interest", threshold, format!("₹{} for {} days", amount, days), reference)
+                .input("tax_amount", tax_amount)
+                .input("due_date", due_date)
+                .input("payment_date", payment_date),
+        ],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn d(y: i32, m: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, day).unwrap()
+    }
+
+    fn late(return_type: &str, days: i64, nil_return: bool, turnover: Option<i64>) -> GstLateFee {
+        late_fee(&GstLateFeeQuery {
+            return_type: return_type.to_string(),
+            due_date: d(2024, 5, 20),
+            filing_date: d(2024, 5, 20) + chrono::Duration::days(days),
+            nil_return,
+            aggregate_turnover: turnover,
+        })
+    }
+
+    #[test]
+    fn monthly_and_qrmp_due_dates() {
+        assert_eq!(gstr1_due(d(2024, 4, 1), FilingScheme::Monthly), d(2024, 5, 11));
+        assert_eq!(gstr3b_due(d(2024, 4, 1), FilingScheme::Monthly, StateGroup::B), d(2024, 5, 20));
+        // Quarter April-June under QRMP
+        assert_eq!(gstr1_due(d(2024, 6, 1), FilingScheme::Qrmp), d(2024, 7, 13));
+        assert_eq!(gstr3b_due(d(2024, 6, 1), FilingScheme::Qrmp, StateGroup::A), d(2024, 7, 22));
+        assert_eq!(gstr3b_due(d(2024, 6, 1), FilingScheme::Qrmp, StateGroup::B), d(2024, 7, 24));
+        // December period rolls into January of the next year
+        assert_eq!(gstr3b_due(d(2024, 12, 1), FilingScheme::Monthly, StateGroup::B), d(2025, 1, 20));
+    }
+
+    #[test]
+    fn march_period_adds_annual_returns() {
+        let (returns, _) = due_dates(d(2024, 3, 1), FilingScheme::Monthly, None, Some(60000000));
+        let types: Vec<&str> = returns.iter().map(|r| r.return_type.as_str()).collect();
+        assert_eq!(types, ["GSTR-1", "GSTR-3B", "GSTR-9", "GSTR-9C"]);
+        assert_eq!(returns[2].due_date, d(2024, 12, 31));
+
+        // GSTR-9C only above ₹5 crore
+        let (returns, _) = due_dates(d(2024, 3, 1), FilingScheme::Monthly, None, Some(40000000));
+        assert!(returns.iter().all(|r| r.return_type != "GSTR-9C"));
+    }
+
+    // N.No. 19/2021-CT: ₹50/day (₹25 CGST + ₹25 SGST), capped at ₹2000 up to ₹1.5 crore turnover
+    #[test]
+    fn gstr3b_late_fee_and_cap() {
+        let fee = late("GSTR-3B", 10, false, Some(10000000));
+        assert_eq!((fee.days_late, fee.late_fee, fee.capped), (10, 500, false));
+
+        let fee = late("GSTR-3B", 100, false, Some(10000000));
+        assert_eq!((fee.late_fee, fee.capped), (2000, true));
+
+        let fee = late("GSTR-3B", 100, false, Some(40000000));
+        assert_eq!(fee.late_fee, 5000);
+    }
+
+    // Nil return: ₹20/day up to ₹500
+    #[test]
+    fn nil_return_late_fee() {
+        assert_eq!(late("GSTR-1", 10, true, None).late_fee, 200);
+        assert_eq!(late("GSTR-1", 30, true, None).late_fee, 500);
+    }
+
+    // N.No. 7/2023-CT: GSTR-9 ₹50/day up to 0.04% of turnover (≤ ₹5 crore); ₹200/day up to 0.5% above ₹20 crore
+    #[test]
+    fn gstr9_late_fee_by_turnover() {
+        assert_eq!(late("GSTR-9", 100, false, Some(40000000)).late_fee, 5000);
+        assert_eq!(late("GSTR-9", 400, false, Some(40000000)).late_fee, 16000);
+        assert_eq!(late("GSTR-9", 100, false, Some(250000000)).late_fee, 20000);
+    }
+
+    // Caps are divided out of turnover, so the largest turnover a client can send still prices
+    #[test]
+    fn gstr9_cap_does_not_overflow() {
+        let fee = late("GSTR-9", 100, false, Some(i64::MAX));
+        assert_eq!((fee.cap, fee.late_fee), (i64::MAX / 200, 20000));
+    }
+
+    #[test]
+    fn gstr9_requires_turnover() {
+        let q = GstLateFeeQuery {
+            return_type: "GSTR-9".to_string(),
+            due_date: d(2024, 12, 31),
+            filing_date: d(2025, 1, 30),
+            nil_return: false,
+            aggregate_turnover: None,
+        };
+        let fields: Vec<String> = q.check().iter().map(|e| format!("{}: {}", e.field(), e.reason())).collect();
+        assert_eq!(fields, ["aggregate_turnover: required for GSTR-9"]);
+        // Evaluated anyway, the fee is never silently ₹0
+        assert_eq!(q.evaluate().late_fee, 6000);
+
+        let q = GstLateFeeQuery { return_type: "GSTR-4".to_string(), aggregate_turnover: Some(-1), ..q };
+        let fields: Vec<String> = q.check().iter().map(|e| e.field().to_string()).collect();
+        assert_eq!(fields, ["return_type", "aggregate_turnover"]);
+    }
+
+    // Sec 50(1): ₹1,00,000 paid 30 days late at 18% = 1,00,000 x 18% x 30/365 = ₹1,479.45, rounded to ₹1,479
+    #[test]
+    fn interest_per_day() {
+        let i = interest(100000, InterestGround::LatePayment, d(2024, 5, 20), d(2024, 6, 19));
+        assert_eq!((i.days, i.interest), (30, 1479));
+
+        // Sec 50(3): 24% for a full year
+        let i = interest(100000, InterestGround::ExcessItc, d(2023, 5, 20), d(2024, 5, 19));
+        assert_eq!((i.days, i.interest), (365, 24000));
+
+        assert_eq!(interest(100000, InterestGround::LatePayment, d(2024, 5, 20), d(2024, 5, 20)).interest, 0);
+    }
+}
@@ -0,0 +1,154 @@
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+
+use crate::calendar::ComplianceCalendar;
+use crate::fees;
+
+// RFC 5545 iCalendar export of computed deadlines
+
+const PRODID: &str = "-//Pratyaksh AI//Compliance Calendar//EN";
+const ALARMS: [&str; 2] = ["-P7D", "-P1D"];
+const MAX_LINE_OCTETS: usize = 75;
+
+pub struct IcsEvent {
+    pub uid: String,
+    pub date: NaiveDate,
+    pub summary: String,
+    pub description: String,
+}
+
+// RFC 5545 3.3.11: backslash, semicolon, comma and newline are escaped in TEXT values.
+// CRLF and lone CR count as newlines too, so no input can start a property line of its own
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace("\r\n", "\n")
+        .replace('\r', "\n")
+        .replace('\n', "\\n")
+}
+
+// RFC 5545 3.1: lines longer than 75 octets are folded with CRLF + space, never inside a UTF-8 character
+fn fold(line: &str, out: &mut String) {
+    let mut width = 0;
+    for ch in line.chars() {
+        let len = ch.len_utf8();
+        if width + len > MAX_LINE_OCTETS {
+            out.push_str("\r\n ");
+            width = 1;
+        }
+        out.push(ch);
+        width += len;
+    }
+    out.push_str("\r\n");
+}
+
+pub fn render(name: &str, events: &[IcsEvent], stamp: DateTime<Utc>) -> String {
+    let mut out = String::new();
+    let dtstamp = stamp.format("%Y%m%dT%H%M%SZ").to_string();
+
+    for line in ["BEGIN:VCALENDAR", "VERSION:2.0", &format!("PRODID:{}", PRODID), "CALSCALE:GREGORIAN", "METHOD:PUBLISH"] {
+        fold(line, &mut out);
+    }
+    fold(&format!("X-WR-CALNAME:{}", escape(name)), &mut out);
+
+    for e in events {
+        fold("BEGIN:VEVENT", &mut out);
+        fold(&format!("UID:{}", e.uid), &mut out);
+        fold(&format!("DTSTAMP:{}", dtstamp), &mut out);
+        // All-day event: DTEND is exclusive
+        fold(&format!("DTSTART;VALUE=DATE:{}", e.date.format("%Y%m%d")), &mut out);
+        fold(&format!("DTEND;VALUE=DATE:{}", (e.date + Duration::days(1)).format("%Y%m%d")), &mut out);
+        fold(&format!("SUMMARY:{}", escape(&e.summary)), &mut out);
+        fold(&format!("DESCRIPTION:{}", escape(&e.description)), &mut out);
+        fold("TRANSP:TRANSPARENT", &mut out);
+        for trigger in ALARMS {
+            fold("BEGIN:VALARM", &mut out);
+            fold("ACTION:DISPLAY", &mut out);
+            fold(&format!("DESCRIPTION:{}", escape(&e.summary)), &mut out);
+            fold(&format!("TRIGGER:{}", trigger), &mut out);
+            fold("END:VALARM", &mut out);
+        }
+        fold("END:VEVENT", &mut out);
+    }
+
+    fold("END:VCALENDAR", &mut out);
+    out
+}
+
+pub fn calendar_events(company: &str, calendar: &ComplianceCalendar) -> Vec<IcsEvent> {
+    calendar.obligations.iter()
+        .map(|o| {
+            let mut description = format!("{}\nSection: {}\nIf missed: {}", o.description, o.act_section, fees::late_filing_consequence(&o.form));
+            if let Some(c) = &o.circular {
+                description.push_str(&format!("\nExtended by {}", c.id));
+            }
+            let uid_part: String = format!("{}-{}-{}", company, o.form, o.due_date.format("%Y%m%d"))
+                .chars()
+                .map(|c| if c.is_ascii_alphanumeric() || c == '-' { c } else { '-' })
+                .collect();
+            IcsEvent {
+                uid: format!("{}@pratyaksh.ai", uid_part),
+                date: o.due_date,
+                summary: format!("{}: {}", company, o.form),
+                description,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn escapes_text_values() {
+        assert_eq!(escape("AOC-4; MGT-7, and\nmore \\"), r"AOC-4\; MGT-7\, and\nmore \\");
+        assert_eq!(escape("Acme\r\nEND:VEVENT\rBEGIN:VEVENT"), r"Acme\nEND:VEVENT\nBEGIN:VEVENT");
+    }
+
+    // A company name carrying CRLF stays on its SUMMARY line
+    #[test]
+    fn carriage_returns_cannot_inject_lines() {
+        let event = IcsEvent {
+            uid: "c1@pratyaksh.ai".to_string(),
+            date: NaiveDate::from_ymd_opt(2024, 10, 30).unwrap(),
+            summary: "Acme\r\nATTENDEE:mailto:x@example.com".to_string(),
+            description: "Line one\rLine two".to_string(),
+        };
+        let ics = render("Acme", &[event], Utc.with_ymd_and_hms(2024, 9, 1, 0, 0, 0).unwrap());
+        assert!(!ics.replace("\r\n", "").contains('\r'));
+        assert!(ics.lines().all(|l| !l.starts_with("ATTENDEE")));
+        assert!(ics.contains(r"SUMMARY:Acme\nATTENDEE:mailto:x@example.com"));
+        assert!(ics.contains(r"DESCRIPTION:Line one\nLine two"));
+    }
+
+    // RFC 5545 3.1: no line over 75 octets, continuation lines start with a space
+    #[test]
+    fn folds_long_lines_on_character_boundaries() {
+        let mut out = String::new();
+        fold(&format!("DESCRIPTION:{}", "₹".repeat(40)), &mut out);
+        let lines: Vec<&str> = out.trim_end_matches("\r\n").split("\r\n").collect();
+        assert!(lines.len() > 1);
+        assert!(lines.iter().all(|l| l.len() <= MAX_LINE_OCTETS));
+        assert!(lines[1..].iter().all(|l| l.starts_with(' ')));
+        let unfolded: String = lines.iter().enumerate().map(|(i, l)| if i == 0 { *l } else { &l[1..] }).collect();
+        assert_eq!(unfolded, format!("DESCRIPTION:{}", "₹".repeat(40)));
+    }
+
+    #[test]
+    fn all_day_event_with_exclusive_end() {
+        let event = IcsEvent {
+            uid: "c1-AOC-4@pratyaksh.ai".to_string(),
+            date: NaiveDate::from_ymd_opt(2024, 10, 30).unwrap(),
+            summary: "Acme: AOC-4".to_string(),
+            description: "Financial statements".to_string(),
+        };
+        let ics = render("Acme compliance", &[event], Utc.with_ymd_and_hms(2024, 9, 1, 0, 0, 0).unwrap());
+        assert!(ics.starts_with("BEGIN:VCALENDAR\r\nVERSION:2.0\r\n"));
+        assert!(ics.contains("DTSTART;VALUE=DATE:20241030\r\nDTEND;VALUE=DATE:20241031\r\n"));
+        assert!(ics.contains("DTSTAMP:20240901T000000Z\r\n"));
+        assert_eq!(ics.matches("BEGIN:VALARM").count(), 2);
+        assert!(ics.ends_with("END:VCALENDAR\r\n"));
+    }
+}
@@ -0,0 +1,298 @@
+use chrono::{Datelike, Duration, Months, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+use crate::errors::{check_amount, FieldError};
+use crate::tools::Calculator;
+use crate::trace::RuleTrace;
+
+// Income-tax Act, 1961 - interest under Sec 234A, 234B and 234C, computed per Rule 119A
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug)]
+pub struct TaxPayment {
+    pub date: NaiveDate,
+    pub amount: i64,
+}
+
+// Validated interest computation input
+#[derive(Clone, Debug)]
+pub struct InterestInput {
+    pub fy_end: NaiveDate,
+    pub assessed_tax: i64,
+    pub advance_tax: Vec<TaxPayment>,
+    pub self_assessment_tax: Vec<TaxPayment>,
+    pub return_due_date: NaiveDate,
+    pub filing_date: NaiveDate,
+    pub presumptive: bool,
+}
+
+#[derive(Serialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct InterestLine {
+    pub section: String,
+    pub period: String,
+    pub principal: i64, // Rounded down to ₹100
+    pub months: u32,
+    pub interest: i64,
+}
+
+#[derive(Serialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct IncomeTaxInterest {
+    pub assessment_year: String,
+    pub assessed_tax: i64,
+    pub advance_tax_paid: i64,
+    pub interest_234a: i64,
+    pub interest_234b: i64,
+    pub interest_234c: i64,
+    pub total_interest: i64,
+    pub lines: Vec<InterestLine>,
+    pub explanation: Vec<RuleTrace>,
+}
+
+// Sec 211 instalments: (due day/month, cumulative % due, % that avoids interest, months charged)
+const INSTALMENTS: [(u32, u32, i64, i64, u32); 4] = [
+    (6, 15, 15, 12, 3),
+    (9, 15, 45, 36, 3),
+    (12, 15, 75, 75, 3),
+    (3, 15, 100, 100, 1),
+];
+
+// Sec 208: no advance tax, hence no 234B/234C, when the year's tax is below ₹10000
+const ADVANCE_TAX_THRESHOLD: i64 = 10000;
+
+// Rule 119A(b): amount on which interest runs is rounded down to a multiple of ₹100
+fn round_down_100(amount: i64) -> i64 {
+    amount.max(0) / 100 * 100
+}
+
+// Sec 288B: interest payable rounded to the nearest multiple of ₹10
+fn round_10(amount: i64) -> i64 {
+    amount.saturating_add(5) / 10 * 10
+}
+
+// Rule 119A(a): months from the day after `from` up to `to`, a part of a month counting as a full month.
+// Counted from the day after: stepping from a 30th would keep day 30 and charge one month too many.
+pub fn months_or_part(from: NaiveDate, to: NaiveDate) -> u32 {
+    let start = from + Duration::days(1);
+    let mut months = 0;
+    while start + Months::new(months) <= to {
+        months += 1;
+    }
+    months
+}
+
+fn paid_by(payments: &[TaxPayment], date: NaiveDate) -> i64 {
+    payments.iter().filter(|p| p.date <= date).fold(0, |sum, p| sum.saturating_add(p.amount))
+}
+
+fn line(section: &str, period: String, principal: i64, months: u32) -> InterestLine {
+    InterestLine { section: section.to_string(), period, principal, months, interest: principal.saturating_mul(months as i64) / 100 }
+}
+
+// Sec 234C: 1% per month on the shortfall against each cumulative instalment
+fn interest_234c(input: &InterestInput, lines: &mut Vec<InterestLine>, explanation: &mut Vec<RuleTrace>) {
+    let fy_start_year = input.fy_end.year() - 1;
+    let instalments: Vec<_> = if input.presumptive { INSTALMENTS[3..].to_vec() } else { INSTALMENTS.to_vec() };
+
+    for (month, day, due_pct, safe_pct, months) in instalments {
+        let year = if month <= 3 { fy_start_year + 1 } else { fy_start_year };
+        let due_date = NaiveDate::from_ymd_opt(year, month, day).unwrap();
+        let paid = paid_by(&input.advance_tax, due_date);
+        let shortfall = if paid.saturating_mul(100) < input.assessed_tax.saturating_mul(safe_pct) {
+            round_down_100((input.assessed_tax.saturating_mul(due_pct) / 100).saturating_sub(paid))
+        } else {
+            0
+        };
+        let outcome = if shortfall > 0 { format!("Shortfall ₹{} x 1% x {} month(s)", shortfall, months) } else { "No interest".to_string() };
+        explanation.push(RuleTrace::new("234C instalment", format!("{}% paid by {} ({}% avoids interest)", due_pct, due_date, safe_pct), outcome, "Sec 234C(1)")
+            .input("assessed_tax", input.assessed_tax)
+            .input("paid_by_due_date", paid));
+        if shortfall > 0 {
+            lines.push(line("234C", format!("Instalment due {}", due_date), shortfall, months));
+        }
+    }
+}
+
+// Sec 234B: 1% per month from 1 April of the AY while advance tax is below 90% of assessed tax
+fn interest_234b(input: &InterestInput, lines: &mut Vec<InterestLine>, explanation: &mut Vec<RuleTrace>) {
+    let advance = paid_by(&input.advance_tax, input.fy_end);
+    let applies = advance.saturating_mul(10) < input.assessed_tax.saturating_mul(9);
+    explanation.push(RuleTrace::new("234B default", "Advance tax paid below 90% of assessed tax", if applies { "Interest applies" } else { "No interest" }, "Sec 234B(1)")
+        .input("assessed_tax", input.assessed_tax)
+        .input("advance_tax_paid", advance));
+    if !applies {
+        return;
+    }
+
+    // Sec 234B(2): each self-assessment payment reduces the amount for the months after it
+    let mut shortfall = input.assessed_tax.saturating_sub(advance);
+    let mut start_month = 1;
+    let mut start_date = input.fy_end;
+    let mut payments: Vec<_> = input.self_assessment_tax.iter().filter(|p| p.date > input.fy_end && p.date <= input.filing_date).collect();
+    payments.sort_by_key(|p| p.date);
+
+    for p in payments {
+        let month = months_or_part(input.fy_end, p.date);
+        if month >= start_month && shortfall > 0 {
+            lines.push(line("234B", format!("{} to {}", start_date + Duration::days(1), p.date), round_down_100(shortfall), month - start_month + 1));
+        }
+        shortfall = shortfall.saturating_sub(p.amount);
+        start_month = month + 1;
+        start_date = p.date;
+    }
+    let end_month = months_or_part(input.fy_end, input.filing_date);
+    if shortfall > 0 && end_month >= start_month {
+        lines.push(line("234B", format!("{} to {}", start_date + Duration::days(1), input.filing_date), round_down_100(shortfall), end_month - start_month + 1));
+    }
+}
+
+// Sec 234A: 1% per month after the return due date on tax still unpaid by that date
+fn interest_234a(input: &InterestInput, lines: &mut Vec<InterestLine>, explanation: &mut Vec<RuleTrace>) {
+    let months = months_or_part(input.return_due_date, input.filing_date);
+    let unpaid = input.assessed_tax
+        .saturating_sub(paid_by(&input.advance_tax, input.fy_end))
+        .saturating_sub(paid_by(&input.self_assessment_tax, input.return_due_date));
+    let principal = round_down_100(unpaid);
+    let outcome = if months > 0 && principal > 0 { format!("₹{} x 1% x {} month(s)", principal, months) } else { "No interest".to_string() };
+    explanation.push(RuleTrace::new("234A late return", format!("Return filed after {}", input.return_due_date), outcome, "Sec 234A(1)")
+        .input("return_filing_date", input.filing_date)
+        .input("tax_unpaid_on_due_date", unpaid.max(0)));
+    if months > 0 && principal > 0 {
+        lines.push(line("234A", format!("{} to {}", input.return_due_date + Duration::days(1), input.filing_date), principal, months));
+    }
+}
+
+// Amounts beyond MAX_AMOUNT are rejected; the arithmetic below also saturates so unchecked callers cannot panic
+impl Calculator for InterestInput {
+    type Output = IncomeTaxInterest;
+
+    fn check(&self) -> Vec<FieldError> {
+        let mut errors = Vec::new();
+        check_amount("assessed_tax", self.assessed_tax, &mut errors);
+        for (field, payments) in [("advance_tax", &self.advance_tax), ("self_assessment_tax", &self.self_assessment_tax)] {
+            for (i, p) in payments.iter().enumerate() {
+                check_amount(&format!("{}[{}].amount", field, i), p.amount, &mut errors);
+            }
+        }
+        errors
+    }
+
+    fn evaluate(&self) -> IncomeTaxInterest {
+        compute_interest(self)
+    }
+}
+
+pub fn compute_interest(input: &InterestInput) -> IncomeTaxInterest {
+    let mut lines = Vec::new();
+    let mut explanation = Vec::new();
+
+    interest_234a(input, &mut lines, &mut explanation);
+    if input.assessed_tax >= ADVANCE_TAX_THRESHOLD {
+        interest_234b(input, &mut lines, &mut explanation);
+        interest_234c(input, &mut lines, &mut explanation);
+    } else {
+        explanation.push(RuleTrace::new("Advance tax liability", format!("Tax of ₹{} or more", ADVANCE_TAX_THRESHOLD), "No advance tax due; 234B and 234C do not apply", "Sec 208")
+            .input("assessed_tax", input.assessed_tax));
+    }
+
+    let total = |section: &str| round_10(lines.iter().filter(|l| l.section == section).fold(0, |sum, l| sum.saturating_add(l.interest)));
+    let (a, b, c) = (total("234A"), total("234B"), total("234C"));
+    let total_interest = a.saturating_add(b).saturating_add(c);
+    explanation.push(RuleTrace::new("Rounding", "Principal down to ₹100 (Rule 119A); interest to nearest ₹10 (Sec 288B)", format!("₹{}", total_interest), "Rule 119A, Sec 288B"));
+
+    let ay = input.fy_end.year();
+    IncomeTaxInterest {
+        assessment_year: format!("{}-{:02}", ay, (ay + 1) % 100),
+        assessed_tax: input.assessed_tax,
+        advance_tax_paid: paid_by(&input.advance_tax, input.fy_end),
+        interest_234a: a,
+        interest_234b: b,
+        interest_234c: c,
+        total_interest,
+        lines,
+        explanation,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn d(y: i32, m: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, day).unwrap()
+    }
+
+    fn input(assessed_tax: i64, advance_tax: Vec<TaxPayment>, filing_date: NaiveDate) -> InterestInput {
+        InterestInput {
+            fy_end: d(2024, 3, 31),
+            assessed_tax,
+            advance_tax,
+            self_assessment_tax: Vec::new(),
+            return_due_date: d(2024, 7, 31),
+            filing_date,
+            presumptive: false,
+        }
+    }
+
+    #[test]
+    fn part_month_counts_from_day_after_due_date() {
+        assert_eq!(months_or_part(d(2024, 11, 30), d(2024, 12, 31)), 1);
+        assert_eq!(months_or_part(d(2024, 9, 30), d(2024, 10, 31)), 1);
+        assert_eq!(months_or_part(d(2024, 7, 31), d(2024, 7, 31)), 0);
+        assert_eq!(months_or_part(d(2024, 7, 31), d(2024, 8, 1)), 1);
+        assert_eq!(months_or_part(d(2024, 7, 31), d(2024, 11, 15)), 4);
+    }
+
+    #[test]
+    fn section_234b_runs_from_first_april() {
+        // 1 April to 31 July is four whole months; 1 August starts a fifth
+        assert_eq!(months_or_part(d(2024, 3, 31), d(2024, 7, 31)), 4);
+        assert_eq!(months_or_part(d(2024, 3, 31), d(2024, 8, 1)), 5);
+    }
+
+    // No advance tax on ₹1,00,000, return due 31 July filed 15 November:
+    // 234A 4 months (Aug-Nov) = ₹4,000; 234B 8 months (Apr-Nov) = ₹8,000;
+    // 234C 450 + 1,350 + 2,250 + 1,000 = ₹5,050
+    #[test]
+    fn no_advance_tax_late_return() {
+        let r = compute_interest(&input(100000, Vec::new(), d(2024, 11, 15)));
+        assert_eq!(r.assessment_year, "2024-25");
+        assert_eq!(r.interest_234a, 4000);
+        assert_eq!(r.interest_234b, 8000);
+        assert_eq!(r.interest_234c, 5050);
+        assert_eq!(r.total_interest, 17050);
+    }
+
+    // Return filed by the due date with every instalment paid on time: no interest at all
+    #[test]
+    fn instalments_paid_on_time() {
+        let advance = vec![
+            TaxPayment { date: d(2023, 6, 15), amount: 15000 },
+            TaxPayment { date: d(2023, 9, 15), amount: 30000 },
+            TaxPayment { date: d(2023, 12, 15), amount: 30000 },
+            TaxPayment { date: d(2024, 3, 15), amount: 25000 },
+        ];
+        let r = compute_interest(&input(100000, advance, d(2024, 7, 31)));
+        assert_eq!(r.total_interest, 0);
+    }
+
+    // Sec 208: below ₹10,000 only 234A can apply
+    #[test]
+    fn small_liability_skips_234b_and_234c() {
+        let r = compute_interest(&input(9000, Vec::new(), d(2024, 9, 30)));
+        assert_eq!(r.interest_234a, 180);
+        assert_eq!(r.interest_234b, 0);
+        assert_eq!(r.interest_234c, 0);
+    }
+
+    // Amounts past MAX_AMOUNT fail the check, and the computation itself saturates rather than panicking
+    #[test]
+    fn oversized_amounts_are_rejected_without_overflow() {
+        let huge = input(100_000_000_000_000_000, vec![TaxPayment { date: d(2023, 6, 15), amount: i64::MAX }], d(2024, 11, 15));
+        let fields: Vec<_> = huge.check().iter().map(|e| e.field().to_string()).collect();
+        assert_eq!(fields, ["assessed_tax", "advance_tax[0].amount"]);
+        compute_interest(&huge);
+        compute_interest(&input(i64::MAX, Vec::new(), d(2024, 11, 15)));
+        assert!(input(100000, Vec::new(), d(2024, 11, 15)).check().is_empty());
+    }
+}
@@ -0,0 +1,19 @@
+// Compliance rules shared by the backend, the desktop client and the admin console.
+// Every function here is pure: typed inputs in, typed results (with the rules applied) out.
+// Parsing, storage and presentation stay in the binaries.
+
+pub mod calendar;
+pub mod circulars;
+pub mod errors;
+pub mod fees;
+pub mod gst;
+pub mod ics;
+pub mod income_tax;
+pub mod license;
+pub mod llp;
+pub mod money;
+pub mod risk;
+pub mod tds;
+pub mod tools;
+pub mod trace;
+pub mod upi;
@@ -0,0 +1,158 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+// Offline desktop licenses. The backend signs the claims with its Ed25519 key when a payment is approved;
+// the desktop checks the signature against the embedded public key, with no network access.
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct LicenseClaims {
+    pub license_id: String,
+    pub user_id: String,
+    pub email: String,
+    pub plan: String,
+    pub device_id: String, // Fingerprint shown on the desktop activation screen
+    pub issued_at: DateTime<Utc>,
+    pub expires_on: NaiveDate, // Last day of full access; read-only afterwards
+}
+
+// license.json: the claims exactly as signed, and the hex signature over those bytes.
+// Verifying the payload string rather than re-serialized claims keeps old licenses valid as fields are added.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct LicenseFile {
+    pub payload: String,
+    pub signature: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum LicenseError {
+    Malformed(String),
+    BadSignature,
+    WrongDevice { licensed: String },
+}
+
+impl fmt::Display for LicenseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LicenseError::Malformed(detail) => write!(f, "not a Pratyaksh license file ({})", detail),
+            LicenseError::BadSignature => write!(f, "the license signature does not match"),
+            LicenseError::WrongDevice { licensed } => write!(f, "the license is for device {}", licensed),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LicenseStatus {
+    Active,
+    Expired, // Existing data stays readable; nothing new can be saved
+}
+
+impl LicenseClaims {
+    pub fn status(&self, today: NaiveDate) -> LicenseStatus {
+        if today > self.expires_on { LicenseStatus::Expired } else { LicenseStatus::Active }
+    }
+}
+
+// Device ids are compared as typed into the payment form: case and surrounding spaces do not matter
+pub fn normalize_device_id(raw: &str) -> String {
+    raw.trim().to_ascii_uppercase()
+}
+
+pub fn public_key_hex(key: &VerifyingKey) -> String {
+    hex::encode(key.as_bytes())
+}
+
+pub fn parse_public_key(raw: &str) -> Option<VerifyingKey> {
+    let bytes: [u8; 32] = hex::decode(raw.trim()).ok()?.try_into().ok()?;
+    VerifyingKey::from_bytes(&bytes).ok()
+}
+
+pub fn sign(claims: &LicenseClaims, key: &SigningKey) -> LicenseFile {
+    let payload = serde_json::to_string(claims).expect("license claims serialize");
+    let signature = key.sign(payload.as_bytes());
+    LicenseFile { payload, signature: hex::encode(signature.to_bytes()) }
+}
+
+// Checks the signature, then the device binding; expiry is left to the caller via LicenseClaims::status
+pub fn verify(raw: &str, key: &VerifyingKey, device_id: &str) -> Result<LicenseClaims, LicenseError> {
+    let file: LicenseFile = serde_json::from_str(raw).map_err(|e| LicenseError::Malformed(e.to_string()))?;
+    let signature: [u8; 64] = hex::decode(&file.signature).ok()
+        .and_then(|b| b.try_into().ok())
+        .ok_or_else(|| LicenseError::Malformed("signature is not 64 hex-encoded bytes".to_string()))?;
+    key.verify(file.payload.as_bytes(), &Signature::from_bytes(&signature)).map_err(|_| LicenseError::BadSignature)?;
+
+    let claims: LicenseClaims = serde_json::from_str(&file.payload).map_err(|e| LicenseError::Malformed(e.to_string()))?;
+    if normalize_device_id(&claims.device_id) != normalize_device_id(device_id) {
+        return Err(LicenseError::WrongDevice { licensed: claims.device_id });
+    }
+    Ok(claims)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn key() -> SigningKey {
+        SigningKey::from_bytes(&[7u8; 32])
+    }
+
+    fn claims() -> LicenseClaims {
+        LicenseClaims {
+            license_id: "lic_test".to_string(),
+            user_id: "u1".to_string(),
+            email: "ca@firm.in".to_string(),
+            plan: "scale".to_string(),
+            device_id: "AB12-CD34-EF56-7890".to_string(),
+            issued_at: Utc.with_ymd_and_hms(2024, 10, 1, 9, 0, 0).unwrap(),
+            expires_on: NaiveDate::from_ymd_opt(2024, 11, 1).unwrap(),
+        }
+    }
+
+    fn signed() -> String {
+        serde_json::to_string(&sign(&claims(), &key())).unwrap()
+    }
+
+    #[test]
+    fn verifies_on_licensed_device() {
+        let verified = verify(&signed(), &key().verifying_key(), " ab12-cd34-ef56-7890 ").unwrap();
+        assert_eq!(verified.license_id, "lic_test");
+        assert_eq!(verified.status(NaiveDate::from_ymd_opt(2024, 11, 1).unwrap()), LicenseStatus::Active);
+        assert_eq!(verified.status(NaiveDate::from_ymd_opt(2024, 11, 2).unwrap()), LicenseStatus::Expired);
+    }
+
+    #[test]
+    fn rejects_other_device() {
+        let err = verify(&signed(), &key().verifying_key(), "0000-0000-0000-0000").unwrap_err();
+        assert_eq!(err, LicenseError::WrongDevice { licensed: "AB12-CD34-EF56-7890".to_string() });
+    }
+
+    #[test]
+    fn rejects_edited_payload() {
+        let tampered = signed().replace("2024-11-01", "2034-11-01");
+        assert_eq!(verify(&tampered, &key().verifying_key(), "AB12-CD34-EF56-7890").unwrap_err(), LicenseError::BadSignature);
+    }
+
+    #[test]
+    fn rejects_other_signing_key() {
+        let other = SigningKey::from_bytes(&[8u8; 32]).verifying_key();
+        assert_eq!(verify(&signed(), &other, "AB12-CD34-EF56-7890").unwrap_err(), LicenseError::BadSignature);
+    }
+
+    #[test]
+    fn rejects_malformed_files() {
+        assert!(matches!(verify("not json", &key().verifying_key(), "x"), Err(LicenseError::Malformed(_))));
+        let short = r#"{"payload":"{}","signature":"abcd"}"#;
+        assert!(matches!(verify(short, &key().verifying_key(), "x"), Err(LicenseError::Malformed(_))));
+    }
+
+    #[test]
+    fn public_key_round_trips() {
+        let public = key().verifying_key();
+        assert_eq!(parse_public_key(&public_key_hex(&public)), Some(public));
+        assert_eq!(parse_public_key("zz"), None);
+    }
+}
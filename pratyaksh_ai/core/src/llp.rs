@@ -0,0 +1,119 @@
+use chrono::{Duration, Months, NaiveDate};
+
+use crate::calendar::{entry, next_fixed_date, CalendarEntry};
+use crate::trace::RuleTrace;
+
+// Limited Liability Partnership Act, 2008 r/w LLP Rules, 2009
+
+pub const LLP_FORMS: &[&str] = &["Form 8", "Form 11"];
+
+// LLP (Amendment) Rules, 2022: ₹100 per day of delay on Form 8 and Form 11, without any cap
+pub const LLP_ADDITIONAL_FEE_PER_DAY: i64 = 100;
+
+pub fn is_llp_form(form: &str) -> bool {
+    LLP_FORMS.contains(&form)
+}
+
+// Filing fee by contribution slab (Annexure A, Form 8 / Form 11)
+pub fn normal_fee(contribution: Option<i64>) -> i64 {
+    match contribution.unwrap_or(0) {
+        c if c <= 100000 => 50,
+        c if c <= 500000 => 100,
+        c if c <= 1000000 => 150,
+        c if c <= 2500000 => 200,
+        c if c <= 10000000 => 400,
+        _ => 600,
+    }
+}
+
+pub fn additional_fee(days_late: i64) -> i64 {
+    LLP_ADDITIONAL_FEE_PER_DAY * days_late.max(0)
+}
+
+// Sec 34(2) r/w Rule 24: Form 8 within 30 days from the end of six months of the FY
+pub fn statement_of_account_due(fy_end: NaiveDate) -> NaiveDate {
+    fy_end + Months::new(6) + Duration::days(30)
+}
+
+// Sec 35(1) r/w Rule 25: Form 11 within 60 days of FY close
+pub fn annual_return_due(fy_end: NaiveDate) -> NaiveDate {
+    fy_end + Duration::days(60)
+}
+
+// Statutory due date and section for an LLP form
+pub fn due_date(form: &str, fy_end: NaiveDate) -> (NaiveDate, &'static str) {
+    match form {
+        "Form 8" => (statement_of_account_due(fy_end), "Sec 34(2), LLP Act"),
+        _ => (annual_return_due(fy_end), "Sec 35(1), LLP Act"),
+    }
+}
+
+pub fn due_date_trace(form: &str, fy_end: NaiveDate) -> RuleTrace {
+    let (due, section) = due_date(form, fy_end);
+    let (rule, threshold) = match form {
+        "Form 8" => ("Form 8 due date", "30 days from the end of six months of the FY"),
+        _ => ("Form 11 due date", "60 days of FY close"),
+    };
+    RuleTrace::new(rule, threshold, format!("{} due by {}", form, due), section)
+        .input("fy_end_date", fy_end)
+        .input("company_type", "llp")
+}
+
+pub fn normal_fee_trace(contribution: Option<i64>) -> RuleTrace {
+    let fee = normal_fee(contribution);
+    RuleTrace::new("Normal filing fee", format!("Contribution slab charging ₹{}", fee), format!("₹{}", fee), "Annexure A, LLP Rules, 2009")
+        .input("contribution", contribution.unwrap_or(0))
+}
+
+// LLP ANNUAL FILINGS
+pub fn obligations(fy_end: NaiveDate) -> Vec<CalendarEntry> {
+    vec![
+        entry("Form 11", "Annual return of LLP", annual_return_due(fy_end), "Sec 35(1) r/w Rule 25"),
+        entry("Form 8", "Statement of account and solvency", statement_of_account_due(fy_end), "Sec 34(2) r/w Rule 24"),
+        // Designated partners hold a DIN and file the same KYC as company directors
+        entry("DIR-3 KYC", "KYC of every designated partner holding a DIN", next_fixed_date(fy_end, 9, 30).expect("30 September exists"), "Sec 7, LLP Act r/w Rule 12A"),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn d(y: i32, m: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, day).unwrap()
+    }
+
+    // Annexure A: up to ₹1 lakh ₹50; ₹1-5 lakh ₹100; ₹5-10 lakh ₹150; ₹10-25 lakh ₹200; ₹25 lakh-1 crore ₹400; above ₹600
+    #[test]
+    fn contribution_slab_boundaries() {
+        assert_eq!(normal_fee(None), 50);
+        assert_eq!(normal_fee(Some(100000)), 50);
+        assert_eq!(normal_fee(Some(100001)), 100);
+        assert_eq!(normal_fee(Some(500000)), 100);
+        assert_eq!(normal_fee(Some(1000000)), 150);
+        assert_eq!(normal_fee(Some(2500000)), 200);
+        assert_eq!(normal_fee(Some(10000000)), 400);
+        assert_eq!(normal_fee(Some(10000001)), 600);
+    }
+
+    #[test]
+    fn additional_fee_is_uncapped_per_day() {
+        assert_eq!(additional_fee(0), 0);
+        assert_eq!(additional_fee(-5), 0);
+        assert_eq!(additional_fee(1), 100);
+        assert_eq!(additional_fee(400), 40000);
+    }
+
+    // FY ending 31 March 2024: Form 11 by 30 May, Form 8 by 30 October
+    #[test]
+    fn form_8_and_form_11_due_dates() {
+        assert_eq!(due_date("Form 11", d(2024, 3, 31)), (d(2024, 5, 30), "Sec 35(1), LLP Act"));
+        assert_eq!(due_date("Form 8", d(2024, 3, 31)), (d(2024, 10, 30), "Sec 34(2), LLP Act"));
+    }
+
+    #[test]
+    fn only_form_8_and_11_are_llp_forms() {
+        assert!(is_llp_form("Form 8") && is_llp_form("Form 11"));
+        assert!(!is_llp_form("AOC-4") && !is_llp_form("form 8"));
+    }
+}
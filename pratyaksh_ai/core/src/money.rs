@@ -0,0 +1,85 @@
+// Rupee amounts as the apps display and store them: "₹19,999", "₹1,00,000.50", "Rs. 499".
+// Parsed into whole paise so totals never pick up floating-point drift.
+
+// Accepts an optional ₹ / Rs / INR prefix, Indian or Western digit grouping and up to two decimals
+pub fn parse_inr(raw: &str) -> Option<i64> {
+    let mut s = raw.trim();
+    for prefix in ["₹", "INR", "Rs.", "Rs"] {
+        if let Some(rest) = s.strip_prefix(prefix) {
+            s = rest.trim_start();
+            break;
+        }
+    }
+    let s: String = s.chars().filter(|c| *c != ',').collect();
+    let (whole, fraction) = match s.split_once('.') {
+        Some((w, f)) => (w, f),
+        None => (s.as_str(), ""),
+    };
+    if whole.is_empty() || !whole.chars().all(|c| c.is_ascii_digit()) || fraction.len() > 2 || !fraction.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    let rupees: i64 = whole.parse().ok()?;
+    let paise: i64 = format!("{:0<2}", fraction).parse().ok()?;
+    rupees.checked_mul(100)?.checked_add(paise)
+}
+
+// Indian digit grouping: last three digits, then pairs (₹1,23,45,678.90)
+pub fn format_inr(paise: i64) -> String {
+    let sign = if paise < 0 { "-" } else { "" };
+    let paise = paise.unsigned_abs();
+    let digits = (paise / 100).to_string();
+    let (head, tail) = digits.split_at(digits.len().saturating_sub(3));
+    let mut grouped = String::new();
+    for (i, ch) in head.chars().enumerate() {
+        if i > 0 && (head.len() - i) % 2 == 0 {
+            grouped.push(',');
+        }
+        grouped.push(ch);
+    }
+    if !head.is_empty() {
+        grouped.push(',');
+    }
+    grouped.push_str(tail);
+    format!("{}₹{}.{:02}", sign, grouped, paise % 100)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_display_strings() {
+        assert_eq!(parse_inr("₹19,999"), Some(1999900));
+        assert_eq!(parse_inr("₹1,00,000.50"), Some(10000050));
+        assert_eq!(parse_inr("Rs. 499"), Some(49900));
+        assert_eq!(parse_inr("INR 2,399.5"), Some(239950));
+        assert_eq!(parse_inr(" 1,234,567.89 "), Some(123456789));
+    }
+
+    #[test]
+    fn rejects_malformed_amounts() {
+        assert_eq!(parse_inr(""), None);
+        assert_eq!(parse_inr("₹"), None);
+        assert_eq!(parse_inr("12.345"), None);
+        assert_eq!(parse_inr("-500"), None);
+        assert_eq!(parse_inr("five hundred"), None);
+        assert_eq!(parse_inr("99999999999999999999"), None);
+    }
+
+    #[test]
+    fn formats_with_indian_grouping() {
+        assert_eq!(format_inr(0), "₹0.00");
+        assert_eq!(format_inr(99900), "₹999.00");
+        assert_eq!(format_inr(1999900), "₹19,999.00");
+        assert_eq!(format_inr(10000050), "₹1,00,000.50");
+        assert_eq!(format_inr(12345678990), "₹12,34,56,789.90");
+        assert_eq!(format_inr(-250000), "-₹2,500.00");
+    }
+
+    #[test]
+    fn round_trips() {
+        for paise in [0, 5, 99900, 10000050, 12345678990] {
+            assert_eq!(parse_inr(&format_inr(paise)), Some(paise));
+        }
+    }
+}
@@ -0,0 +1,265 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+use crate::calendar::{self, CompanyProfile};
+use crate::circulars::{CircularCitation, CircularRegistry};
+use crate::errors::FieldError;
+use crate::fees::{self, FeeProjection};
+use crate::llp;
+use crate::trace::RuleTrace;
+
+// ROC filing risk: how late a form is, what it costs, and how the deadline was reached
+
+// Forms the risk engine can evaluate; anything else is rejected rather than guessed
+pub const ACCEPTED_FORMS: &[&str] = &["AOC-4", "AOC-4 CFS", "AOC-4 XBRL", "MGT-7", "MGT-7A", "Form 8", "Form 11"];
+
+// A form filed on the MCA portal, as recorded against a company
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct Filing {
+    pub id: i64,
+    pub cin: String,
+    pub form: String,
+    pub fy_end_date: NaiveDate,
+    pub srn: String,
+    pub filing_date: NaiveDate,
+}
+
+// Validated input the risk engine works on
+#[derive(Clone, Debug)]
+pub struct RiskInput {
+    pub fy_end: NaiveDate,
+    pub form_type: String,
+    pub as_of: NaiveDate,
+    pub profile: CompanyProfile,
+    pub share_capital: Option<i64>, // Nominal share capital; picks the normal fee slab the slab-charged forms multiply
+    pub filing: Option<Filing>,     // Filing recorded in the store for this form and FY
+}
+
+#[derive(Serialize, Clone)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ComplianceRisk {
+    pub form_type: String,
+    pub agm_due_date: Option<NaiveDate>, // None for OPCs, which hold no AGM
+    pub filing_due_date: NaiveDate,
+    pub penalty_estimate: i64,
+    pub penalty_projection: Vec<FeeProjection>,
+    pub risk_level: String,
+    pub act_section: String,
+    pub as_of: NaiveDate,
+    pub circulars: Vec<CircularCitation>, // MCA circulars that moved the deadline or waived the fee
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub filing: Option<Filing>, // Stored filing that settled the form, if any
+    pub explanation: Vec<RuleTrace>,      // Every rule evaluated, in order
+}
+
+// Negative capital would fall into the lowest slab and understate the fee
+pub fn check_share_capital(field: &str, share_capital: Option<i64>, errors: &mut Vec<FieldError>) {
+    if share_capital.is_some_and(|c| c < 0) {
+        errors.push(FieldError::OutOfRange { field: field.to_string(), reason: "must not be negative".to_string() });
+    }
+}
+
+pub fn check_form(field: &str, form_type: &str, profile: &CompanyProfile, errors: &mut Vec<FieldError>) {
+    if !ACCEPTED_FORMS.contains(&form_type) {
+        errors.push(FieldError::UnsupportedForm { field: field.to_string(), value: form_type.to_string(), accepted: ACCEPTED_FORMS });
+    } else {
+        profile.check_form(field, form_type, errors);
+    }
+}
+
+// REAL LOGIC ENGINE
+pub fn calculate_risk(input: &RiskInput, registry: &CircularRegistry) -> ComplianceRisk {
+    // Sec 96: AGM deadline depends on company type, first FY and any ROC extension
+    let agm_deadline = calendar::agm_due_date(input.fy_end, &input.profile);
+
+    let mut explanation = vec![calendar::agm_trace(input.fy_end, &input.profile)];
+
+    // Sec 137 vs Sec 92; LLPs under Sec 34 / Sec 35 of the LLP Act
+    let (statutory_deadline, section) = match input.form_type.as_str() {
+        form if llp::is_llp_form(form) => {
+            explanation.push(llp::due_date_trace(form, input.fy_end));
+            llp::due_date(form, input.fy_end)
+        }
+        "MGT-7" | "MGT-7A" => {
+            explanation.push(calendar::annual_return_trace(input.fy_end, &input.profile));
+            (calendar::annual_return_due(input.fy_end, &input.profile), "Sec 92")
+        }
+        _ => {
+            explanation.push(calendar::financial_statements_trace(input.fy_end, &input.profile));
+            (calendar::financial_statements_due(input.fy_end, &input.profile), "Sec 137")
+        }
+    };
+
+    // MCA General Circulars may extend the deadline or waive the additional fee
+    let (filing_deadline, extension) = registry.extended_due_date(&input.form_type, input.fy_end, statutory_deadline);
+    if let Some(c) = &extension {
+        explanation.push(RuleTrace::new("MCA circular extension", "Due date extended by circular", &c.effect, format!("{} ({})", c.id, c.title))
+            .input("form", &input.form_type)
+            .input("statutory_due_date", statutory_deadline));
+    }
+    let mut circulars: Vec<CircularCitation> = extension.into_iter().collect();
+
+    // A filing recorded on or before as_of stops the delay at its filing date
+    let filed = input.filing.as_ref().filter(|f| f.filing_date <= input.as_of);
+    let delay_end = filed.map_or(input.as_of, |f| f.filing_date);
+    let days_late = (delay_end - filing_deadline).num_days().max(0);
+    let threshold = match filed {
+        Some(_) => format!("Filed after {}", filing_deadline),
+        None => format!("Unfiled after {}", filing_deadline),
+    };
+    explanation.push(RuleTrace::new("Filing delay", threshold, format!("{} days late", days_late), section)
+        .input("as_of", input.as_of)
+        .input("filing_due_date", filing_deadline));
+
+    let normal_fee = fees::normal_fee(input.share_capital);
+    let mut penalty = 0;
+    let mut risk = "SAFE".to_string();
+
+    if let Some(f) = filed {
+        // The additional fee was paid with the form, so nothing is outstanding
+        let waiver = registry.fee_waiver(&input.form_type, filing_deadline, f.filing_date);
+        let paid = match &waiver {
+            Some(_) => 0,
+            None => fees::additional_fee(&input.form_type, normal_fee, days_late),
+        };
+        circulars.extend(waiver);
+        explanation.push(RuleTrace::new("Filing status", "Filed with an SRN on or before as_of", format!("Filed; additional fee of ₹{} paid with the form", paid), section)
+            .input("srn", &f.srn)
+            .input("filing_date", f.filing_date));
+    } else if days_late > 0 {
+        // Companies (Registration Offices and Fees) Rules, 2014
+        let waiver = registry.fee_waiver(&input.form_type, filing_deadline, input.as_of);
+        explanation.push(fees::additional_fee_trace(&input.form_type, normal_fee, days_late, waiver.as_ref()));
+        match waiver {
+            Some(waiver) => circulars.push(waiver),
+            None => penalty = fees::additional_fee(&input.form_type, normal_fee, days_late),
+        }
+        risk = if days_late > 60 { "CRITICAL" } else { "MODERATE" }.to_string();
+    }
+    explanation.push(RuleTrace::new("Risk level", "SAFE if on time; MODERATE up to 60 days late; CRITICAL beyond 60 days", &risk, section)
+        .input("days_late", days_late));
+
+    ComplianceRisk {
+        form_type: input.form_type.clone(),
+        agm_due_date: agm_deadline,
+        filing_due_date: filing_deadline,
+        penalty_estimate: penalty,
+        penalty_projection: match filed {
+            Some(_) => Vec::new(),
+            None => fees::project_additional_fee(&input.form_type, normal_fee, filing_deadline, input.as_of, registry),
+        },
+        risk_level: risk,
+        act_section: section.to_string(),
+        as_of: input.as_of,
+        circulars,
+        filing: filed.cloned(),
+        explanation,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn d(y: i32, m: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, day).unwrap()
+    }
+
+    fn empty_registry() -> CircularRegistry {
+        CircularRegistry { version: "test".to_string(), circulars: Vec::new() }
+    }
+
+    // AOC-4 of a private company for FY 2023-24, due 30 Oct 2024
+    fn aoc4(as_of: NaiveDate) -> RiskInput {
+        RiskInput { fy_end: d(2024, 3, 31), form_type: "AOC-4".to_string(), as_of, profile: CompanyProfile::default(), share_capital: None, filing: None }
+    }
+
+    #[test]
+    fn penalty_and_risk_level_by_delay() {
+        let risk = calculate_risk(&aoc4(d(2024, 10, 30)), &empty_registry());
+        assert_eq!((risk.filing_due_date, risk.penalty_estimate, risk.risk_level.as_str()), (d(2024, 10, 30), 0, "SAFE"));
+
+        let risk = calculate_risk(&aoc4(d(2024, 12, 14)), &empty_registry());
+        assert_eq!((risk.penalty_estimate, risk.risk_level.as_str()), (4500, "MODERATE"));
+
+        let risk = calculate_risk(&aoc4(d(2025, 1, 13)), &empty_registry());
+        assert_eq!((risk.penalty_estimate, risk.risk_level.as_str()), (7500, "CRITICAL"));
+    }
+
+    // 45 days late on 14 Dec 2024: the curve mixes the waiting horizons with the first day of each later Table B slab
+    #[test]
+    fn projection_covers_horizons_and_slab_starts() {
+        let risk = calculate_risk(&aoc4(d(2024, 12, 14)), &empty_registry());
+        let curve: Vec<(NaiveDate, i64, i64)> = risk.penalty_projection.iter().map(|p| (p.date, p.days_late, p.additional_fee)).collect();
+        assert_eq!(&curve[..4], [
+            (d(2024, 12, 30), 61, 6100),
+            (d(2025, 1, 13), 75, 7500),
+            (d(2025, 1, 29), 91, 9100),
+            (d(2025, 2, 12), 105, 10500),
+        ]);
+        assert!(curve.iter().all(|(date, _, _)| *date > d(2024, 12, 14)));
+        assert!(curve.windows(2).all(|w| w[0].0 < w[1].0 && w[0].2 <= w[1].2));
+        assert_eq!(curve.last(), Some(&(d(2025, 9, 10), 315, 31500)));
+    }
+
+    // Before the due date the curve starts at the first day of delay
+    #[test]
+    fn projection_before_due_date_starts_at_first_late_day() {
+        let risk = calculate_risk(&aoc4(d(2024, 10, 1)), &empty_registry());
+        assert_eq!(risk.penalty_estimate, 0);
+        let first = &risk.penalty_projection[0];
+        assert_eq!((first.date, first.days_late, first.additional_fee), (d(2024, 10, 31), 1, 100));
+    }
+
+    // Slab-charged forms multiply the normal fee, so a ₹1 crore company pays 3x the ₹200 slab
+    #[test]
+    fn projection_scales_with_share_capital_slab() {
+        let project = |capital| fees::project_additional_fee("ADT-1", fees::normal_fee(capital), d(2024, 10, 15), d(2024, 10, 15), &empty_registry());
+        let small = project(None);
+        let large = project(Some(10000000));
+        let beyond_180 = |curve: &[FeeProjection]| curve.iter().find(|p| p.days_late > 180).map(|p| p.additional_fee);
+        assert_eq!(beyond_180(&small), Some(2400));
+        assert_eq!(beyond_180(&large), Some(7200));
+    }
+
+    #[test]
+    fn filed_form_has_no_projection() {
+        let filing = Filing { id: 1, cin: "U1".to_string(), form: "AOC-4".to_string(), fy_end_date: d(2024, 3, 31), srn: "F123".to_string(), filing_date: d(2024, 11, 9) };
+        let risk = calculate_risk(&RiskInput { filing: Some(filing), ..aoc4(d(2024, 12, 14)) }, &empty_registry());
+        assert_eq!((risk.penalty_estimate, risk.risk_level.as_str()), (0, "SAFE"));
+        assert!(risk.penalty_projection.is_empty());
+    }
+
+    fn rules(risk: &ComplianceRisk) -> Vec<&str> {
+        risk.explanation.iter().map(|t| t.rule.as_str()).collect()
+    }
+
+    // Every rule evaluated, in the order applied, with the inputs it read
+    #[test]
+    fn explanation_traces_each_rule_in_order() {
+        let risk = calculate_risk(&aoc4(d(2024, 12, 14)), &empty_registry());
+        assert_eq!(rules(&risk), ["AGM deadline", "AOC-4 due date", "Filing delay", "Additional fee", "Risk level"]);
+
+        let delay = &risk.explanation[2];
+        assert_eq!(delay.outcome, "45 days late");
+        assert_eq!(delay.inputs["as_of"], "2024-12-14");
+        assert_eq!(delay.inputs["filing_due_date"], "2024-10-30");
+        assert_eq!(risk.explanation[3].outcome, "₹4500");
+        assert_eq!(risk.explanation[4].inputs["days_late"], "45");
+    }
+
+    // FY 2020-21 AOC-4: GC 22/2021 moved the 30 Oct 2021 due date to 15 Feb 2022, and the trace cites it
+    #[test]
+    fn explanation_cites_circular_extension() {
+        let input = RiskInput { fy_end: d(2021, 3, 31), ..aoc4(d(2022, 2, 20)) };
+        let risk = calculate_risk(&input, &CircularRegistry::bundled());
+        assert_eq!(rules(&risk), ["AGM deadline", "AOC-4 due date", "MCA circular extension", "Filing delay", "Additional fee", "Risk level"]);
+
+        let extension = &risk.explanation[2];
+        assert!(extension.reference.starts_with("GC 22/2021"));
+        assert_eq!(extension.inputs["statutory_due_date"], "2021-10-30");
+        assert_eq!(risk.filing_due_date, d(2022, 2, 15));
+        assert_eq!(risk.circulars.iter().map(|c| c.id.as_str()).collect::<Vec<_>>(), ["GC 22/2021"]);
+    }
+}
@@ -0,0 +1,224 @@
+use chrono::{Datelike, NaiveDate};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use crate::trace::RuleTrace;
+
+// Income-tax Act, 1961 - TDS defaults: interest under Sec 201(1A) and fee under Sec 234E
+
+const LATE_DEDUCTION_RATE: f64 = 1.0; // % per month, Sec 201(1A)(i)
+const LATE_DEPOSIT_RATE: f64 = 1.5;   // % per month, Sec 201(1A)(ii)
+const FEE_234E_PER_DAY: i64 = 200;
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct TdsEntry {
+    pub reference: Option<String>,
+    pub amount: i64,
+    pub payment_date: NaiveDate,
+    pub deduction_date: NaiveDate,
+    pub deposit_date: NaiveDate,
+    pub return_filing_date: NaiveDate,
+}
+
+#[derive(Serialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct TdsEntryDefault {
+    pub reference: Option<String>,
+    pub amount: i64,
+    pub deposit_due_date: NaiveDate,
+    pub late_deduction_months: u32,
+    pub late_deduction_interest: i64,
+    pub late_deposit_months: u32,
+    pub late_deposit_interest: i64,
+}
+
+#[derive(Serialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct TdsStatementFee {
+    pub quarter: String,
+    pub return_due_date: NaiveDate,
+    pub return_filing_date: NaiveDate,
+    pub days_late: i64,
+    pub tds_amount: i64,
+    pub fee_234e: i64, // Capped at the TDS in the statement
+}
+
+#[derive(Serialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct TdsDefaults {
+    pub entries: Vec<TdsEntryDefault>,
+    pub statements: Vec<TdsStatementFee>,
+    pub total_interest: i64,
+    pub total_fee_234e: i64,
+    pub explanation: Vec<RuleTrace>,
+}
+
+// Calendar months from `from` to `to`, both months counted in full (TRACES method for "month or part of a month")
+pub fn months_spanned(from: NaiveDate, to: NaiveDate) -> u32 {
+    if to <= from {
+        return 0;
+    }
+    let span = (to.year() * 12 + to.month() as i32) - (from.year() * 12 + from.month() as i32) + 1;
+    span as u32
+}
+
+// Rule 30(1): 7th of the next month; tax deducted in March by 30 April
+pub fn deposit_due_date(deduction_date: NaiveDate) -> NaiveDate {
+    match deduction_date.month() {
+        3 => NaiveDate::from_ymd_opt(deduction_date.year(), 4, 30).unwrap(),
+        12 => NaiveDate::from_ymd_opt(deduction_date.year() + 1, 1, 7).unwrap(),
+        m => NaiveDate::from_ymd_opt(deduction_date.year(), m + 1, 7).unwrap(),
+    }
+}
+
+// Rule 31A: quarter label and statement due date for a deduction
+pub fn statement_due(deduction_date: NaiveDate) -> (String, NaiveDate) {
+    let y = deduction_date.year();
+    let fy_start = if deduction_date.month() >= 4 { y } else { y - 1 };
+    let label = |q: u32| format!("Q{} FY {}-{:02}", q, fy_start, (fy_start + 1) % 100);
+    match deduction_date.month() {
+        4..=6 => (label(1), NaiveDate::from_ymd_opt(y, 7, 31).unwrap()),
+        7..=9 => (label(2), NaiveDate::from_ymd_opt(y, 10, 31).unwrap()),
+        10..=12 => (label(3), NaiveDate::from_ymd_opt(y + 1, 1, 31).unwrap()),
+        _ => (label(4), NaiveDate::from_ymd_opt(y, 5, 31).unwrap()),
+    }
+}
+
+// The f64 cast saturates, so the totals below must saturate too
+fn interest(amount: i64, rate: f64, months: u32) -> i64 {
+    (amount as f64 * rate / 100.0 * months as f64).round() as i64
+}
+
+pub fn compute_defaults(entries: Vec<TdsEntry>) -> TdsDefaults {
+    let mut explanation = Vec::new();
+    let mut results = Vec::with_capacity(entries.len());
+    // Quarter -> (due date, latest filing date, TDS in the statement)
+    let mut quarters: BTreeMap<String, (NaiveDate, NaiveDate, i64)> = BTreeMap::new();
+
+    for e in entries {
+        let due = deposit_due_date(e.deduction_date);
+
+        // 201(1A)(i): from the date tax was deductible to the date it was actually deducted
+        let ded_months = months_spanned(e.payment_date, e.deduction_date);
+        let ded_interest = interest(e.amount, LATE_DEDUCTION_RATE, ded_months);
+
+        // 201(1A)(ii): from the date of deduction to the date of deposit, once the deposit is late
+        let dep_months = if e.deposit_date > due { months_spanned(e.deduction_date, e.deposit_date) } else { 0 };
+        let dep_interest = interest(e.amount, LATE_DEPOSIT_RATE, dep_months);
+
+        let label = e.reference.clone().unwrap_or_else(|| format!("₹{} deducted {}", e.amount, e.deduction_date));
+        if ded_months > 0 {
+            explanation.push(RuleTrace::new("Late deduction", format!("Deducted after {}", e.payment_date), format!("₹{} x 1% x {} month(s) = ₹{}", e.amount, ded_months, ded_interest), "Sec 201(1A)(i)")
+                .input("entry", &label)
+                .input("deduction_date", e.deduction_date));
+        }
+        if dep_months > 0 {
+            explanation.push(RuleTrace::new("Late deposit", format!("Deposited after {}", due), format!("₹{} x 1.5% x {} month(s) = ₹{}", e.amount, dep_months, dep_interest), "Sec 201(1A)(ii) r/w Rule 30")
+                .input("entry", &label)
+                .input("deduction_date", e.deduction_date)
+                .input("deposit_date", e.deposit_date));
+        }
+
+        let (quarter, return_due) = statement_due(e.deduction_date);
+        let q = quarters.entry(quarter).or_insert((return_due, e.return_filing_date, 0));
+        q.1 = q.1.max(e.return_filing_date);
+        q.2 = q.2.saturating_add(e.amount);
+
+        results.push(TdsEntryDefault {
+            reference: e.reference,
+            amount: e.amount,
+            deposit_due_date: due,
+            late_deduction_months: ded_months,
+            late_deduction_interest: ded_interest,
+            late_deposit_months: dep_months,
+            late_deposit_interest: dep_interest,
+        });
+    }
+
+    let statements: Vec<TdsStatementFee> = quarters.into_iter()
+        .map(|(quarter, (return_due, filed, tds))| {
+            let days_late = (filed - return_due).num_days().max(0);
+            let fee = (FEE_234E_PER_DAY * days_late).min(tds);
+            if days_late > 0 {
+                explanation.push(RuleTrace::new("Late TDS statement", format!("₹{}/day after {}, capped at TDS of ₹{}", FEE_234E_PER_DAY, return_due, tds), format!("₹{}", fee), "Sec 234E r/w Rule 31A")
+                    .input("quarter", &quarter)
+                    .input("return_filing_date", filed)
+                    .input("days_late", days_late));
+            }
+            TdsStatementFee { quarter, return_due_date: return_due, return_filing_date: filed, days_late, tds_amount: tds, fee_234e: fee }
+        })
+        .collect();
+
+    TdsDefaults {
+        total_interest: results.iter().fold(0, |sum: i64, r| sum.saturating_add(r.late_deduction_interest).saturating_add(r.late_deposit_interest)),
+        total_fee_234e: statements.iter().fold(0, |sum: i64, s| sum.saturating_add(s.fee_234e)),
+        entries: results,
+        statements,
+        explanation,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn d(y: i32, m: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, day).unwrap()
+    }
+
+    fn entry(amount: i64, payment: NaiveDate, deduction: NaiveDate, deposit: NaiveDate, filed: NaiveDate) -> TdsEntry {
+        TdsEntry { reference: None, amount, payment_date: payment, deduction_date: deduction, deposit_date: deposit, return_filing_date: filed }
+    }
+
+    #[test]
+    fn deposit_due_dates() {
+        assert_eq!(deposit_due_date(d(2024, 5, 20)), d(2024, 6, 7));
+        assert_eq!(deposit_due_date(d(2024, 12, 31)), d(2025, 1, 7));
+        assert_eq!(deposit_due_date(d(2025, 3, 31)), d(2025, 4, 30));
+    }
+
+    #[test]
+    fn statement_due_dates() {
+        assert_eq!(statement_due(d(2024, 5, 20)), ("Q1 FY 2024-25".to_string(), d(2024, 7, 31)));
+        assert_eq!(statement_due(d(2024, 11, 5)), ("Q3 FY 2024-25".to_string(), d(2025, 1, 31)));
+        assert_eq!(statement_due(d(2025, 2, 10)), ("Q4 FY 2024-25".to_string(), d(2025, 5, 31)));
+    }
+
+    // Deducted 20 May, due 7 June, deposited 10 July: May, June and July count, 3 x 1.5% of ₹50,000 = ₹2,250
+    #[test]
+    fn late_deposit_counts_calendar_months() {
+        let r = compute_defaults(vec![entry(50000, d(2024, 5, 20), d(2024, 5, 20), d(2024, 7, 10), d(2024, 7, 31))]);
+        assert_eq!(r.entries[0].late_deposit_months, 3);
+        assert_eq!(r.entries[0].late_deposit_interest, 2250);
+        assert_eq!(r.entries[0].late_deduction_interest, 0);
+        assert_eq!(r.total_fee_234e, 0);
+    }
+
+    // Paid 15 June, deducted 5 August: June, July and August, 3 x 1% of ₹10,000 = ₹300; deposited on time
+    #[test]
+    fn late_deduction_interest() {
+        let r = compute_defaults(vec![entry(10000, d(2024, 6, 15), d(2024, 8, 5), d(2024, 9, 7), d(2024, 10, 31))]);
+        assert_eq!(r.entries[0].late_deduction_months, 3);
+        assert_eq!(r.entries[0].late_deduction_interest, 300);
+        assert_eq!(r.entries[0].late_deposit_interest, 0);
+        assert_eq!(r.total_interest, 300);
+    }
+
+    // Q1 statement filed 30 August: 30 days x ₹200 = ₹6,000, but never more than the TDS in it
+    #[test]
+    fn fee_234e_capped_at_tds() {
+        let r = compute_defaults(vec![entry(50000, d(2024, 5, 20), d(2024, 5, 20), d(2024, 6, 7), d(2024, 8, 30))]);
+        assert_eq!((r.statements[0].days_late, r.statements[0].fee_234e), (30, 6000));
+
+        let r = compute_defaults(vec![entry(5000, d(2024, 5, 20), d(2024, 5, 20), d(2024, 6, 7), d(2024, 8, 30))]);
+        assert_eq!(r.statements[0].fee_234e, 5000);
+    }
+
+    // Two entries of ₹5e18 used to overflow the quarter's TDS; deposited years late, their interest saturates the cast and the total
+    #[test]
+    fn oversized_entries_saturate() {
+        let big = entry(5_000_000_000_000_000_000, d(2024, 5, 20), d(2024, 5, 20), d(2040, 7, 10), d(2024, 8, 30));
+        let r = compute_defaults(vec![big.clone(), big]);
+        assert_eq!((r.statements[0].tds_amount, r.total_interest), (i64::MAX, i64::MAX));
+    }
+}
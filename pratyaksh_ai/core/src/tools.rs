@@ -0,0 +1,1158 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+use crate::errors::FieldError;
+use crate::trace::RuleTrace;
+
+// Smart Tools: the notice predictors and the tax and finance calculators of the desktop client.
+// Amounts are rupees; results are rounded to the paisa.
+
+// Every calculator validates its whole input before evaluating, so callers can report all bad fields at once
+pub trait Calculator {
+    type Output;
+    fn check(&self) -> Vec<FieldError>;
+    fn evaluate(&self) -> Self::Output;
+}
+
+fn rupees(amount: f64) -> f64 {
+    (amount * 100.0).round() / 100.0
+}
+
+fn non_negative(field: &str, value: f64, errors: &mut Vec<FieldError>) {
+    if value < 0.0 {
+        errors.push(FieldError::OutOfRange { field: field.to_string(), reason: "must not be negative".to_string() });
+    }
+}
+
+fn positive(field: &str, value: f64, errors: &mut Vec<FieldError>) {
+    if value <= 0.0 {
+        errors.push(FieldError::OutOfRange { field: field.to_string(), reason: "must be greater than zero".to_string() });
+    }
+}
+
+// NOTICE PREDICTORS
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct GstMismatchInput {
+    pub gstr1_sales: f64,  // Outward supplies declared in GSTR-1
+    pub gstr3b_sales: f64, // Outward supplies declared in GSTR-3B
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct GstMismatch {
+    pub mismatch_percent: f64,
+    pub risk_level: String, // CRITICAL, HIGH or SAFE
+    pub verdict: String,
+    pub explanation: Vec<RuleTrace>,
+}
+
+impl Calculator for GstMismatchInput {
+    type Output = GstMismatch;
+
+    fn check(&self) -> Vec<FieldError> {
+        let mut errors = Vec::new();
+        positive("gstr1_sales", self.gstr1_sales, &mut errors);
+        non_negative("gstr3b_sales", self.gstr3b_sales, &mut errors);
+        errors
+    }
+
+    // Sec 61 r/w Rule 99: scrutiny of returns; a GSTR-1 vs GSTR-3B gap is the usual ASMT-10 trigger
+    fn evaluate(&self) -> GstMismatch {
+        let percent = (self.gstr1_sales - self.gstr3b_sales).abs() / self.gstr1_sales * 100.0;
+        let (risk, verdict) = if percent > 10.0 {
+            ("CRITICAL", format!("CRITICAL: {:.2}% Mismatch. ASMT-10 Notice Probability: 85%", percent))
+        } else if percent > 5.0 {
+            ("HIGH", format!("HIGH: {:.2}% Mismatch. Reconcile Immediately.", percent))
+        } else {
+            ("SAFE", format!("SAFE: {:.2}% Mismatch is within tolerance.", percent))
+        };
+        GstMismatch {
+            mismatch_percent: rupees(percent),
+            risk_level: risk.to_string(),
+            verdict,
+            explanation: vec![RuleTrace::new("GSTR-1 vs GSTR-3B mismatch", "CRITICAL above 10%; HIGH above 5%", risk, "Sec 61 r/w Rule 99, CGST Rules")
+                .input("gstr1_sales", self.gstr1_sales)
+                .input("gstr3b_sales", self.gstr3b_sales)],
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct SftMismatchInput {
+    pub declared_income: f64,
+    pub high_value_transactions: f64, // Reported by third parties in the SFT / AIS
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct SftMismatch {
+    pub risk_level: String, // HIGH or LOW
+    pub verdict: String,
+    pub explanation: Vec<RuleTrace>,
+}
+
+impl Calculator for SftMismatchInput {
+    type Output = SftMismatch;
+
+    fn check(&self) -> Vec<FieldError> {
+        let mut errors = Vec::new();
+        non_negative("declared_income", self.declared_income, &mut errors);
+        non_negative("high_value_transactions", self.high_value_transactions, &mut errors);
+        errors
+    }
+
+    // Sec 285BA transactions well above declared income are the usual Sec 148A trigger
+    fn evaluate(&self) -> SftMismatch {
+        let high = self.high_value_transactions > self.declared_income * 0.5;
+        let (risk, verdict) = if high {
+            ("HIGH", "HIGH RISK: Sec 148A Notice Likely (SFT Mismatch)")
+        } else {
+            ("LOW", "LOW RISK: Income supports transactions.")
+        };
+        SftMismatch {
+            risk_level: risk.to_string(),
+            verdict: verdict.to_string(),
+            explanation: vec![RuleTrace::new("SFT mismatch", "High-value transactions above 50% of declared income", risk, "Sec 148A r/w Sec 285BA")
+                .input("declared_income", self.declared_income)
+                .input("high_value_transactions", self.high_value_transactions)],
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct TdsShortfallInput {
+    pub deducted: f64,
+    pub deposited: f64,
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct TdsShortfall {
+    pub shortfall: f64,
+    pub verdict: String,
+    pub explanation: Vec<RuleTrace>,
+}
+
+impl Calculator for TdsShortfallInput {
+    type Output = TdsShortfall;
+
+    fn check(&self) -> Vec<FieldError> {
+        let mut errors = Vec::new();
+        non_negative("deducted", self.deducted, &mut errors);
+        non_negative("deposited", self.deposited, &mut errors);
+        errors
+    }
+
+    // Sec 200(1): tax deducted must be paid to the Government; the gap is raised in a Sec 156 demand
+    fn evaluate(&self) -> TdsShortfall {
+        let shortfall = (self.deducted - self.deposited).max(0.0);
+        let verdict = if shortfall > 0.0 {
+            format!("SHORTFALL: ₹{}. Demand Notice Imminent.", rupees(shortfall))
+        } else {
+            "MATCHED: No Demand Risk.".to_string()
+        };
+        TdsShortfall {
+            shortfall: rupees(shortfall),
+            explanation: vec![RuleTrace::new("TDS deposit shortfall", "Deposited less than deducted", &verdict, "Sec 200(1) r/w Sec 201")
+                .input("deducted", self.deducted)
+                .input("deposited", self.deposited)],
+            verdict,
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct McaNoticeInput {
+    pub city: String, // ROC jurisdiction
+    pub form: String,
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct McaNotice {
+    pub probability_percent: u32, // Chance of clearing without an ROC query
+    pub explanation: Vec<RuleTrace>,
+}
+
+impl Calculator for McaNoticeInput {
+    type Output = McaNotice;
+
+    fn check(&self) -> Vec<FieldError> {
+        let mut errors = Vec::new();
+        if self.form.trim().is_empty() {
+            errors.push(FieldError::OutOfRange { field: "form".to_string(), reason: "must not be empty".to_string() });
+        }
+        errors
+    }
+
+    // Scrutiny is heavier at ROC Pune and on financial statements
+    fn evaluate(&self) -> McaNotice {
+        let mut score = 90;
+        if self.city.trim() == "Pune" {
+            score -= 15;
+        }
+        if self.form.trim() == "AOC-4" {
+            score -= 5;
+        }
+        McaNotice {
+            probability_percent: score,
+            explanation: vec![RuleTrace::new("ROC scrutiny", "90% base; -15 at ROC Pune; -5 for AOC-4", format!("Probability: {}%", score), "Sec 206, Companies Act 2013")
+                .input("city", self.city.trim())
+                .input("form", self.form.trim())],
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct BoardRiskInput {
+    pub resolution_text: String,
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct BoardRisk {
+    pub flags: Vec<String>,
+    pub explanation: Vec<RuleTrace>,
+}
+
+impl Calculator for BoardRiskInput {
+    type Output = BoardRisk;
+
+    fn check(&self) -> Vec<FieldError> {
+        Vec::new()
+    }
+
+    // Sec 185: loans to directors and their related parties are barred or need a special resolution
+    fn evaluate(&self) -> BoardRisk {
+        let loan = self.resolution_text.to_lowercase().contains("loan");
+        let flag = if loan { "Sec 185 Risk" } else { "No obvious risks" };
+        BoardRisk {
+            flags: vec![flag.to_string()],
+            explanation: vec![RuleTrace::new("Loan to directors", "Resolution mentions a loan", flag, "Sec 185, Companies Act 2013")],
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct TrustScoreInput {
+    pub gst_turnover: f64,
+    pub bank_credits: f64,
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct TrustScore {
+    pub score: u32, // 0 to 100
+    pub explanation: Vec<RuleTrace>,
+}
+
+impl Calculator for TrustScoreInput {
+    type Output = TrustScore;
+
+    fn check(&self) -> Vec<FieldError> {
+        let mut errors = Vec::new();
+        non_negative("gst_turnover", self.gst_turnover, &mut errors);
+        positive("bank_credits", self.bank_credits, &mut errors);
+        errors
+    }
+
+    // Turnover declared to GST should match what reaches the bank
+    fn evaluate(&self) -> TrustScore {
+        let score = (100.0 - (self.gst_turnover - self.bank_credits).abs() / self.bank_credits * 100.0).clamp(0.0, 100.0).round() as u32;
+        TrustScore {
+            score,
+            explanation: vec![RuleTrace::new("Client trust score", "100 less the % gap between GST turnover and bank credits", format!("{}/100", score), "Internal scoring")
+                .input("gst_turnover", self.gst_turnover)
+                .input("bank_credits", self.bank_credits)],
+        }
+    }
+}
+
+// TAX CALCULATORS
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct MsmeInterestInput {
+    pub amount: f64,
+    pub invoice_date: NaiveDate,
+    pub payment_date: NaiveDate,
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct MsmeInterest {
+    pub days: i64,
+    pub compliant: bool,
+    pub interest: f64,
+    pub explanation: Vec<RuleTrace>,
+}
+
+const MSME_PAYMENT_DAYS: i64 = 45;
+
+impl Calculator for MsmeInterestInput {
+    type Output = MsmeInterest;
+
+    fn check(&self) -> Vec<FieldError> {
+        let mut errors = Vec::new();
+        non_negative("amount", self.amount, &mut errors);
+        if self.payment_date < self.invoice_date {
+            errors.push(FieldError::OutOfRange { field: "payment_date".to_string(), reason: "must not be before invoice_date".to_string() });
+        }
+        errors
+    }
+
+    // Sec 15, MSMED Act: pay within 45 days; Sec 43B(h) disallows the expense until paid
+    fn evaluate(&self) -> MsmeInterest {
+        let days = (self.payment_date - self.invoice_date).num_days();
+        let compliant = days <= MSME_PAYMENT_DAYS;
+        let interest = if compliant { 0.0 } else { self.amount * 0.18 * ((days - 15) as f64 / 365.0) };
+        let outcome = if compliant { "Compliant".to_string() } else { format!("Non-Compliant. Liability: ₹{:.2}", interest) };
+        MsmeInterest {
+            days,
+            compliant,
+            interest: rupees(interest),
+            explanation: vec![RuleTrace::new("MSE payment window", format!("Paid within {} days of invoice", MSME_PAYMENT_DAYS), outcome, "Sec 15-16, MSMED Act r/w Sec 43B(h)")
+                .input("amount", self.amount)
+                .input("invoice_date", self.invoice_date)
+                .input("payment_date", self.payment_date)],
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct GratuityInput {
+    pub monthly_salary: f64, // Last drawn basic + DA
+    pub years_of_service: f64,
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct Gratuity {
+    pub gratuity: f64,
+    pub explanation: Vec<RuleTrace>,
+}
+
+impl Calculator for GratuityInput {
+    type Output = Gratuity;
+
+    fn check(&self) -> Vec<FieldError> {
+        let mut errors = Vec::new();
+        non_negative("monthly_salary", self.monthly_salary, &mut errors);
+        non_negative("years_of_service", self.years_of_service, &mut errors);
+        errors
+    }
+
+    // Sec 4(2), Payment of Gratuity Act: 15 days' wages per completed year, a month taken as 26 days
+    fn evaluate(&self) -> Gratuity {
+        let gratuity = (self.monthly_salary * (15.0 / 26.0) * self.years_of_service).round();
+        Gratuity {
+            gratuity,
+            explanation: vec![RuleTrace::new("Gratuity", "15/26 of monthly wages per year of service", format!("₹{}", gratuity), "Sec 4(2), Payment of Gratuity Act 1972")
+                .input("monthly_salary", self.monthly_salary)
+                .input("years_of_service", self.years_of_service)],
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct TaxRegimeInput {
+    pub income: f64,
+    pub deductions: f64, // Chapter VI-A and other deductions available only under the old regime
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct TaxRegime {
+    pub old_regime_tax: f64,
+    pub new_regime_tax: f64,
+    pub better: String, // "old" or "new"
+    pub explanation: Vec<RuleTrace>,
+}
+
+impl Calculator for TaxRegimeInput {
+    type Output = TaxRegime;
+
+    fn check(&self) -> Vec<FieldError> {
+        let mut errors = Vec::new();
+        non_negative("income", self.income, &mut errors);
+        non_negative("deductions", self.deductions, &mut errors);
+        errors
+    }
+
+    // Sec 115BAC: new regime with ₹75000 standard deduction; old regime with ₹50000 and all deductions
+    fn evaluate(&self) -> TaxRegime {
+        let old = ((self.income - self.deductions - 50000.0) * 0.3).max(0.0).round();
+        let new = ((self.income - 75000.0) * 0.2).max(0.0).round();
+        let better = if new <= old { "new" } else { "old" };
+        TaxRegime {
+            old_regime_tax: old,
+            new_regime_tax: new,
+            better: better.to_string(),
+            explanation: vec![RuleTrace::new("Regime comparison", "Old: 30% after deductions and ₹50000; New: 20% after ₹75000", format!("Old: {:.0} | New: {:.0}", old, new), "Sec 115BAC")
+                .input("income", self.income)
+                .input("deductions", self.deductions)],
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct CryptoTaxInput {
+    pub profit: f64, // Transfer of virtual digital assets
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct CryptoTax {
+    pub tax: f64,
+    pub explanation: Vec<RuleTrace>,
+}
+
+impl Calculator for CryptoTaxInput {
+    type Output = CryptoTax;
+
+    fn check(&self) -> Vec<FieldError> {
+        let mut errors = Vec::new();
+        non_negative("profit", self.profit, &mut errors);
+        errors
+    }
+
+    // Sec 115BBH: 30% flat on VDA income, plus 4% cess
+    fn evaluate(&self) -> CryptoTax {
+        let tax = rupees(self.profit * 0.312);
+        CryptoTax {
+            tax,
+            explanation: vec![RuleTrace::new("VDA tax", "30% plus 4% health and education cess", format!("₹{}", tax), "Sec 115BBH")
+                .input("profit", self.profit)],
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct HraInput {
+    pub basic_salary: f64,
+    pub rent_paid: f64,
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct Hra {
+    pub exempt: f64,
+    pub explanation: Vec<RuleTrace>,
+}
+
+impl Calculator for HraInput {
+    type Output = Hra;
+
+    fn check(&self) -> Vec<FieldError> {
+        let mut errors = Vec::new();
+        non_negative("basic_salary", self.basic_salary, &mut errors);
+        non_negative("rent_paid", self.rent_paid, &mut errors);
+        errors
+    }
+
+    // Rule 2A: rent paid in excess of 10% of salary
+    fn evaluate(&self) -> Hra {
+        let exempt = (self.rent_paid - self.basic_salary * 0.1).max(0.0).round();
+        Hra {
+            exempt,
+            explanation: vec![RuleTrace::new("HRA exemption", "Rent paid less 10% of salary", format!("₹{}", exempt), "Sec 10(13A) r/w Rule 2A")
+                .input("basic_salary", self.basic_salary)
+                .input("rent_paid", self.rent_paid)],
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct PmlaInput {
+    pub amount: f64,
+    #[serde(default)]
+    pub cash: bool,
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct Pmla {
+    pub risk_level: String, // HIGH RISK or Standard
+    pub explanation: Vec<RuleTrace>,
+}
+
+impl Calculator for PmlaInput {
+    type Output = Pmla;
+
+    fn check(&self) -> Vec<FieldError> {
+        let mut errors = Vec::new();
+        non_negative("amount", self.amount, &mut errors);
+        errors
+    }
+
+    // Rule 3, PML (Maintenance of Records) Rules: ₹10 lakh transactions and suspicious cash dealings are reported
+    fn evaluate(&self) -> Pmla {
+        let high = self.amount > 1000000.0 || (self.amount > 50000.0 && self.cash);
+        let risk = if high { "HIGH RISK" } else { "Standard" };
+        Pmla {
+            risk_level: risk.to_string(),
+            explanation: vec![RuleTrace::new("Reportable transaction", "Above ₹10 lakh, or cash above ₹50000", risk, "Sec 12, PMLA 2002 r/w Rule 3")
+                .input("amount", self.amount)
+                .input("cash", self.cash)],
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ShellRiskInput {
+    pub turnover: f64,
+    pub total_assets: f64,
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ShellRisk {
+    pub asset_turnover: f64,
+    pub risk_level: String,
+    pub explanation: Vec<RuleTrace>,
+}
+
+impl Calculator for ShellRiskInput {
+    type Output = ShellRisk;
+
+    fn check(&self) -> Vec<FieldError> {
+        let mut errors = Vec::new();
+        non_negative("turnover", self.turnover, &mut errors);
+        positive("total_assets", self.total_assets, &mut errors);
+        errors
+    }
+
+    // Sec 248: companies without significant business activity are struck off
+    fn evaluate(&self) -> ShellRisk {
+        let ratio = self.turnover / self.total_assets;
+        let risk = if ratio < 0.05 { "High Risk (Shell Indicator)" } else { "Active" };
+        ShellRisk {
+            asset_turnover: (ratio * 10000.0).round() / 10000.0,
+            risk_level: risk.to_string(),
+            explanation: vec![RuleTrace::new("Asset turnover", "Turnover below 5% of total assets", risk, "Sec 248, Companies Act 2013")
+                .input("turnover", self.turnover)
+                .input("total_assets", self.total_assets)],
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct AngelTaxInput {
+    pub issue_price: f64,
+    pub fair_market_value: f64,
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct AngelTax {
+    pub taxable_excess: f64,
+    pub explanation: Vec<RuleTrace>,
+}
+
+impl Calculator for AngelTaxInput {
+    type Output = AngelTax;
+
+    fn check(&self) -> Vec<FieldError> {
+        let mut errors = Vec::new();
+        non_negative("issue_price", self.issue_price, &mut errors);
+        non_negative("fair_market_value", self.fair_market_value, &mut errors);
+        errors
+    }
+
+    // Sec 56(2)(viib): premium above fair market value is income from other sources
+    fn evaluate(&self) -> AngelTax {
+        let excess = rupees((self.issue_price - self.fair_market_value).max(0.0));
+        let outcome = if excess > 0.0 { format!("Taxable: ₹{}", excess) } else { "Safe".to_string() };
+        AngelTax {
+            taxable_excess: excess,
+            explanation: vec![RuleTrace::new("Share premium above FMV", "Issue price above fair market value", outcome, "Sec 56(2)(viib)")
+                .input("issue_price", self.issue_price)
+                .input("fair_market_value", self.fair_market_value)],
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct BuybackTaxInput {
+    pub shares: f64,
+    pub price: f64, // Buyback price per share
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct BuybackTax {
+    pub tax: f64,
+    pub explanation: Vec<RuleTrace>,
+}
+
+impl Calculator for BuybackTaxInput {
+    type Output = BuybackTax;
+
+    fn check(&self) -> Vec<FieldError> {
+        let mut errors = Vec::new();
+        non_negative("shares", self.shares, &mut errors);
+        non_negative("price", self.price, &mut errors);
+        errors
+    }
+
+    // Sec 115QA: 20% plus 12% surcharge and 4% cess on the distributed amount
+    fn evaluate(&self) -> BuybackTax {
+        let tax = rupees(self.shares * self.price * 0.23296);
+        BuybackTax {
+            tax,
+            explanation: vec![RuleTrace::new("Buyback tax", "23.296% of the amount distributed", format!("₹{}", tax), "Sec 115QA")
+                .input("shares", self.shares)
+                .input("price", self.price)],
+        }
+    }
+}
+
+// COMPANY LAW CHECKS
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct EsgInput {
+    pub market_cap_crore: f64,
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct Esg {
+    pub brsr_mandatory: bool,
+    pub explanation: Vec<RuleTrace>,
+}
+
+impl Calculator for EsgInput {
+    type Output = Esg;
+
+    fn check(&self) -> Vec<FieldError> {
+        let mut errors = Vec::new();
+        non_negative("market_cap_crore", self.market_cap_crore, &mut errors);
+        errors
+    }
+
+    // Reg 34(2)(f), SEBI LODR: Business Responsibility and Sustainability Report for the largest listed entities
+    fn evaluate(&self) -> Esg {
+        let mandatory = self.market_cap_crore > 5000.0;
+        Esg {
+            brsr_mandatory: mandatory,
+            explanation: vec![RuleTrace::new("BRSR applicability", "Market capitalisation above ₹5000 crore", if mandatory { "Mandatory BRSR" } else { "Voluntary" }, "Reg 34(2)(f), SEBI LODR")
+                .input("market_cap_crore", self.market_cap_crore)],
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct UdinInput {
+    pub udin: String,
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct Udin {
+    pub valid_format: bool,
+    pub explanation: Vec<RuleTrace>,
+}
+
+const UDIN_LENGTH: usize = 18;
+
+impl Calculator for UdinInput {
+    type Output = Udin;
+
+    fn check(&self) -> Vec<FieldError> {
+        Vec::new()
+    }
+
+    // ICAI UDIN: 18 characters (membership number, year, 6 random characters)
+    fn evaluate(&self) -> Udin {
+        let valid = self.udin.trim().len() == UDIN_LENGTH;
+        Udin {
+            valid_format: valid,
+            explanation: vec![RuleTrace::new("UDIN format", format!("{} characters", UDIN_LENGTH), if valid { "Valid Format" } else { "Invalid" }, "ICAI UDIN Guidelines")
+                .input("udin", self.udin.trim())],
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct AuditRotationInput {
+    pub years_served: u32,
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct AuditRotation {
+    pub rotation_required: bool,
+    pub years_left: u32,
+    pub explanation: Vec<RuleTrace>,
+}
+
+const AUDIT_FIRM_TERM_YEARS: u32 = 10;
+
+impl Calculator for AuditRotationInput {
+    type Output = AuditRotation;
+
+    fn check(&self) -> Vec<FieldError> {
+        Vec::new()
+    }
+
+    // Sec 139(2)(b): an audit firm serves at most two terms of five consecutive years
+    fn evaluate(&self) -> AuditRotation {
+        let left = AUDIT_FIRM_TERM_YEARS.saturating_sub(self.years_served);
+        let outcome = if left == 0 { "Rotation Required".to_string() } else { format!("{} years left", left) };
+        AuditRotation {
+            rotation_required: left == 0,
+            years_left: left,
+            explanation: vec![RuleTrace::new("Auditor rotation", format!("{} consecutive years for an audit firm", AUDIT_FIRM_TERM_YEARS), outcome, "Sec 139(2)(b)")
+                .input("years_served", self.years_served)],
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct NetWorthInput {
+    pub paid_up_capital: f64,
+    pub reserves: f64, // Free reserves and securities premium; negative for accumulated losses
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct NetWorth {
+    pub net_worth: f64,
+    pub explanation: Vec<RuleTrace>,
+}
+
+impl Calculator for NetWorthInput {
+    type Output = NetWorth;
+
+    fn check(&self) -> Vec<FieldError> {
+        let mut errors = Vec::new();
+        non_negative("paid_up_capital", self.paid_up_capital, &mut errors);
+        errors
+    }
+
+    // Sec 2(57): paid-up capital plus reserves
+    fn evaluate(&self) -> NetWorth {
+        let net_worth = rupees(self.paid_up_capital + self.reserves);
+        NetWorth {
+            net_worth,
+            explanation: vec![RuleTrace::new("Net worth", "Paid-up capital plus reserves", format!("₹{}", net_worth), "Sec 2(57), Companies Act 2013")
+                .input("paid_up_capital", self.paid_up_capital)
+                .input("reserves", self.reserves)],
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ExportRealisationInput {
+    pub shipment_date: NaiveDate,
+    pub as_of: NaiveDate,
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ExportRealisation {
+    pub days_outstanding: i64,
+    pub overdue: bool,
+    pub explanation: Vec<RuleTrace>,
+}
+
+const EXPORT_REALISATION_DAYS: i64 = 270;
+
+impl Calculator for ExportRealisationInput {
+    type Output = ExportRealisation;
+
+    fn check(&self) -> Vec<FieldError> {
+        let mut errors = Vec::new();
+        if self.as_of < self.shipment_date {
+            errors.push(FieldError::OutOfRange { field: "as_of".to_string(), reason: "must not be before shipment_date".to_string() });
+        }
+        errors
+    }
+
+    // FEMA Export Regulations, 2015: export value realised within nine months of shipment
+    fn evaluate(&self) -> ExportRealisation {
+        let days = (self.as_of - self.shipment_date).num_days();
+        let overdue = days > EXPORT_REALISATION_DAYS;
+        ExportRealisation {
+            days_outstanding: days,
+            overdue,
+            explanation: vec![RuleTrace::new("Export realisation", format!("Realised within {} days of shipment", EXPORT_REALISATION_DAYS), if overdue { "Overdue (FEMA Risk)" } else { "Compliant" }, "Reg 9, FEMA (Export of Goods and Services) Regulations 2015")
+                .input("shipment_date", self.shipment_date)
+                .input("as_of", self.as_of)],
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct PartnerDissolutionInput {
+    pub assets: f64,
+    pub liabilities: f64,
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct PartnerDissolution {
+    pub net_assets: f64,
+    pub explanation: Vec<RuleTrace>,
+}
+
+impl Calculator for PartnerDissolutionInput {
+    type Output = PartnerDissolution;
+
+    fn check(&self) -> Vec<FieldError> {
+        let mut errors = Vec::new();
+        non_negative("assets", self.assets, &mut errors);
+        non_negative("liabilities", self.liabilities, &mut errors);
+        errors
+    }
+
+    // Sec 48, Partnership Act: liabilities are settled first, the residue goes to the partners
+    fn evaluate(&self) -> PartnerDissolution {
+        let net = rupees(self.assets - self.liabilities);
+        PartnerDissolution {
+            net_assets: net,
+            explanation: vec![RuleTrace::new("Settlement on dissolution", "Assets less third-party liabilities", format!("₹{}", net), "Sec 48, Indian Partnership Act 1932")
+                .input("assets", self.assets)
+                .input("liabilities", self.liabilities)],
+        }
+    }
+}
+
+// FINANCE CALCULATORS
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct LeaseInput {
+    pub annual_payment: f64,
+    pub rate_percent: f64, // Incremental borrowing rate
+    pub years: f64,
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct Lease {
+    pub right_of_use_asset: f64,
+    pub explanation: Vec<RuleTrace>,
+}
+
+impl Calculator for LeaseInput {
+    type Output = Lease;
+
+    fn check(&self) -> Vec<FieldError> {
+        let mut errors = Vec::new();
+        non_negative("annual_payment", self.annual_payment, &mut errors);
+        non_negative("rate_percent", self.rate_percent, &mut errors);
+        positive("years", self.years, &mut errors);
+        errors
+    }
+
+    // Ind AS 116: lease liability is the present value of the payments, discounted at the borrowing rate
+    fn evaluate(&self) -> Lease {
+        let r = self.rate_percent / 100.0;
+        let pv = if r == 0.0 { self.annual_payment * self.years } else { self.annual_payment * ((1.0 - (1.0 + r).powf(-self.years)) / r) };
+        let pv = rupees(pv);
+        Lease {
+            right_of_use_asset: pv,
+            explanation: vec![RuleTrace::new("Lease liability", "Present value of annual payments", format!("ROU Asset: ₹{}", pv), "Ind AS 116, para 26")
+                .input("annual_payment", self.annual_payment)
+                .input("rate_percent", self.rate_percent)
+                .input("years", self.years)],
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct DepreciationInput {
+    pub cost: f64, // Opening written down value of the block
+    pub rate_percent: f64,
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct Depreciation {
+    pub depreciation: f64,
+    pub written_down_value: f64,
+    pub explanation: Vec<RuleTrace>,
+}
+
+impl Calculator for DepreciationInput {
+    type Output = Depreciation;
+
+    fn check(&self) -> Vec<FieldError> {
+        let mut errors = Vec::new();
+        non_negative("cost", self.cost, &mut errors);
+        if !(0.0..=100.0).contains(&self.rate_percent) {
+            errors.push(FieldError::OutOfRange { field: "rate_percent".to_string(), reason: "must be between 0 and 100".to_string() });
+        }
+        errors
+    }
+
+    // Sec 32(1)(ii): written down value method on the block of assets
+    fn evaluate(&self) -> Depreciation {
+        let depreciation = rupees(self.cost * self.rate_percent / 100.0);
+        let wdv = rupees(self.cost - depreciation);
+        Depreciation {
+            depreciation,
+            written_down_value: wdv,
+            explanation: vec![RuleTrace::new("WDV depreciation", format!("{}% of opening WDV", self.rate_percent), format!("WDV: ₹{}", wdv), "Sec 32(1)(ii)")
+                .input("cost", self.cost)],
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct IndexedCostInput {
+    pub cost: f64,
+    pub cii_purchase: f64, // Cost Inflation Index for the year of purchase
+    pub cii_sale: f64,     // Cost Inflation Index for the year of transfer
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct IndexedCost {
+    pub indexed_cost: f64,
+    pub explanation: Vec<RuleTrace>,
+}
+
+impl Calculator for IndexedCostInput {
+    type Output = IndexedCost;
+
+    fn check(&self) -> Vec<FieldError> {
+        let mut errors = Vec::new();
+        non_negative("cost", self.cost, &mut errors);
+        positive("cii_purchase", self.cii_purchase, &mut errors);
+        positive("cii_sale", self.cii_sale, &mut errors);
+        errors
+    }
+
+    // Sec 48, Explanation (iii): cost x CII of transfer year / CII of acquisition year
+    fn evaluate(&self) -> IndexedCost {
+        let indexed = rupees(self.cost * (self.cii_sale / self.cii_purchase));
+        IndexedCost {
+            indexed_cost: indexed,
+            explanation: vec![RuleTrace::new("Indexed cost of acquisition", "Cost scaled by the Cost Inflation Index", format!("Indexed Cost: ₹{}", indexed), "Sec 48, Explanation (iii)")
+                .input("cost", self.cost)
+                .input("cii_purchase", self.cii_purchase)
+                .input("cii_sale", self.cii_sale)],
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct EmiInput {
+    pub principal: f64,
+    pub annual_rate_percent: f64,
+    pub years: f64,
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct Emi {
+    pub emi: f64,
+    pub total_payment: f64,
+    pub total_interest: f64,
+    pub explanation: Vec<RuleTrace>,
+}
+
+impl Calculator for EmiInput {
+    type Output = Emi;
+
+    fn check(&self) -> Vec<FieldError> {
+        let mut errors = Vec::new();
+        non_negative("principal", self.principal, &mut errors);
+        non_negative("annual_rate_percent", self.annual_rate_percent, &mut errors);
+        positive("years", self.years, &mut errors);
+        errors
+    }
+
+    // Reducing balance: P x r x (1+r)^n / ((1+r)^n - 1), monthly rate and tenure
+    fn evaluate(&self) -> Emi {
+        let r = self.annual_rate_percent / 1200.0;
+        let n = self.years * 12.0;
+        let emi = if r == 0.0 { self.principal / n } else { self.principal * r * (1.0 + r).powf(n) / ((1.0 + r).powf(n) - 1.0) };
+        Emi {
+            emi: rupees(emi),
+            total_payment: rupees(emi * n),
+            total_interest: rupees(emi * n - self.principal),
+            explanation: vec![RuleTrace::new("EMI", "Reducing balance, monthly rests", format!("Monthly EMI: ₹{:.2}", emi), "Standard amortisation formula")
+                .input("principal", self.principal)
+                .input("annual_rate_percent", self.annual_rate_percent)
+                .input("years", self.years)],
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct BurnRateInput {
+    pub cash: f64,
+    pub monthly_spend: f64,
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct BurnRate {
+    pub runway_months: f64,
+    pub explanation: Vec<RuleTrace>,
+}
+
+impl Calculator for BurnRateInput {
+    type Output = BurnRate;
+
+    fn check(&self) -> Vec<FieldError> {
+        let mut errors = Vec::new();
+        non_negative("cash", self.cash, &mut errors);
+        positive("monthly_spend", self.monthly_spend, &mut errors);
+        errors
+    }
+
+    fn evaluate(&self) -> BurnRate {
+        let runway = (self.cash / self.monthly_spend * 10.0).round() / 10.0;
+        BurnRate {
+            runway_months: runway,
+            explanation: vec![RuleTrace::new("Runway", "Cash divided by monthly net spend", format!("Runway: {:.1} months", runway), "Cash flow projection")
+                .input("cash", self.cash)
+                .input("monthly_spend", self.monthly_spend)],
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct SimpleInterestInput {
+    pub principal: f64,
+    pub rate_percent: f64,
+    pub years: f64,
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct SimpleInterest {
+    pub interest: f64,
+    pub explanation: Vec<RuleTrace>,
+}
+
+impl Calculator for SimpleInterestInput {
+    type Output = SimpleInterest;
+
+    fn check(&self) -> Vec<FieldError> {
+        let mut errors = Vec::new();
+        non_negative("principal", self.principal, &mut errors);
+        non_negative("rate_percent", self.rate_percent, &mut errors);
+        non_negative("years", self.years, &mut errors);
+        errors
+    }
+
+    fn evaluate(&self) -> SimpleInterest {
+        let interest = rupees(self.principal * self.rate_percent * self.years / 100.0);
+        SimpleInterest {
+            interest,
+            explanation: vec![RuleTrace::new("Simple interest", "P x R x T / 100", format!("Interest: ₹{}", interest), "Simple interest formula")
+                .input("principal", self.principal)
+                .input("rate_percent", self.rate_percent)
+                .input("years", self.years)],
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct CagrInput {
+    pub start_value: f64,
+    pub end_value: f64,
+    pub years: f64,
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct Cagr {
+    pub cagr_percent: f64,
+    pub explanation: Vec<RuleTrace>,
+}
+
+impl Calculator for CagrInput {
+    type Output = Cagr;
+
+    fn check(&self) -> Vec<FieldError> {
+        let mut errors = Vec::new();
+        positive("start_value", self.start_value, &mut errors);
+        non_negative("end_value", self.end_value, &mut errors);
+        positive("years", self.years, &mut errors);
+        errors
+    }
+
+    fn evaluate(&self) -> Cagr {
+        let cagr = ((self.end_value / self.start_value).powf(1.0 / self.years) - 1.0) * 100.0;
+        Cagr {
+            cagr_percent: rupees(cagr),
+            explanation: vec![RuleTrace::new("CAGR", "(end / start)^(1 / years) - 1", format!("CAGR: {:.2}%", cagr), "Compound annual growth rate")
+                .input("start_value", self.start_value)
+                .input("end_value", self.end_value)
+                .input("years", self.years)],
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct BreakEvenInput {
+    pub fixed_costs: f64,
+    pub price: f64,         // Selling price per unit
+    pub variable_cost: f64, // Variable cost per unit
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct BreakEven {
+    pub units: f64,
+    pub explanation: Vec<RuleTrace>,
+}
+
+impl Calculator for BreakEvenInput {
+    type Output = BreakEven;
+
+    fn check(&self) -> Vec<FieldError> {
+        let mut errors = Vec::new();
+        non_negative("fixed_costs", self.fixed_costs, &mut errors);
+        non_negative("variable_cost", self.variable_cost, &mut errors);
+        if self.price <= self.variable_cost {
+            errors.push(FieldError::OutOfRange { field: "price".to_string(), reason: "must exceed variable_cost".to_string() });
+        }
+        errors
+    }
+
+    fn evaluate(&self) -> BreakEven {
+        let units = (self.fixed_costs / (self.price - self.variable_cost)).ceil();
+        BreakEven {
+            units,
+            explanation: vec![RuleTrace::new("Break-even volume", "Fixed costs / contribution per unit", format!("Break Even: {:.0} units", units), "Cost-volume-profit analysis")
+                .input("fixed_costs", self.fixed_costs)
+                .input("price", self.price)
+                .input("variable_cost", self.variable_cost)],
+        }
+    }
+}
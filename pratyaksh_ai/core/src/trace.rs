@@ -0,0 +1,30 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+// One evaluated rule, in a form that can be quoted in a client advisory letter
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct RuleTrace {
+    pub rule: String,
+    pub inputs: BTreeMap<String, String>,
+    pub threshold: String,
+    pub outcome: String,
+    pub reference: String,
+}
+
+impl RuleTrace {
+    pub fn new(rule: &str, threshold: impl Into<String>, outcome: impl Into<String>, reference: impl Into<String>) -> Self {
+        RuleTrace {
+            rule: rule.to_string(),
+            inputs: BTreeMap::new(),
+            threshold: threshold.into(),
+            outcome: outcome.into(),
+            reference: reference.into(),
+        }
+    }
+
+    pub fn input(mut self, name: &str, value: impl ToString) -> Self {
+        self.inputs.insert(name.to_string(), value.to_string());
+        self
+    }
+}
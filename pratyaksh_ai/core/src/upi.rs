@@ -0,0 +1,82 @@
+use serde::Serialize;
+
+// Subscription plans and the UPI intent links that pay for them (NPCI UPI Linking Specification).
+// Amounts are paise, as everywhere money is stored.
+
+#[derive(Serialize, Clone, Debug)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct Plan {
+    pub id: &'static str,
+    pub name: &'static str,
+    pub amount: i64, // Paise per billing period
+    pub months: u32, // License period one payment buys
+}
+
+// Same tiers and prices as the website's pricing page
+pub const PLANS: &[Plan] = &[
+    Plan { id: "launch", name: "Launch", amount: 119900, months: 1 },
+    Plan { id: "scale", name: "Scale", amount: 239900, months: 1 },
+    Plan { id: "enterprise", name: "Enterprise", amount: 499900, months: 1 },
+];
+
+// Accepts the plan id or its display name, in any case
+pub fn find_plan(raw: &str) -> Option<&'static Plan> {
+    let raw = raw.trim();
+    PLANS.iter().find(|p| p.id.eq_ignore_ascii_case(raw) || p.name.eq_ignore_ascii_case(raw))
+}
+
+// Percent-encodes a query value; '@' stays literal since some UPI apps reject an encoded VPA
+fn encode(value: &str) -> String {
+    value.bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b'@' => (b as char).to_string(),
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+// upi://pay with a fixed amount; tr identifies the order and tn repeats it, since most bank
+// statements only carry the remark
+pub fn intent_uri(vpa: &str, payee_name: &str, amount: i64, txn_ref: &str, note: &str) -> String {
+    format!(
+        "upi://pay?pa={}&pn={}&am={}.{:02}&cu=INR&tr={}&tn={}",
+        encode(vpa),
+        encode(payee_name),
+        amount / 100,
+        amount % 100,
+        encode(txn_ref),
+        encode(note),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn find_plan_accepts_id_or_name() {
+        assert_eq!(find_plan("scale").map(|p| p.amount), Some(239900));
+        assert_eq!(find_plan(" Enterprise ").map(|p| p.id), Some("enterprise"));
+        assert!(find_plan("Enterprise Suite").is_none());
+    }
+
+    // Spaces, '&' and '=' in names must not split or add query parameters; the VPA keeps its '@'
+    #[test]
+    fn intent_uri_encodes_values() {
+        let uri = intent_uri("pratyaksh@okaxis", "Sharma & Sons=CA", 239900, "PRK00AB12CD34EF", "Scale PRK00AB12CD34EF");
+        assert_eq!(uri, "upi://pay?pa=pratyaksh@okaxis&pn=Sharma%20%26%20Sons%3DCA&am=2399.00&cu=INR&tr=PRK00AB12CD34EF&tn=Scale%20PRK00AB12CD34EF");
+    }
+
+    #[test]
+    fn amount_keeps_two_decimal_places() {
+        assert!(intent_uri("a@b", "A", 119905, "PRK1", "n").contains("&am=1199.05&"));
+        assert!(intent_uri("a@b", "A", 100, "PRK1", "n").contains("&am=1.00&"));
+    }
+
+    // Non-ASCII names are encoded byte by byte as UTF-8
+    #[test]
+    fn encodes_utf8_bytes() {
+        assert_eq!(encode("₹"), "%E2%82%B9");
+        assert_eq!(encode("a-b.c_d~e"), "a-b.c_d~e");
+    }
+}
@@ -2,7 +2,13 @@
 
 use eframe::egui;
 use rusqlite::{params, Connection};
-use chrono::{Local, NaiveDate};
+use chrono::{Datelike, Local, Months, NaiveDate, Utc};
+use pratyaksh_core::calendar::{build_calendar, CalendarQuery, CompanyType};
+use pratyaksh_core::circulars::CircularRegistry;
+use pratyaksh_core::errors::check_amount;
+use pratyaksh_core::tools::*;
+use pratyaksh_core::license::{self, LicenseClaims, LicenseStatus};
+use pratyaksh_core::{fees, gst, ics, income_tax, llp, tds};
 use std::sync::{Arc, Mutex};
 use sha2::{Sha256, Digest};
 use std::collections::HashMap;
@@ -24,6 +30,11 @@ const ICON_LOCK: &[u8] = r##"<svg viewBox="0 0 24 24" fill="none" stroke="white"
 const ICON_DOC: &[u8] = r##"<svg viewBox="0 0 24 24" fill="none" stroke="white" stroke-width="1.5"><path d="M14 2H6a2 2 0 0 0-2 2v16a2 2 0 0 0 2 2h12a2 2 0 0 0 2-2V8z"/><polyline points="14 2 14 8 20 8"/><line x1="16" y1="13" x2="8" y2="13"/><line x1="16" y1="17" x2="8" y2="17"/></svg>"##.as_bytes();
 const ICON_TOOL: &[u8] = r##"<svg viewBox="0 0 24 24" fill="none" stroke="white" stroke-width="1.5"><path d="M14.7 6.3a1 1 0 0 0 0 1.4l1.6 1.6a1 1 0 0 0 1.4 0l3.77-3.77a6 6 0 0 1-7.94 7.94l-6.91 6.91a2.12 2.12 0 0 1-3-3l6.91-6.91a6 6 0 0 1 7.94-7.94l-3.76 3.76z"/></svg>"##.as_bytes();
 const ICON_SETT: &[u8] = r##"<svg viewBox="0 0 24 24" fill="none" stroke="white" stroke-width="1.5"><circle cx="12" cy="12" r="3"/><path d="M19.4 15a1.65 1.65 0 0 0 .33 1.82l.06.06a2 2 0 0 1 0 2.83 2 2 0 0 1-2.83 0l-.06-.06a1.65 1.65 0 0 0-1.82-.33 1.65 1.65 0 0 0-1 1.51V21a2 2 0 0 1-2 2 2 2 0 0 1-2-2v-.09A1.65 1.65 0 0 0 9 19.4a1.65 1.65 0 0 0-1.82.33l-.06.06a2 2 0 0 1-2.83 0 2 2 0 0 1 0-2.83l.06-.06a1.65 1.65 0 0 0 .33-1.82 1.65 1.65 0 0 0-1.51-1H3a2 2 0 0 1-2-2 2 2 0 0 1 2-2h.09A1.65 1.65 0 0 0 4.6 9a1.65 1.65 0 0 0-.33-1.82l-.06-.06a2 2 0 0 1 0-2.83 2 2 0 0 1 2.83 0l.06.06a1.65 1.65 0 0 0 1.82.33H9a1.65 1.65 0 0 0 1-1.51V3a2 2 0 0 1 2-2 2 2 0 0 1 2 2v.09a1.65 1.65 0 0 0 1 1.51 1.65 1.65 0 0 0 1.82-.33l.06-.06a2 2 0 0 1 2.83 0 2 2 0 0 1 0 2.83l-.06.06a1.65 1.65 0 0 0-.33 1.82V9a1.65 1.65 0 0 0 1.51 1H21a2 2 0 0 1 2 2 2 2 0 0 1-2 2h-.09a1.65 1.65 0 0 0-1.51 1z"/></svg>"##.as_bytes();
+// Offline license check: license.json must be signed by the backend key whose public half is baked in at build time
+const LICENSE_PUBLIC_KEY: Option<&str> = option_env!("PRATYAKSH_LICENSE_PUBLIC_KEY");
+const LICENSE_FILE: &str = "license.json"; // Kept next to the executable, whatever the start-in directory
+const NO_DEVICE_ID: &str = "This computer exposes no machine ID (MachineGuid / COMPUTERNAME), so a license cannot be bound to it.";
+
 const ICON_SHIELD: &[u8] = r##"<svg viewBox="0 0 24 24" fill="none" stroke="white" stroke-width="1.5"><path d="M12 22s8-4 8-10V5l-8-3-8 3v7c0 6 8 10 8 10z"/></svg>"##.as_bytes();
 
 // ============================================================================
@@ -70,6 +81,12 @@ enum ActiveTool {
     BurnRate, SimpleInt, TdsInterest, CagrCalc, BreakEven
 }
 
+enum LicenseState {
+    Missing(String), // Why no usable license was found; shows the activation screen
+    Active(LicenseClaims),
+    Expired(LicenseClaims), // Read-only: stored data can be viewed, nothing new saved
+}
+
 #[derive(Debug, Clone)]
 struct Client { id: i32, name: String, city: String, trust: i32 }
 #[derive(Debug, Clone)]
@@ -77,9 +94,13 @@ struct EvidenceLog { id: i32, client: String, note: String, hash: String, date:
 
 struct PratyakshApp {
     db: Arc<Mutex<Connection>>,
+    circulars: CircularRegistry, // Bundled MCA circulars for deadline extensions and fee waivers
     current_page: Page,
     current_lang: Language,
     license_accepted: bool,
+    device_id: Option<String>, // None: no machine identifier, activation refused
+    license: LicenseState,
+    license_input: String,
     
     client_count: i32,
     evidence_count: i32,
@@ -98,12 +119,12 @@ struct PratyakshApp {
     msme_reg: String, msme_res: String,
 
     // --- Core Inputs ---
-    new_client_name: String, new_client_city: String,
+    new_client_name: String, new_client_city: String, ics_fy_end: NaiveDate,
     ev_client_name: String, ev_note: String,
     active_tool: ActiveTool,
     
     // Tool Inputs
-    mca_city: String, mca_form: String, mca_result: String,
+    mca_city: String, mca_form: String, mca_capital: String, mca_due_date: NaiveDate, mca_result: String, mca_curve: Vec<fees::FeeProjection>,
     board_text: String, board_result: Vec<String>,
     trust_gst: String, trust_bank: String, trust_result: String,
     reg_id: String, reg_note: String,
@@ -111,7 +132,7 @@ struct PratyakshApp {
     // Calculators
     msme_amt: String, msme_inv_date: NaiveDate, msme_pay_date: NaiveDate, msme_result: String,
     grat_sal: String, grat_yrs: String, grat_result: String,
-    pen_days: String, pen_filing_type: String, pen_result: String,
+    pen_days: String, pen_filing_type: String, pen_capital: String, pen_result: String,
     
     // New 30+ Tools Inputs
     tax_inc: String, tax_ded: String, tax_result: String,
@@ -119,7 +140,8 @@ struct PratyakshApp {
     pmla_amt: String, pmla_cash: bool, pmla_result: String,
     shell_to: String, shell_ast: String, shell_result: String,
     hra_basic: String, hra_rent: String, hra_result: String,
-    adv_tax: String, adv_paid: String, adv_result: String,
+    adv_tax: String, adv_paid: String, adv_pay_date: NaiveDate, adv_payments: Vec<income_tax::TaxPayment>,
+    adv_fy_end: NaiveDate, adv_filing_date: NaiveDate, adv_presumptive: bool, adv_result: String,
     lease_pmt: String, lease_rate: String, lease_years: String, lease_result: String,
     angel_issue: String, angel_fmv: String, angel_result: String,
     buy_shares: String, buy_price: String, buy_result: String,
@@ -131,14 +153,15 @@ struct PratyakshApp {
     part_ast: String, part_lia: String, part_result: String,
     
     // Extra 10 Tools Inputs
-    gst_tax: String, gst_days: String, gst_calc_res: String,
+    gst_tax: String, gst_due_date: NaiveDate, gst_paid_date: NaiveDate, gst_excess_itc: bool,
+    gst_return: String, gst_turnover: String, gst_nil: bool, gst_calc_res: String,
     dep_cost: String, dep_rate: String, dep_res: String,
     cg_cost: String, cg_idx1: String, cg_idx2: String, cg_result: String,
     llp_contrib: String, llp_result: String,
     emi_p: String, emi_r: String, emi_n: String, emi_result: String,
     burn_cash: String, burn_spend: String, burn_result: String,
     si_p: String, si_r: String, si_t: String, si_result: String,
-    tds_amt: String, tds_months: String, tds_calc_res: String,
+    tds_amt: String, tds_paid_date: NaiveDate, tds_ded_date: NaiveDate, tds_dep_date: NaiveDate, tds_filed_date: NaiveDate, tds_calc_res: String,
     cagr_start: String, cagr_end: String, cagr_yrs: String, cagr_result: String,
     be_fixed: String, be_price: String, be_var: String, be_result: String,
 
@@ -170,11 +193,23 @@ impl PratyakshApp {
             "SELECT value FROM settings WHERE key = 'license_accepted'", [], |r| r.get::<_, String>(0)
         ).unwrap_or("false".into()) == "true";
 
+        let device_id = device_id();
+        let license = match &device_id {
+            None => LicenseState::Missing(NO_DEVICE_ID.into()),
+            Some(id) => license_path().and_then(std::fs::read_to_string)
+                .map(|raw| check_license(&raw, id))
+                .unwrap_or_else(|_| LicenseState::Missing("No license installed yet.".into())),
+        };
+
         let mut app = Self {
             db: Arc::new(Mutex::new(db_conn)),
+            circulars: CircularRegistry::bundled(),
             current_page: if license_accepted { Page::Dashboard } else { Page::LicenseAgreement },
             current_lang: Language::English,
             license_accepted,
+            device_id,
+            license,
+            license_input: "".into(),
             active_tool: ActiveTool::None,
             
             client_count: 0, evidence_count: 0,
@@ -193,15 +228,15 @@ impl PratyakshApp {
             startup_dipp: "".into(), startup_res: "".into(),
             msme_reg: "".into(), msme_res: "".into(),
 
-            new_client_name: "".into(), new_client_city: "Pune".into(),
+            new_client_name: "".into(), new_client_city: "Pune".into(), ics_fy_end: NaiveDate::from_ymd_opt(Local::now().year() - 1, 3, 31).unwrap(),
             ev_client_name: "".into(), ev_note: "".into(),
-            mca_city: "Pune".into(), mca_form: "AOC-4".into(), mca_result: "".into(),
+            mca_city: "Pune".into(), mca_form: "AOC-4".into(), mca_capital: "".into(), mca_due_date: Local::now().date_naive(), mca_result: "".into(), mca_curve: vec![],
             board_text: "".into(), board_result: vec![],
             trust_gst: "".into(), trust_bank: "".into(), trust_result: "".into(),
             reg_id: "".into(), reg_note: "".into(),
             msme_amt: "".into(), msme_inv_date: Local::now().date_naive(), msme_pay_date: Local::now().date_naive(), msme_result: "".into(),
             grat_sal: "".into(), grat_yrs: "".into(), grat_result: "".into(),
-            pen_days: "".into(), pen_filing_type: "AOC-4".into(), pen_result: "".into(),
+            pen_days: "".into(), pen_filing_type: "AOC-4".into(), pen_capital: "".into(), pen_result: "".into(),
             
             // New Tools
             tax_inc: "".into(), tax_ded: "".into(), tax_result: "".into(),
@@ -209,7 +244,8 @@ impl PratyakshApp {
             pmla_amt: "".into(), pmla_cash: false, pmla_result: "".into(),
             shell_to: "".into(), shell_ast: "".into(), shell_result: "".into(),
             hra_basic: "".into(), hra_rent: "".into(), hra_result: "".into(),
-            adv_tax: "".into(), adv_paid: "".into(), adv_result: "".into(),
+            adv_tax: "".into(), adv_paid: "".into(), adv_pay_date: Local::now().date_naive(), adv_payments: vec![],
+            adv_fy_end: NaiveDate::from_ymd_opt(Local::now().year() - 1, 3, 31).unwrap(), adv_filing_date: Local::now().date_naive(), adv_presumptive: false, adv_result: "".into(),
             lease_pmt: "".into(), lease_rate: "10".into(), lease_years: "5".into(), lease_result: "".into(),
             angel_issue: "".into(), angel_fmv: "".into(), angel_result: "".into(),
             buy_shares: "".into(), buy_price: "".into(), buy_result: "".into(),
@@ -221,14 +257,16 @@ impl PratyakshApp {
             part_ast: "".into(), part_lia: "".into(), part_result: "".into(),
 
             // Extra 10 Tools
-            gst_tax: "".into(), gst_days: "".into(), gst_calc_res: "".into(),
+            gst_tax: "".into(), gst_due_date: Local::now().date_naive(), gst_paid_date: Local::now().date_naive(), gst_excess_itc: false,
+            gst_return: "GSTR-3B".into(), gst_turnover: "".into(), gst_nil: false, gst_calc_res: "".into(),
             dep_cost: "".into(), dep_rate: "".into(), dep_res: "".into(),
-            cg_cost: "".into(), cg_idx1: "".into(), cg_idx2: "".into(), cg_res: "".into(),
-            llp_contrib: "".into(), llp_res: "".into(),
-            emi_p: "".into(), emi_r: "".into(), emi_n: "".into(), emi_res: "".into(),
-            burn_cash: "".into(), burn_spend: "".into(), burn_res: "".into(),
-            si_p: "".into(), si_r: "".into(), si_t: "".into(), si_res: "".into(),
-            tds_amt: "".into(), tds_months: "".into(), tds_calc_res: "".into(),
+            cg_cost: "".into(), cg_idx1: "".into(), cg_idx2: "".into(), cg_result: "".into(),
+            llp_contrib: "".into(), llp_result: "".into(),
+            emi_p: "".into(), emi_r: "".into(), emi_n: "".into(), emi_result: "".into(),
+            burn_cash: "".into(), burn_spend: "".into(), burn_result: "".into(),
+            si_p: "".into(), si_r: "".into(), si_t: "".into(), si_result: "".into(),
+            tds_amt: "".into(), tds_paid_date: Local::now().date_naive(), tds_ded_date: Local::now().date_naive(),
+            tds_dep_date: Local::now().date_naive(), tds_filed_date: Local::now().date_naive(), tds_calc_res: "".into(),
             cagr_start: "".into(), cagr_end: "".into(), cagr_yrs: "".into(), cagr_result: "".into(),
             be_fixed: "".into(), be_price: "".into(), be_var: "".into(), be_result: "".into(),
 
@@ -289,6 +327,26 @@ impl PratyakshApp {
         self.current_page = Page::Dashboard;
     }
 
+    fn read_only(&self) -> bool {
+        matches!(self.license, LicenseState::Expired(_))
+    }
+
+    fn activate_license(&mut self) {
+        let Some(device_id) = &self.device_id else {
+            self.license = LicenseState::Missing(NO_DEVICE_ID.into());
+            return;
+        };
+        let raw = self.license_input.trim().to_string();
+        self.license = check_license(&raw, device_id);
+        if !matches!(self.license, LicenseState::Missing(_)) {
+            if let Err(e) = license_path().and_then(|path| std::fs::write(path, &raw)) {
+                self.license = LicenseState::Missing(format!("Could not save {}: {}", LICENSE_FILE, e));
+                return;
+            }
+            self.license_input.clear();
+        }
+    }
+
     fn refresh_db(&mut self) {
         let conn = self.db.lock().unwrap();
         self.client_count = conn.query_row("SELECT COUNT(*) FROM clients", [], |r| r.get(0)).unwrap_or(0);
@@ -308,45 +366,56 @@ impl PratyakshApp {
     // --- REAL AI NOTICE PREDICTION LOGIC ---
 
     fn calc_gst_risk(&mut self) {
-        let r1 = self.gst_sales_1.parse::<f64>().unwrap_or(0.0);
-        let r3b = self.gst_sales_3b.parse::<f64>().unwrap_or(0.0);
-        if r1 == 0.0 { return; }
-        
-        let diff = (r1 - r3b).abs();
-        let percent = (diff / r1) * 100.0;
-
-        self.gst_res = if percent > 10.0 {
-            format!("CRITICAL: {:.2}% Mismatch. ASMT-10 Notice Probability: 85%", percent)
-        } else if percent > 5.0 {
-            format!("HIGH: {:.2}% Mismatch. Reconcile Immediately.", percent)
-        } else {
-            format!("SAFE: {:.2}% Mismatch is within tolerance.", percent)
+        let input = GstMismatchInput {
+            gstr1_sales: self.gst_sales_1.parse::<f64>().unwrap_or(0.0),
+            gstr3b_sales: self.gst_sales_3b.parse::<f64>().unwrap_or(0.0),
         };
+        self.gst_res = run(input, |r| r.verdict);
     }
 
     fn calc_it_risk(&mut self) {
-        let inc = self.it_income.parse::<f64>().unwrap_or(0.0);
-        let txn = self.it_high_val.parse::<f64>().unwrap_or(0.0);
-        self.it_res = if txn > (inc * 0.5) {
-            "HIGH RISK: Sec 148A Notice Likely (SFT Mismatch)".to_string()
-        } else {
-            "LOW RISK: Income supports transactions.".to_string()
+        let input = SftMismatchInput {
+            declared_income: self.it_income.parse::<f64>().unwrap_or(0.0),
+            high_value_transactions: self.it_high_val.parse::<f64>().unwrap_or(0.0),
         };
+        self.it_res = run(input, |r| r.verdict);
     }
 
     fn calc_tds_recon(&mut self) {
-        let ded = self.tds_deducted.parse::<f64>().unwrap_or(0.0);
-        let dep = self.tds_deposited.parse::<f64>().unwrap_or(0.0);
-        self.tds_res = if dep < ded {
-            format!("SHORTFALL: ₹{}. Demand Notice Imminent.", ded - dep)
-        } else {
-            "MATCHED: No Demand Risk.".to_string()
+        let input = TdsShortfallInput {
+            deducted: self.tds_deducted.parse::<f64>().unwrap_or(0.0),
+            deposited: self.tds_deposited.parse::<f64>().unwrap_or(0.0),
         };
+        self.tds_res = run(input, |r| r.verdict);
     }
     
     // --- CORE LOGIC ---
+    // Each client is calendared as a private company on the shared ROC engine, with circular extensions applied
+    fn export_ics(&mut self, clients: Vec<String>, file: &str) {
+        let events: Vec<ics::IcsEvent> = clients.iter()
+            .flat_map(|name| {
+                let query = CalendarQuery {
+                    cin: Some(name.clone()),
+                    fy_end_date: self.ics_fy_end,
+                    incorporation_date: self.ics_fy_end - Months::new(12),
+                    company_type: CompanyType::Private,
+                    has_subsidiaries: false,
+                    board_meeting_date: None,
+                    first_fy: false,
+                    agm_extension_months: 0,
+                };
+                ics::calendar_events(name, &build_calendar(&query, &self.circulars))
+            })
+            .collect();
+        let body = ics::render("Pratyaksh deadlines", &events, Utc::now());
+        self.status_msg = match std::fs::write(file, body) {
+            Ok(_) => format!("Exported {}", file),
+            Err(e) => format!("Export failed: {}", e),
+        };
+    }
+
     fn add_client(&mut self) {
-        if self.new_client_name.is_empty() { return; }
+        if self.new_client_name.is_empty() || self.read_only() { return; }
         let conn = self.db.lock().unwrap();
         conn.execute("INSERT INTO clients (name, city, trust) VALUES (?1, ?2, ?3)", 
             params![self.new_client_name, self.new_client_city, 90]).ok();
@@ -357,7 +426,7 @@ impl PratyakshApp {
     }
 
     fn save_evidence(&mut self) {
-        if self.ev_client_name.is_empty() { return; }
+        if self.ev_client_name.is_empty() || self.read_only() { return; }
         let now = Local::now().to_rfc3339();
         let mut hasher = Sha256::new();
         hasher.update(format!("{}{}{}", self.ev_client_name, self.ev_note, now));
@@ -373,25 +442,24 @@ impl PratyakshApp {
 
     // --- CALCULATORS ---
     fn calc_mca(&mut self) {
-        let mut score = 90;
-        if self.mca_city == "Pune" { score -= 15; }
-        if self.mca_form == "AOC-4" { score -= 5; }
-        self.mca_result = format!("Probability: {}%", score);
+        let input = McaNoticeInput { city: self.mca_city.clone(), form: self.mca_form.clone() };
+        self.mca_result = run(input, |r| format!("Probability: {}%", r.probability_percent));
+        // Blank share capital means a company without share capital, charged the lowest slab
+        let capital = self.mca_capital.trim().parse::<i64>().ok().filter(|c| *c >= 0);
+        self.mca_curve = fees::project_additional_fee(self.mca_form.trim(), fees::normal_fee(capital), self.mca_due_date, Local::now().date_naive(), &self.circulars);
     }
     
     fn calc_board_risk(&mut self) {
-        self.board_result.clear();
-        if self.board_text.to_lowercase().contains("loan") { self.board_result.push("Sec 185 Risk".into()); }
-        else { self.board_result.push("No obvious risks".into()); }
+        let input = BoardRiskInput { resolution_text: self.board_text.clone() };
+        self.board_result = input.evaluate().flags;
     }
 
     fn calc_trust(&mut self) {
-        let g = self.trust_gst.parse::<f64>().unwrap_or(0.0);
-        let b = self.trust_bank.parse::<f64>().unwrap_or(0.0);
-        if b > 0.0 {
-            let score = 100.0 - ((g - b).abs() / b * 100.0);
-            self.trust_result = format!("{:.0}/100", score.clamp(0.0, 100.0));
-        }
+        let input = TrustScoreInput {
+            gst_turnover: self.trust_gst.parse::<f64>().unwrap_or(0.0),
+            bank_credits: self.trust_bank.parse::<f64>().unwrap_or(0.0),
+        };
+        self.trust_result = run(input, |r| format!("{}/100", r.score));
     }
 
     fn calc_regulator(&mut self) {
@@ -399,181 +467,242 @@ impl PratyakshApp {
     }
 
     fn calc_msme(&mut self) {
-        let amt = self.msme_amt.parse::<f64>().unwrap_or(0.0);
-        let days = (self.msme_pay_date - self.msme_inv_date).num_days();
-        if days > 45 {
-             let interest = amt * 0.18 * ((days - 15) as f64 / 365.0);
-             self.msme_result = format!("Non-Compliant. Liability: ₹{:.2}", interest);
-        } else {
-             self.msme_result = "Compliant".to_owned();
-        }
+        let input = MsmeInterestInput {
+            amount: self.msme_amt.parse::<f64>().unwrap_or(0.0),
+            invoice_date: self.msme_inv_date,
+            payment_date: self.msme_pay_date,
+        };
+        self.msme_result = run(input, |r| if r.compliant { "Compliant".to_owned() } else { format!("Non-Compliant. Liability: ₹{:.2}", r.interest) });
     }
 
     fn calc_gratuity(&mut self) {
-        let sal = self.grat_sal.parse::<f64>().unwrap_or(0.0);
-        let yrs = self.grat_yrs.parse::<f64>().unwrap_or(0.0);
-        self.grat_result = format!("Payable: ₹{:.0}", sal * (15.0/26.0) * yrs);
+        let input = GratuityInput {
+            monthly_salary: self.grat_sal.parse::<f64>().unwrap_or(0.0),
+            years_of_service: self.grat_yrs.parse::<f64>().unwrap_or(0.0),
+        };
+        self.grat_result = run(input, |r| format!("Payable: ₹{:.0}", r.gratuity));
     }
 
     fn calc_penalty(&mut self) {
-        let days = self.pen_days.parse::<i32>().unwrap_or(0);
-        let rate = if self.pen_filing_type == "AOC-4" { 100 } else { 200 };
-        self.pen_result = format!("Fee: ₹{}", days * rate);
+        let days = self.pen_days.parse::<i64>().unwrap_or(0);
+        let capital = self.pen_capital.parse::<i64>().ok();
+        let normal = fees::normal_fee(capital);
+        let additional = fees::additional_fee(self.pen_filing_type.trim(), normal, days);
+        self.pen_result = format!("Normal: ₹{} | Additional: ₹{} | Total: ₹{}", normal, additional, normal + additional);
     }
 
     fn calc_tax_regime(&mut self) {
-        let i = self.tax_inc.parse::<f64>().unwrap_or(0.0);
-        let d = self.tax_ded.parse::<f64>().unwrap_or(0.0);
-        let old_tax = (i - d - 50000.0) * 0.3;
-        let new_tax = (i - 75000.0) * 0.2;
-        self.tax_result = format!("Old: {:.0} | New: {:.0}", old_tax, new_tax);
+        let input = TaxRegimeInput {
+            income: self.tax_inc.parse::<f64>().unwrap_or(0.0),
+            deductions: self.tax_ded.parse::<f64>().unwrap_or(0.0),
+        };
+        self.tax_result = run(input, |r| format!("Old: {:.0} | New: {:.0}", r.old_regime_tax, r.new_regime_tax));
     }
     
     fn calc_crypto(&mut self) {
-        let p = self.cry_prof.parse::<f64>().unwrap_or(0.0);
-        self.cry_result = format!("Tax: {:.2}", p * 0.312);
+        let input = CryptoTaxInput { profit: self.cry_prof.parse::<f64>().unwrap_or(0.0) };
+        self.cry_result = run(input, |r| format!("Tax: {:.2}", r.tax));
     }
 
     fn calc_hra(&mut self) {
-        let b = self.hra_basic.parse::<f64>().unwrap_or(0.0);
-        let r = self.hra_rent.parse::<f64>().unwrap_or(0.0);
-        let ex = (r - (b * 0.1)).max(0.0);
-        self.hra_result = format!("Exempt: {:.0}", ex);
+        let input = HraInput {
+            basic_salary: self.hra_basic.parse::<f64>().unwrap_or(0.0),
+            rent_paid: self.hra_rent.parse::<f64>().unwrap_or(0.0),
+        };
+        self.hra_result = run(input, |r| format!("Exempt: {:.0}", r.exempt));
     }
 
     fn calc_pmla(&mut self) {
-        let amt = self.pmla_amt.parse::<f64>().unwrap_or(0.0);
-        self.pmla_result = if amt > 1000000.0 || (amt > 50000.0 && self.pmla_cash) { "HIGH RISK".into() } else { "Standard".into() };
+        let input = PmlaInput { amount: self.pmla_amt.parse::<f64>().unwrap_or(0.0), cash: self.pmla_cash };
+        self.pmla_result = run(input, |r| r.risk_level);
     }
 
     fn calc_shell(&mut self) {
-        let t = self.shell_to.parse::<f64>().unwrap_or(1.0);
-        let a = self.shell_ast.parse::<f64>().unwrap_or(1.0);
-        self.shell_result = if (t / a) < 0.05 { "High Risk (Shell Indicator)" } else { "Active".into() }.into();
+        let input = ShellRiskInput {
+            turnover: self.shell_to.parse::<f64>().unwrap_or(0.0),
+            total_assets: self.shell_ast.parse::<f64>().unwrap_or(0.0),
+        };
+        self.shell_result = run(input, |r| r.risk_level);
     }
     
+    // Payments dated after the FY end are self-assessment tax (Sec 140A)
     fn calc_advance_tax(&mut self) {
-        let tax = self.adv_tax.parse::<f64>().unwrap_or(0.0);
-        let paid = self.adv_paid.parse::<f64>().unwrap_or(0.0);
-        self.adv_result = format!("Due: ₹{:.2}", (tax * 0.15) - paid);
+        let (advance_tax, self_assessment_tax) = self.adv_payments.iter().partition(|p| p.date <= self.adv_fy_end);
+        let input = income_tax::InterestInput {
+            fy_end: self.adv_fy_end,
+            assessed_tax: self.adv_tax.parse::<i64>().unwrap_or(0),
+            advance_tax,
+            self_assessment_tax,
+            return_due_date: NaiveDate::from_ymd_opt(self.adv_fy_end.year(), 7, 31).unwrap(),
+            filing_date: self.adv_filing_date,
+            presumptive: self.adv_presumptive,
+        };
+        self.adv_result = run(input, |i| format!("234A: ₹{} | 234B: ₹{} | 234C: ₹{} | Total: ₹{}", i.interest_234a, i.interest_234b, i.interest_234c, i.total_interest));
     }
 
     fn calc_lease(&mut self) {
-        let pmt = self.lease_pmt.parse::<f64>().unwrap_or(0.0);
-        let r = self.lease_rate.parse::<f64>().unwrap_or(0.0) / 100.0;
-        let n = self.lease_years.parse::<f64>().unwrap_or(0.0);
-        let rou = pmt * ((1.0 - (1.0 + r).powf(-n)) / r);
-        self.lease_result = format!("ROU Asset: ₹{:.2}", rou);
+        let input = LeaseInput {
+            annual_payment: self.lease_pmt.parse::<f64>().unwrap_or(0.0),
+            rate_percent: self.lease_rate.parse::<f64>().unwrap_or(0.0),
+            years: self.lease_years.parse::<f64>().unwrap_or(0.0),
+        };
+        self.lease_result = run(input, |r| format!("ROU Asset: ₹{:.2}", r.right_of_use_asset));
     }
 
     fn calc_angel(&mut self) {
-        let issue = self.angel_issue.parse::<f64>().unwrap_or(0.0);
-        let fmv = self.angel_fmv.parse::<f64>().unwrap_or(0.0);
-        self.angel_result = if issue > fmv { format!("Taxable: ₹{}", issue - fmv) } else { "Safe".into() };
+        let input = AngelTaxInput {
+            issue_price: self.angel_issue.parse::<f64>().unwrap_or(0.0),
+            fair_market_value: self.angel_fmv.parse::<f64>().unwrap_or(0.0),
+        };
+        self.angel_result = run(input, |r| if r.taxable_excess > 0.0 { format!("Taxable: ₹{}", r.taxable_excess) } else { "Safe".into() });
     }
 
     fn calc_buyback(&mut self) {
-        let sh = self.buy_shares.parse::<f64>().unwrap_or(0.0);
-        let pr = self.buy_price.parse::<f64>().unwrap_or(0.0);
-        self.buy_result = format!("Tax: ₹{:.2}", sh * pr * 0.23296);
+        let input = BuybackTaxInput {
+            shares: self.buy_shares.parse::<f64>().unwrap_or(0.0),
+            price: self.buy_price.parse::<f64>().unwrap_or(0.0),
+        };
+        self.buy_result = run(input, |r| format!("Tax: ₹{:.2}", r.tax));
     }
 
     fn calc_esg(&mut self) {
-        let mc = self.esg_mcap.parse::<f64>().unwrap_or(0.0);
-        self.esg_result = if mc > 5000.0 { "Mandatory BRSR" } else { "Voluntary" }.into();
+        let input = EsgInput { market_cap_crore: self.esg_mcap.parse::<f64>().unwrap_or(0.0) };
+        self.esg_result = run(input, |r| if r.brsr_mandatory { "Mandatory BRSR" } else { "Voluntary" }.into());
     }
 
     fn calc_udin(&mut self) {
-        self.udin_result = if self.udin_val.len() == 18 { "Valid Format" } else { "Invalid" }.into();
+        let input = UdinInput { udin: self.udin_val.clone() };
+        self.udin_result = run(input, |r| if r.valid_format { "Valid Format" } else { "Invalid" }.into());
     }
 
     fn calc_audit(&mut self) {
-        let y = self.audit_years.parse::<i32>().unwrap_or(0);
-        self.audit_result = if y >= 10 { "Rotation Required".to_string() } else { format!("{} years left", 10 - y) };
+        let input = AuditRotationInput { years_served: self.audit_years.parse::<u32>().unwrap_or(0) };
+        self.audit_result = run(input, |r| if r.rotation_required { "Rotation Required".to_string() } else { format!("{} years left", r.years_left) });
     }
 
     fn calc_networth(&mut self) {
-        let c = self.nw_cap.parse::<f64>().unwrap_or(0.0);
-        let r = self.nw_res.parse::<f64>().unwrap_or(0.0);
-        self.nw_result = format!("Net Worth: ₹{}", c + r);
+        let input = NetWorthInput {
+            paid_up_capital: self.nw_cap.parse::<f64>().unwrap_or(0.0),
+            reserves: self.nw_res.parse::<f64>().unwrap_or(0.0),
+        };
+        self.nw_result = run(input, |r| format!("Net Worth: ₹{}", r.net_worth));
     }
 
     fn calc_export(&mut self) {
-        let days = (Local::now().date_naive() - self.exp_date).num_days();
-        self.exp_result = if days > 270 { "Overdue (FEMA Risk)" } else { "Compliant" }.into();
+        let input = ExportRealisationInput { shipment_date: self.exp_date, as_of: Local::now().date_naive() };
+        self.exp_result = run(input, |r| if r.overdue { "Overdue (FEMA Risk)" } else { "Compliant" }.into());
     }
 
     fn calc_partner(&mut self) {
-        let a = self.part_ast.parse::<f64>().unwrap_or(0.0);
-        let l = self.part_lia.parse::<f64>().unwrap_or(0.0);
-        self.part_result = format!("Net Asset: ₹{}", a - l);
+        let input = PartnerDissolutionInput {
+            assets: self.part_ast.parse::<f64>().unwrap_or(0.0),
+            liabilities: self.part_lia.parse::<f64>().unwrap_or(0.0),
+        };
+        self.part_result = run(input, |r| format!("Net Asset: ₹{}", r.net_assets));
     }
 
     fn calc_gst_int(&mut self) {
-        let tax = self.gst_tax.parse::<f64>().unwrap_or(0.0);
-        let days = self.gst_days.parse::<f64>().unwrap_or(0.0);
-        self.gst_calc_res = format!("Interest (18%): ₹{:.2}", tax * 0.18 * (days / 365.0));
+        let tax = self.gst_tax.parse::<i64>().unwrap_or(0);
+        let ground = if self.gst_excess_itc { gst::InterestGround::ExcessItc } else { gst::InterestGround::LatePayment };
+        let interest = gst::interest(tax, ground, self.gst_due_date, self.gst_paid_date);
+        let fee = run(gst::GstLateFeeQuery {
+            return_type: self.gst_return.trim().to_string(),
+            due_date: self.gst_due_date,
+            filing_date: self.gst_paid_date,
+            nil_return: self.gst_nil,
+            aggregate_turnover: self.gst_turnover.parse::<i64>().ok(),
+        }, |fee| format!("₹{}", fee.late_fee));
+        self.gst_calc_res = format!("Interest ({}%, {} days): ₹{} | Late Fee (Sec 47): {}", interest.rate_percent, interest.days, interest.interest, fee);
     }
     
     fn calc_dep(&mut self) {
-        let cost = self.dep_cost.parse::<f64>().unwrap_or(0.0);
-        let rate = self.dep_rate.parse::<f64>().unwrap_or(0.0) / 100.0;
-        self.dep_res = format!("WDV: ₹{:.2}", cost * (1.0 - rate));
+        let input = DepreciationInput {
+            cost: self.dep_cost.parse::<f64>().unwrap_or(0.0),
+            rate_percent: self.dep_rate.parse::<f64>().unwrap_or(0.0),
+        };
+        self.dep_res = run(input, |r| format!("WDV: ₹{:.2}", r.written_down_value));
     }
 
     fn calc_cg(&mut self) {
-        let c = self.cg_cost.parse::<f64>().unwrap_or(0.0);
-        let i1 = self.cg_idx1.parse::<f64>().unwrap_or(1.0);
-        let i2 = self.cg_idx2.parse::<f64>().unwrap_or(1.0);
-        self.cg_res = format!("Indexed Cost: ₹{:.2}", c * (i2 / i1));
+        let input = IndexedCostInput {
+            cost: self.cg_cost.parse::<f64>().unwrap_or(0.0),
+            cii_purchase: self.cg_idx1.parse::<f64>().unwrap_or(0.0),
+            cii_sale: self.cg_idx2.parse::<f64>().unwrap_or(0.0),
+        };
+        self.cg_result = run(input, |r| format!("Indexed Cost: ₹{:.2}", r.indexed_cost));
     }
 
     fn calc_llp(&mut self) {
-        let c = self.llp_contrib.parse::<f64>().unwrap_or(0.0);
-        let fee = if c < 100000.0 { 50.0 } else { 100.0 };
-        self.llp_res = format!("Filing Fee: ₹{}", fee);
+        let c = self.llp_contrib.parse::<i64>().unwrap_or(0);
+        self.llp_result = format!("Filing Fee: ₹{} (+ ₹{}/day if late)", llp::normal_fee(Some(c)), llp::LLP_ADDITIONAL_FEE_PER_DAY);
     }
 
     fn calc_emi(&mut self) {
-        let p = self.emi_p.parse::<f64>().unwrap_or(0.0);
-        let r = self.emi_r.parse::<f64>().unwrap_or(0.0) / 1200.0;
-        let n = self.emi_n.parse::<f64>().unwrap_or(0.0) * 12.0;
-        let emi = p * r * (1.0 + r).powf(n) / ((1.0 + r).powf(n) - 1.0);
-        self.emi_res = format!("Monthly EMI: ₹{:.2}", emi);
+        let input = EmiInput {
+            principal: self.emi_p.parse::<f64>().unwrap_or(0.0),
+            annual_rate_percent: self.emi_r.parse::<f64>().unwrap_or(0.0),
+            years: self.emi_n.parse::<f64>().unwrap_or(0.0),
+        };
+        self.emi_result = run(input, |r| format!("Monthly EMI: ₹{:.2}", r.emi));
     }
 
     fn calc_burn(&mut self) {
-        let c = self.burn_cash.parse::<f64>().unwrap_or(0.0);
-        let s = self.burn_spend.parse::<f64>().unwrap_or(1.0);
-        self.burn_res = format!("Runway: {:.1} months", c / s);
+        let input = BurnRateInput {
+            cash: self.burn_cash.parse::<f64>().unwrap_or(0.0),
+            monthly_spend: self.burn_spend.parse::<f64>().unwrap_or(0.0),
+        };
+        self.burn_result = run(input, |r| format!("Runway: {:.1} months", r.runway_months));
     }
 
     fn calc_si(&mut self) {
-        let p = self.si_p.parse::<f64>().unwrap_or(0.0);
-        let r = self.si_r.parse::<f64>().unwrap_or(0.0);
-        let t = self.si_t.parse::<f64>().unwrap_or(0.0);
-        self.si_res = format!("Interest: ₹{:.2}", (p * r * t) / 100.0);
+        let input = SimpleInterestInput {
+            principal: self.si_p.parse::<f64>().unwrap_or(0.0),
+            rate_percent: self.si_r.parse::<f64>().unwrap_or(0.0),
+            years: self.si_t.parse::<f64>().unwrap_or(0.0),
+        };
+        self.si_result = run(input, |r| format!("Interest: ₹{:.2}", r.interest));
     }
     
     fn calc_tds_int(&mut self) {
-        let a = self.tds_amt.parse::<f64>().unwrap_or(0.0);
-        let m = self.tds_months.parse::<f64>().unwrap_or(0.0);
-        self.tds_calc_res = format!("Interest (1.5%): ₹{:.2}", a * 0.015 * m);
+        let amount = self.tds_amt.parse::<i64>().unwrap_or(0);
+        let mut errors = Vec::new();
+        check_amount("amount", amount, &mut errors);
+        if let Some(e) = errors.first() {
+            self.tds_calc_res = format!("Check {}: {}", e.field(), e.reason());
+            return;
+        }
+        let entry = tds::TdsEntry {
+            reference: None,
+            amount,
+            payment_date: self.tds_paid_date,
+            deduction_date: self.tds_ded_date,
+            deposit_date: self.tds_dep_date,
+            return_filing_date: self.tds_filed_date,
+        };
+        let d = tds::compute_defaults(vec![entry]);
+        let (e, s) = (&d.entries[0], &d.statements[0]);
+        self.tds_calc_res = format!(
+            "Late deduction (1% x {}m): ₹{} | Late deposit (1.5% x {}m): ₹{} | 234E ({} days): ₹{}",
+            e.late_deduction_months, e.late_deduction_interest, e.late_deposit_months, e.late_deposit_interest, s.days_late, s.fee_234e
+        );
     }
     
     fn calc_cagr(&mut self) {
-        let s = self.cagr_start.parse::<f64>().unwrap_or(1.0);
-        let e = self.cagr_end.parse::<f64>().unwrap_or(1.0);
-        let y = self.cagr_yrs.parse::<f64>().unwrap_or(1.0);
-        let cagr = (e / s).powf(1.0 / y) - 1.0;
-        self.cagr_res = format!("CAGR: {:.2}%", cagr * 100.0);
+        let input = CagrInput {
+            start_value: self.cagr_start.parse::<f64>().unwrap_or(0.0),
+            end_value: self.cagr_end.parse::<f64>().unwrap_or(0.0),
+            years: self.cagr_yrs.parse::<f64>().unwrap_or(0.0),
+        };
+        self.cagr_result = run(input, |r| format!("CAGR: {:.2}%", r.cagr_percent));
     }
 
     fn calc_be(&mut self) {
-        let f = self.be_fixed.parse::<f64>().unwrap_or(0.0);
-        let p = self.be_price.parse::<f64>().unwrap_or(1.0);
-        let v = self.be_var.parse::<f64>().unwrap_or(0.0);
-        self.be_res = format!("Break Even: {:.0} units", f / (p - v));
+        let input = BreakEvenInput {
+            fixed_costs: self.be_fixed.parse::<f64>().unwrap_or(0.0),
+            price: self.be_price.parse::<f64>().unwrap_or(0.0),
+            variable_cost: self.be_var.parse::<f64>().unwrap_or(0.0),
+        };
+        self.be_result = run(input, |r| format!("Break Even: {:.0} units", r.units));
     }
 }
 
@@ -598,6 +727,32 @@ impl eframe::App for PratyakshApp {
             return;
         }
 
+        if let LicenseState::Missing(reason) = &self.license {
+            let reason = reason.clone();
+            egui::CentralPanel::default().show(ctx, |ui| {
+                ui.vertical_centered(|ui| {
+                    ui.add_space(50.0);
+                    ui.heading(egui::RichText::new("ACTIVATE PRATYAKSH").size(32.0).color(COLOR_ACCENT));
+                    ui.add_space(10.0);
+                    ui.label(egui::RichText::new(reason).color(COLOR_MUTED));
+                    ui.add_space(20.0);
+                    ui.label("Enter this Device ID with your payment; your license will only open on this machine:");
+                    ui.horizontal(|ui| match &self.device_id {
+                        Some(id) => {
+                            ui.label(egui::RichText::new(id).monospace().size(18.0).strong());
+                            if ui.small_button("Copy").clicked() { ui.output_mut(|o| o.copied_text = id.clone()); }
+                        }
+                        None => { ui.label(egui::RichText::new("Unavailable on this computer").color(egui::Color32::RED)); }
+                    });
+                    ui.add_space(20.0);
+                    ui.label("Paste the license file you received after approval:");
+                    ui.add(egui::TextEdit::multiline(&mut self.license_input).desired_rows(6).desired_width(600.0).code_editor());
+                    if ui.button("Activate").clicked() { self.activate_license(); }
+                });
+            });
+            return;
+        }
+
         egui::SidePanel::left("nav").exact_width(240.0).show(ctx, |ui| {
             ui.add_space(20.0);
             ui.heading(egui::RichText::new("PRATYAKSH").size(24.0).strong());
@@ -649,6 +804,12 @@ impl eframe::App for PratyakshApp {
         });
 
         egui::CentralPanel::default().show(ctx, |ui| {
+            if let LicenseState::Expired(claims) = &self.license {
+                egui::Frame::none().fill(egui::Color32::from_rgb(120, 40, 40)).inner_margin(8.0).rounding(4.0).show(ui, |ui| {
+                    ui.set_width(ui.available_width());
+                    ui.label(egui::RichText::new(format!("License expired on {}. Read-only mode: renew your {} plan to save new data.", claims.expires_on, claims.plan)).color(COLOR_TEXT));
+                });
+            }
             ui.add_space(10.0);
             match self.current_page {
                 Page::Dashboard => {
@@ -698,14 +859,33 @@ impl eframe::App for PratyakshApp {
                     ui.horizontal(|ui| {
                          ui.label("Name:"); ui.text_edit_singleline(&mut self.new_client_name);
                          ui.label("City:"); ui.text_edit_singleline(&mut self.new_client_city);
-                         if ui.button("Add Client").clicked() { self.add_client(); }
+                         if ui.add_enabled(!self.read_only(), egui::Button::new("Add Client")).clicked() { self.add_client(); }
                     });
+                    ui.separator();
+                    ui.horizontal(|ui| {
+                        ui.label("FY End:"); ui.add(egui_extras::DatePickerButton::new(&mut self.ics_fy_end));
+                        if ui.button("Export All (.ics)").clicked() {
+                            let names = self.clients.iter().map(|c| c.name.clone()).collect();
+                            self.export_ics(names, "pratyaksh_deadlines.ics");
+                        }
+                    });
+                    let mut export = None;
+                    for c in &self.clients {
+                        ui.horizontal(|ui| {
+                            ui.label(format!("{} ({})", c.name, c.city));
+                            if ui.small_button("Export .ics").clicked() { export = Some(c.name.clone()); }
+                        });
+                    }
+                    if let Some(name) = export {
+                        let file = format!("{}_deadlines.ics", name.replace(|c: char| !c.is_ascii_alphanumeric(), "_"));
+                        self.export_ics(vec![name], &file);
+                    }
                 },
                 Page::EvidenceLocker => {
                     ui.heading("Evidence Locker");
                     ui.text_edit_singleline(&mut self.ev_client_name);
                     ui.text_edit_singleline(&mut self.ev_note);
-                    if ui.button("Lock Evidence").clicked() { self.save_evidence(); }
+                    if ui.add_enabled(!self.read_only(), egui::Button::new("Lock Evidence")).clicked() { self.save_evidence(); }
                 },
                 Page::SmartTools => {
                     ui.heading("Smart Tools Library");
@@ -779,13 +959,19 @@ impl eframe::App for PratyakshApp {
                                 ui.heading("Penalty");
                                 ui.horizontal(|ui| { ui.label("Days Delayed:"); ui.text_edit_singleline(&mut self.pen_days); });
                                 ui.horizontal(|ui| { ui.label("Form:"); ui.text_edit_singleline(&mut self.pen_filing_type); });
+                                ui.horizontal(|ui| { ui.label("Share Capital:"); ui.text_edit_singleline(&mut self.pen_capital); });
                                 if ui.button("Calc").clicked() { self.calc_penalty(); }
                                 ui.label(&self.pen_result);
                             },
                             ActiveTool::GstInterest => {
                                 ui.heading("GST Interest (Sec 50)");
+                                ui.horizontal(|ui| { ui.label("Return:"); ui.text_edit_singleline(&mut self.gst_return); });
                                 ui.horizontal(|ui| { ui.label("Tax Amt:"); ui.text_edit_singleline(&mut self.gst_tax); });
-                                ui.horizontal(|ui| { ui.label("Days Late:"); ui.text_edit_singleline(&mut self.gst_days); });
+                                ui.horizontal(|ui| { ui.label("Turnover:"); ui.text_edit_singleline(&mut self.gst_turnover); });
+                                ui.horizontal(|ui| { ui.label("Due Date:"); ui.add(egui_extras::DatePickerButton::new(&mut self.gst_due_date)); });
+                                ui.horizontal(|ui| { ui.label("Paid On:"); ui.add(egui_extras::DatePickerButton::new(&mut self.gst_paid_date)); });
+                                ui.checkbox(&mut self.gst_nil, "Nil return");
+                                ui.checkbox(&mut self.gst_excess_itc, "Excess ITC (24%)");
                                 if ui.button("Calc").clicked() { self.calc_gst_int(); }
                                 ui.label(&self.gst_calc_res);
                             },
@@ -802,13 +988,13 @@ impl eframe::App for PratyakshApp {
                                 ui.horizontal(|ui| { ui.label("CII Year 1:"); ui.text_edit_singleline(&mut self.cg_idx1); });
                                 ui.horizontal(|ui| { ui.label("CII Year 2:"); ui.text_edit_singleline(&mut self.cg_idx2); });
                                 if ui.button("Calc").clicked() { self.calc_cg(); }
-                                ui.label(&self.cg_res);
+                                ui.label(&self.cg_result);
                             },
                             ActiveTool::LlpFee => {
                                 ui.heading("LLP Filing Fee");
                                 ui.horizontal(|ui| { ui.label("Contribution:"); ui.text_edit_singleline(&mut self.llp_contrib); });
                                 if ui.button("Calc").clicked() { self.calc_llp(); }
-                                ui.label(&self.llp_res);
+                                ui.label(&self.llp_result);
                             },
                             ActiveTool::EmiCalc => {
                                 ui.heading("Loan EMI");
@@ -816,14 +1002,14 @@ impl eframe::App for PratyakshApp {
                                 ui.horizontal(|ui| { ui.label("R%:"); ui.text_edit_singleline(&mut self.emi_r); });
                                 ui.horizontal(|ui| { ui.label("Yrs:"); ui.text_edit_singleline(&mut self.emi_n); });
                                 if ui.button("Calc").clicked() { self.calc_emi(); }
-                                ui.label(&self.emi_res);
+                                ui.label(&self.emi_result);
                             },
                             ActiveTool::BurnRate => {
                                 ui.heading("Startup Runway");
                                 ui.horizontal(|ui| { ui.label("Cash:"); ui.text_edit_singleline(&mut self.burn_cash); });
                                 ui.horizontal(|ui| { ui.label("Monthly Spend:"); ui.text_edit_singleline(&mut self.burn_spend); });
                                 if ui.button("Calc").clicked() { self.calc_burn(); }
-                                ui.label(&self.burn_res);
+                                ui.label(&self.burn_result);
                             },
                             ActiveTool::SimpleInt => {
                                 ui.heading("Simple Interest");
@@ -831,12 +1017,15 @@ impl eframe::App for PratyakshApp {
                                 ui.horizontal(|ui| { ui.label("R%:"); ui.text_edit_singleline(&mut self.si_r); });
                                 ui.horizontal(|ui| { ui.label("T:"); ui.text_edit_singleline(&mut self.si_t); });
                                 if ui.button("Calc").clicked() { self.calc_si(); }
-                                ui.label(&self.si_res);
+                                ui.label(&self.si_result);
                             },
                             ActiveTool::TdsInterest => {
                                 ui.heading("TDS Interest");
                                 ui.horizontal(|ui| { ui.label("TDS Amt:"); ui.text_edit_singleline(&mut self.tds_amt); });
-                                ui.horizontal(|ui| { ui.label("Months Late:"); ui.text_edit_singleline(&mut self.tds_months); });
+                                ui.horizontal(|ui| { ui.label("Paid/Credited:"); ui.add(egui_extras::DatePickerButton::new(&mut self.tds_paid_date)); });
+                                ui.horizontal(|ui| { ui.label("Deducted:"); ui.add(egui_extras::DatePickerButton::new(&mut self.tds_ded_date)); });
+                                ui.horizontal(|ui| { ui.label("Deposited:"); ui.add(egui_extras::DatePickerButton::new(&mut self.tds_dep_date)); });
+                                ui.horizontal(|ui| { ui.label("Return Filed:"); ui.add(egui_extras::DatePickerButton::new(&mut self.tds_filed_date)); });
                                 if ui.button("Calc").clicked() { self.calc_tds_int(); }
                                 ui.label(&self.tds_calc_res);
                             },
@@ -846,7 +1035,7 @@ impl eframe::App for PratyakshApp {
                                 ui.horizontal(|ui| { ui.label("End Val:"); ui.text_edit_singleline(&mut self.cagr_end); });
                                 ui.horizontal(|ui| { ui.label("Years:"); ui.text_edit_singleline(&mut self.cagr_yrs); });
                                 if ui.button("Calc").clicked() { self.calc_cagr(); }
-                                ui.label(&self.cagr_res);
+                                ui.label(&self.cagr_result);
                             },
                             ActiveTool::BreakEven => {
                                 ui.heading("Break Even Analysis");
@@ -854,7 +1043,7 @@ impl eframe::App for PratyakshApp {
                                 ui.horizontal(|ui| { ui.label("Unit Price:"); ui.text_edit_singleline(&mut self.be_price); });
                                 ui.horizontal(|ui| { ui.label("Var Cost:"); ui.text_edit_singleline(&mut self.be_var); });
                                 if ui.button("Calc").clicked() { self.calc_be(); }
-                                ui.label(&self.be_res);
+                                ui.label(&self.be_result);
                             },
                             ActiveTool::CryptoTax => {
                                 ui.heading("VDA / Crypto Tax");
@@ -866,8 +1055,15 @@ impl eframe::App for PratyakshApp {
                                 ui.heading("MCA Predictor");
                                 ui.horizontal(|ui| { ui.label("City:"); ui.text_edit_singleline(&mut self.mca_city); });
                                 ui.horizontal(|ui| { ui.label("Form:"); ui.text_edit_singleline(&mut self.mca_form); });
+                                ui.horizontal(|ui| { ui.label("Share Capital (₹):"); ui.text_edit_singleline(&mut self.mca_capital); });
+                                ui.horizontal(|ui| { ui.label("Due Date:"); ui.add(egui_extras::DatePickerButton::new(&mut self.mca_due_date)); });
                                 if ui.button("Predict").clicked() { self.calc_mca(); }
                                 ui.label(&self.mca_result);
+                                if !self.mca_curve.is_empty() {
+                                    ui.add_space(10.0);
+                                    ui.label(egui::RichText::new("Additional fee if filed on:").color(COLOR_MUTED));
+                                    fee_curve(ui, &self.mca_curve);
+                                }
                             },
                             ActiveTool::BoardRisk => {
                                 ui.heading("Board Risk");
@@ -891,14 +1087,35 @@ impl eframe::App for PratyakshApp {
                             },
                             ActiveTool::AdvanceTax => {
                                 ui.heading("Advance Tax");
-                                ui.horizontal(|ui| { ui.label("Est Tax:"); ui.text_edit_singleline(&mut self.adv_tax); });
-                                ui.horizontal(|ui| { ui.label("Paid:"); ui.text_edit_singleline(&mut self.adv_paid); });
+                                ui.horizontal(|ui| { ui.label("Assessed Tax:"); ui.text_edit_singleline(&mut self.adv_tax); });
+                                ui.horizontal(|ui| { ui.label("FY End:"); ui.add(egui_extras::DatePickerButton::new(&mut self.adv_fy_end)); });
+                                ui.horizontal(|ui| { ui.label("ITR Filed On:"); ui.add(egui_extras::DatePickerButton::new(&mut self.adv_filing_date)); });
+                                ui.checkbox(&mut self.adv_presumptive, "Presumptive (44AD/44ADA)");
+                                ui.horizontal(|ui| {
+                                    ui.label("Paid:"); ui.text_edit_singleline(&mut self.adv_paid);
+                                    ui.add(egui_extras::DatePickerButton::new(&mut self.adv_pay_date));
+                                    if ui.button("Add").clicked() {
+                                        if let Ok(amount) = self.adv_paid.parse::<i64>() {
+                                            self.adv_payments.push(income_tax::TaxPayment { date: self.adv_pay_date, amount });
+                                            self.adv_paid.clear();
+                                        }
+                                    }
+                                });
+                                let mut remove = None;
+                                for (idx, p) in self.adv_payments.iter().enumerate() {
+                                    ui.horizontal(|ui| {
+                                        ui.label(format!("{}  ₹{}", p.date, p.amount));
+                                        if ui.small_button("x").clicked() { remove = Some(idx); }
+                                    });
+                                }
+                                if let Some(idx) = remove { self.adv_payments.remove(idx); }
                                 if ui.button("Calculate").clicked() { self.calc_advance_tax(); }
                                 ui.label(&self.adv_result);
                             },
                             ActiveTool::LeaseCalc => {
                                 ui.heading("Lease Liability");
                                 ui.horizontal(|ui| { ui.label("Pmt:"); ui.text_edit_singleline(&mut self.lease_pmt); });
+                                ui.horizontal(|ui| { ui.label("Rate %:"); ui.text_edit_singleline(&mut self.lease_rate); });
                                 ui.horizontal(|ui| { ui.label("Yrs:"); ui.text_edit_singleline(&mut self.lease_years); });
                                 if ui.button("Calculate").clicked() { self.calc_lease(); }
                                 ui.label(&self.lease_result);
@@ -979,7 +1196,20 @@ impl eframe::App for PratyakshApp {
                         }
                     });
                 },
-                Page::Settings => { ui.heading("Settings"); ui.label("V11.0 Enterprise Local Build"); },
+                Page::Settings => {
+                    ui.heading("Settings");
+                    ui.label("V11.0 Enterprise Local Build");
+                    ui.separator();
+                    ui.label(format!("Device ID: {}", self.device_id.as_deref().unwrap_or("unavailable")));
+                    if let LicenseState::Active(claims) | LicenseState::Expired(claims) = &self.license {
+                        ui.label(format!("Licensed to: {}", claims.email));
+                        ui.label(format!("Plan: {}", claims.plan));
+                        ui.label(format!("Valid until: {}", claims.expires_on));
+                    }
+                    ui.label("Install a renewed license:");
+                    ui.add(egui::TextEdit::multiline(&mut self.license_input).desired_rows(4).code_editor());
+                    if ui.button("Activate").clicked() { self.activate_license(); }
+                },
                 _ => { ui.label("Page content loading..."); }
             }
         });
@@ -987,6 +1217,53 @@ impl eframe::App for PratyakshApp {
 }
 
 // --- HELPERS ---
+fn check_license(raw: &str, device_id: &str) -> LicenseState {
+    let Some(key) = LICENSE_PUBLIC_KEY.and_then(license::parse_public_key) else {
+        return LicenseState::Missing("This build has no license public key; set PRATYAKSH_LICENSE_PUBLIC_KEY when building.".into());
+    };
+    match license::verify(raw, &key, device_id) {
+        Ok(claims) => match claims.status(Local::now().date_naive()) {
+            LicenseStatus::Active => LicenseState::Active(claims),
+            LicenseStatus::Expired => LicenseState::Expired(claims),
+        },
+        Err(e) => LicenseState::Missing(format!("License rejected: {}.", e)),
+    }
+}
+
+// license.json beside the executable, so shortcuts with another start-in directory still find it
+fn license_path() -> std::io::Result<std::path::PathBuf> {
+    let exe = std::env::current_exe()?;
+    let dir = exe.parent().ok_or_else(|| std::io::Error::new(std::io::ErrorKind::NotFound, "executable has no parent directory"))?;
+    Ok(dir.join(LICENSE_FILE))
+}
+
+// Stable per-machine id shown as XXXX-XXXX-XXXX-XXXX: a hash of the OS machine GUID, not the GUID itself.
+// None when neither source exists: hashing "" would give every such machine the same id.
+fn device_id() -> Option<String> {
+    let source = machine_guid()
+        .or_else(|| std::env::var("COMPUTERNAME").ok())
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())?;
+    let digest = hex::encode_upper(&Sha256::digest(source.as_bytes())[..8]);
+    Some(digest.as_bytes().chunks(4).map(|c| String::from_utf8_lossy(c).into_owned()).collect::<Vec<_>>().join("-"))
+}
+
+#[cfg(windows)]
+fn machine_guid() -> Option<String> {
+    use std::os::windows::process::CommandExt;
+    const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+    let out = std::process::Command::new("reg")
+        .args(["query", r"HKLM\SOFTWARE\Microsoft\Cryptography", "/v", "MachineGuid"])
+        .creation_flags(CREATE_NO_WINDOW)
+        .output().ok()?;
+    String::from_utf8_lossy(&out.stdout).split_whitespace().last().map(str::to_string)
+}
+
+#[cfg(not(windows))]
+fn machine_guid() -> Option<String> {
+    std::fs::read_to_string("/etc/machine-id").ok().map(|s| s.trim().to_string())
+}
+
 fn nav_btn(ui: &mut egui::Ui, text: &str, icon: &'static [u8], active: bool) -> egui::Response {
     let bg = if active { COLOR_ACCENT } else { egui::Color32::TRANSPARENT };
     let fg = if active { egui::Color32::BLACK } else { COLOR_TEXT };
@@ -1000,11 +1277,34 @@ fn nav_btn(ui: &mut egui::Ui, text: &str, icon: &'static [u8], active: bool) ->
     }).response.interact(egui::Sense::click())
 }
 
+// Shows the first invalid input instead of a result computed from it
+fn run<C: Calculator>(input: C, show: impl FnOnce(C::Output) -> String) -> String {
+    match input.check().first() {
+        Some(e) => format!("Check {}: {}", e.field(), e.reason()),
+        None => show(input.evaluate()),
+    }
+}
+
 fn tool_btn(ui: &mut egui::Ui, text: &str, active: bool) -> egui::Response {
     let text_color = if active { COLOR_ACCENT } else { COLOR_TEXT };
     ui.add(egui::Button::new(egui::RichText::new(text).color(text_color)).frame(false))
 }
 
+fn fee_curve(ui: &mut egui::Ui, curve: &[fees::FeeProjection]) {
+    let max_fee = curve.iter().map(|p| p.additional_fee).max().unwrap_or(0).max(1) as f32;
+    egui::Grid::new("fee_curve").spacing([20.0, 6.0]).show(ui, |ui| {
+        for point in curve {
+            ui.label(point.date.format("%d %b %Y").to_string());
+            ui.label(format!("{} days late", point.days_late));
+            let (rect, _) = ui.allocate_exact_size(egui::vec2(200.0, 12.0), egui::Sense::hover());
+            let width = rect.width() * (point.additional_fee as f32 / max_fee);
+            ui.painter().rect_filled(egui::Rect::from_min_size(rect.min, egui::vec2(width, rect.height())), 2.0, COLOR_ACCENT);
+            ui.label(format!("₹{}", point.additional_fee));
+            ui.end_row();
+        }
+    });
+}
+
 fn stat_card(ui: &mut egui::Ui, label: &str, val: &str) {
     egui::Frame::group(ui.style()).fill(COLOR_PANEL).inner_margin(15.0).show(ui, |ui| {
         ui.set_width(ui.available_width());